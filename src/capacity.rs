@@ -0,0 +1,136 @@
+//! Plans `QRCode`s without building them: smallest version, capacity of a version per mode
+//! and bits used by an input, referring to Table 7 of the spec.
+//!
+//! # Example
+//! ```rust
+//! use fast_qr::capacity::{self, Capacity};
+//! use fast_qr::{Mode, Version, ECL};
+//!
+//! let version = capacity::smallest_version(b"https://example.com/", ECL::M).unwrap();
+//! assert!(matches!(version, Version::V02));
+//!
+//! let v10_m = Capacity::new(Version::V10, ECL::M);
+//! assert_eq!(v10_m.size, 57);
+//! assert_eq!(v10_m.numeric, 513);
+//! assert_eq!(v10_m.alphanumeric, 311);
+//! assert_eq!(v10_m.bytes, 213);
+//! assert_eq!(v10_m.kanji, 131);
+//!
+//! assert_eq!(capacity::bits(b"HELLO WORLD", Mode::Alphanumeric, Version::V01), Some(74));
+//! assert_eq!(capacity::bits(b"hello world", Mode::Alphanumeric, Version::V01), None);
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use crate::encode::{self, Mode};
+use crate::{hardcode, Version, ECL};
+use alloc::vec::Vec;
+
+/// Capacity of a `QRCode` for a version and an `ECL`, each mode holding the data alone
+#[derive(Clone, Copy, Debug)]
+pub struct Capacity {
+    /// Version of the `QRCode`
+    pub version: Version,
+    /// Error correction level of the `QRCode`
+    pub ecl: ECL,
+    /// Number of modules per side, without quiet zone
+    pub size: usize,
+    /// Number of data codewords, the others are error correction codewords
+    pub data_codewords: usize,
+    /// Max number of digits
+    pub numeric: usize,
+    /// Max number of alphanumeric characters
+    pub alphanumeric: usize,
+    /// Max number of bytes
+    pub bytes: usize,
+    /// Max number of Kanji characters, each taking 2 bytes of input
+    pub kanji: usize,
+}
+
+impl Capacity {
+    /// Computes the capacity of `version` at `ecl`
+    #[must_use]
+    pub const fn new(version: Version, ecl: ECL) -> Self {
+        Capacity {
+            version,
+            ecl,
+            size: version.size(),
+            data_codewords: hardcode::data_codewords(version, ecl),
+            numeric: max_chars(version, ecl, Mode::Numeric),
+            alphanumeric: max_chars(version, ecl, Mode::Alphanumeric),
+            bytes: max_chars(version, ecl, Mode::Byte),
+            kanji: max_chars(version, ecl, Mode::Kanji),
+        }
+    }
+
+    /// Returns the max number of characters of `mode`, see [`max_chars`]
+    #[must_use]
+    pub const fn chars(&self, mode: Mode) -> usize {
+        match mode {
+            Mode::Numeric => self.numeric,
+            Mode::Alphanumeric => self.alphanumeric,
+            Mode::Byte => self.bytes,
+            Mode::Kanji => self.kanji,
+        }
+    }
+}
+
+/// Returns the capacity of every version at `ecl`, from V01 to V40
+///
+/// # Example
+/// ```rust
+/// use fast_qr::capacity;
+/// use fast_qr::ECL;
+///
+/// let table = capacity::table(ECL::L);
+/// assert_eq!(table.len(), 40);
+/// assert_eq!(table[39].size, 177);
+/// assert_eq!(table[39].bytes, 2953);
+/// ```
+#[must_use]
+pub fn table(ecl: ECL) -> Vec<Capacity> {
+    (Version::V01.size()..=Version::V40.size())
+        .step_by(4)
+        .map(|n| Capacity::new(Version::from_n(n), ecl))
+        .collect()
+}
+
+/// Returns the max number of characters of `mode` fitting alone in `version` at `ecl`:
+/// digits, alphanumeric characters, bytes or Kanji characters
+#[must_use]
+pub const fn max_chars(version: Version, ecl: ECL, mode: Mode) -> usize {
+    let bits = hardcode::data_bits(version, ecl) - 4 - hardcode::cci_bits(version, mode);
+
+    match mode {
+        Mode::Numeric => bits / 10 * 3 + [0, 0, 0, 0, 1, 1, 1, 2, 2, 2][bits % 10],
+        Mode::Alphanumeric => bits / 11 * 2 + (bits % 11 >= 6) as usize,
+        Mode::Byte => bits / 8,
+        Mode::Kanji => bits / 13,
+    }
+}
+
+/// Returns the smallest version holding `input` at `ecl`, as chosen by
+/// [`QRBuilder::build`](crate::QRBuilder::build), or `None` if it is too large
+#[must_use]
+pub fn smallest_version(input: &[u8], ecl: ECL) -> Option<Version> {
+    encode::best_segments(input, ecl, &[]).map(|(version, _)| version)
+}
+
+/// Returns the number of bits `input` takes as a single segment of `mode` in `version`,
+/// mode indicator and character count indicator included, or `None` if `mode` can't encode it
+#[must_use]
+pub fn bits(input: &[u8], mode: Mode, version: Version) -> Option<usize> {
+    if encode::find_invalid(mode, input).is_some() {
+        return None;
+    }
+
+    Some(encode::Segment::Data(mode, input.to_vec()).bits(version))
+}
+
+/// Returns the number of bits `input` takes in `version` once split into the segments
+/// giving the shortest bitstream, as [`QRBuilder::build`](crate::QRBuilder::build) does
+#[must_use]
+pub fn optimal_bits(input: &[u8], version: Version) -> usize {
+    encode::segments_bits(&encode::optimal_segments(input, version, false), version)
+}
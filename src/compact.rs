@@ -3,6 +3,7 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+use alloc::string::String;
 use core::fmt::{Display, Formatter};
 
 use crate::Version;
@@ -30,7 +31,7 @@ use crate::Version;
 /// assert_eq!(b & KEEP_LAST[3], 0b010)
 /// ```
 #[rustfmt::skip]
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(target_pointer_width = "64")]
 pub const KEEP_LAST: [usize; 65] = [
     0, 1, 3, 7, 15, 31, 63, 127, 255, 511, 1023, 2047, 4095, 8191, 16383,
     32767, 65535, 131_071, 262_143, 524_287, 1_048_575, 2_097_151, 4_194_303, 8_388_607,
@@ -49,7 +50,7 @@ pub const KEEP_LAST: [usize; 65] = [
 /// Values to keep last X bits of a u8
 /// `KEEP_LAST[i]` equates `(1 << i) - 1`
 #[rustfmt::skip]
-#[cfg(target_arch = "wasm32")]
+#[cfg(not(target_pointer_width = "64"))]
 pub const KEEP_LAST: [usize; 33] = [
     0, 1, 3, 7, 15, 31, 63, 127, 255, 511, 1_023, 2_047, 4_095, 8_191, 16_383,
     32_767, 65_535, 131_071, 262_143, 524_287, 1_048_575, 2_097_151, 4_194_303, 8_388_607,
@@ -57,10 +58,16 @@ pub const KEEP_LAST: [usize; 33] = [
     2_147_483_647, 4_294_967_295,
 ];
 
-/// `CompactQR` is a struct that contains a `Vec<u8>` to store boolean values as bits.
+/// Number of bytes a `CompactQR` holds, the codewords of a V40 `QRCode`
+pub const MAX_BYTES: usize = 3706;
+
+/// `CompactQR` is a struct that contains a fixed size `[u8]` to store boolean values as bits.
+/// Being on the stack, encoding doesn't allocate.
 pub struct CompactQR {
     pub len: usize,
-    pub data: Vec<u8>,
+    /// Number of bytes `fill` pads up to
+    capacity: usize,
+    pub data: [u8; MAX_BYTES],
 }
 
 /// Returns a string visualization of the `CompactQR`. \
@@ -69,7 +76,7 @@ impl Display for CompactQR {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let mut res = String::with_capacity(self.len);
 
-        for i in 0..(self.data.capacity() / 8) {
+        for i in 0..(self.len + 7) / 8 {
             let nb = self.data[i];
             for j in 0..8 {
                 if i * 8 + j >= self.len {
@@ -93,41 +100,37 @@ impl CompactQR {
     pub const fn new() -> Self {
         CompactQR {
             len: 0,
-            data: Vec::new(),
+            capacity: 0,
+            data: [0; MAX_BYTES],
         }
     }
 
-    pub fn from_version(version: Version) -> Self {
-        let len = version.max_bytes();
-        let data = vec![0; len * 8];
-
-        CompactQR { len: 0, data }
-    }
-
-    /// Instantiates a new `CompactQR`, with a given length, expects the length to be a multiple of 8.
-    #[allow(dead_code)]
-    #[cfg(test)]
-    pub fn with_len(data_length: usize) -> Self {
-        let length = data_length / 8 + usize::from(data_length % 8 != 0);
+    /// Instantiates a new `CompactQR`, filled up to the codewords of `version`
+    pub const fn from_version(version: Version) -> Self {
         CompactQR {
-            len: 0,
-            data: vec![0; length],
+            capacity: version.max_bytes(),
+            ..CompactQR::new()
         }
     }
 
-    /// Increase the length of data to specified length.
-    pub fn increase_len(&mut self, data_length: usize) {
-        if data_length / 8 >= self.data.len() {
-            self.data.resize(data_length / 8 + 1, 0);
+    /// Instantiates a new `CompactQR`, with a given length, expects the length to be a multiple of 8.
+    pub const fn with_len(data_length: usize) -> Self {
+        CompactQR {
+            capacity: data_length / 8 + (data_length % 8 != 0) as usize,
+            ..CompactQR::new()
         }
     }
 
-    /// Instantiates a new `CompactQR` from an already created array
+    /// Instantiates a new `CompactQR` from the first `len` bits of an already created array
     pub fn from_array(data: &[u8], len: usize) -> Self {
-        CompactQR {
+        let bytes = (len + 7) / 8;
+        let mut compact = CompactQR {
             len,
-            data: data.to_vec(),
-        }
+            capacity: bytes,
+            ..CompactQR::new()
+        };
+        compact.data[..bytes].copy_from_slice(&data[..bytes]);
+        compact
     }
 
     /// Returns `len`, length is the current number of bits / boolean values stored in the array.
@@ -136,16 +139,14 @@ impl CompactQR {
     }
 
     /// Returns `data`, the array of bits.
-    pub const fn get_data(&self) -> &Vec<u8> {
+    pub const fn get_data(&self) -> &[u8] {
         &self.data
     }
 
-    /// Pushes eight values in the `CompactQR`, if the array is not big enough, it will be resized.
+    /// Pushes eight values in the `CompactQR`.
     #[inline(always)]
     #[allow(dead_code)]
     pub fn push_u8(&mut self, bits: u8) {
-        self.increase_len(self.len + 8);
-
         let right = self.len % 8;
         let first_idx = self.len / 8;
 
@@ -160,23 +161,17 @@ impl CompactQR {
         self.len += 8;
     }
 
-    /// Pushes the u8 array in the `CompactQR`, using the `push_u8` function. \
-    /// If the array is not big enough, it will be resized.
+    /// Pushes the u8 array in the `CompactQR`, using the `push_u8` function.
     #[inline(always)]
     pub fn push_u8_slice(&mut self, slice: &[u8]) {
-        self.increase_len(self.len + 8 * slice.len());
-
         for &u in slice {
             self.push_u8(u);
         }
     }
 
-    /// Pushes `len` values to the `CompactQR`. \
-    /// If the array is not big enough, it will be resized.
+    /// Pushes `len` values to the `CompactQR`.
     #[inline(always)]
     pub fn push_bits(&mut self, bits: usize, len: usize) {
-        self.increase_len(self.len + len);
-
         // Caps to max usize bits
         let bits = bits & KEEP_LAST[len];
 
@@ -216,7 +211,7 @@ impl CompactQR {
         #[cfg(debug_assertions)]
         assert_eq!(self.len % 8, 0);
 
-        for (i, _) in (self.len..self.data.len()).step_by(8).enumerate() {
+        for (i, _) in (self.len..self.capacity * 8).step_by(8).enumerate() {
             let bits = PAD_BYTES[i % 2];
             self.push_u8(bits);
         }
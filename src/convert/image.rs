@@ -25,10 +25,12 @@
 use std::fmt::Formatter;
 use std::io;
 
-use crate::QRCode;
+use crate::qr::QRCodeError;
+use crate::{QRBuilder, QRCode};
 
+use super::svg::{OcclusionReport, SvgBuilder, SvgError};
 use super::Color;
-use super::{svg::SvgBuilder, Builder, Shape};
+use super::{Builder, Shape};
 
 use resvg::tiny_skia::{self, Pixmap};
 use resvg::usvg;
@@ -51,6 +53,10 @@ pub enum ImageError {
     ImageError(String),
     /// Error while convert to bytes
     EncodingError(String),
+    /// The embedded image destroys more codewords than a block can correct
+    ImageOcclusion(OcclusionReport),
+    /// Error while building the [`QRCode`] in [`ImageBuilder::raise_ecl`]
+    QRCodeError(QRCodeError),
 }
 
 impl std::error::Error for ImageError {}
@@ -61,6 +67,12 @@ impl std::fmt::Display for ImageError {
             ImageError::IoError(io_err) => f.write_str(io_err.to_string().as_str()),
             ImageError::ImageError(error) => f.write_str(error.as_str()),
             ImageError::EncodingError(error) => f.write_str(error.as_str()),
+            ImageError::ImageOcclusion(report) => write!(
+                f,
+                "Image covers more codewords than ECL {} can correct",
+                report.ecl
+            ),
+            ImageError::QRCodeError(error) => f.write_str(error.to_string().as_str()),
         }
     }
 }
@@ -146,6 +158,51 @@ impl ImageBuilder {
         self
     }
 
+    /// Embeds the Swiss cross of the QR-bill, see [`SvgBuilder::swiss_cross`]
+    pub fn swiss_cross(&mut self) -> &mut Self {
+        self.svg_builder.swiss_cross();
+        self
+    }
+
+    /// Erases the modules under the image in its background shape, see
+    /// [`SvgBuilder::shaped_erase`]
+    pub fn shaped_erase(&mut self, shaped_erase: bool) -> &mut Self {
+        self.svg_builder.shaped_erase(shaped_erase);
+        self
+    }
+
+    /// Counts the codewords covered by the embedded image, see [`SvgBuilder::verify_occlusion`]
+    #[must_use]
+    pub fn verify_occlusion(&self, qr: &QRCode) -> Option<OcclusionReport> {
+        self.svg_builder.verify_occlusion(qr)
+    }
+
+    /// Returns `ImageError::ImageOcclusion` if the embedded image makes `qr` unscannable
+    fn check_occlusion(&self, qr: &QRCode) -> Result<(), ImageError> {
+        match self.verify_occlusion(qr) {
+            Some(report) if !report.is_recoverable() => Err(ImageError::ImageOcclusion(report)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Builds the [`QRCode`] with the lowest `ECL` whose codewords covered by the embedded
+    /// image can all be corrected, see [`SvgBuilder::raise_ecl`]
+    ///
+    /// # Errors
+    /// - `ImageError::QRCodeError` if `builder` fails to build
+    /// - `ImageError::ImageOcclusion` with the report for the highest `ECL` if none is enough
+    pub fn raise_ecl(&self, builder: &QRBuilder) -> Result<QRCode, ImageError> {
+        self.svg_builder
+            .raise_ecl(builder)
+            .map_err(|err| match err {
+                SvgError::QRCodeError(error) => ImageError::QRCodeError(error),
+                SvgError::ImageOcclusion(report) => ImageError::ImageOcclusion(report),
+                SvgError::SvgError(error) => ImageError::ImageError(error),
+                #[cfg(not(feature = "wasm-bindgen"))]
+                SvgError::IoError(error) => ImageError::IoError(error),
+            })
+    }
+
     // From https://github.com/RazrFalcon/resvg/blob/374a25f/crates/resvg/tests/integration/main.rs
     /// Return a pixmap containing the svg for a QRCode
     pub fn to_pixmap(&self, qr: &QRCode) -> Pixmap {
@@ -181,6 +238,17 @@ impl ImageBuilder {
         pixmap
     }
 
+    /// Returns a pixmap containing the svg for a QRCode, refusing to embed an image covering
+    /// more codewords than a block can correct
+    ///
+    /// # Errors
+    /// - `ImageError::ImageOcclusion` if the `QRCode` would not scan, see
+    ///   [`SvgBuilder::verify_occlusion`]
+    pub fn try_to_pixmap(&self, qr: &QRCode) -> Result<Pixmap, ImageError> {
+        self.check_occlusion(qr)?;
+        Ok(self.to_pixmap(qr))
+    }
+
     /// Saves the image for a QRCode to a file
     pub fn to_file(&self, qr: &QRCode, file: &str) -> Result<(), ImageError> {
         use io::{Error, ErrorKind};
@@ -196,4 +264,26 @@ impl ImageBuilder {
         out.encode_png()
             .map_err(|err| ImageError::EncodingError(err.to_string()))
     }
+
+    /// Saves the image for a QRCode to a file, refusing to embed an image covering more
+    /// codewords than a block can correct
+    ///
+    /// # Errors
+    /// - `ImageError::ImageOcclusion` if the `QRCode` would not scan
+    /// - Same errors as [`ImageBuilder::to_file`]
+    pub fn try_to_file(&self, qr: &QRCode, file: &str) -> Result<(), ImageError> {
+        self.check_occlusion(qr)?;
+        self.to_file(qr, file)
+    }
+
+    /// Saves the image for a QRCode in a byte buffer, refusing to embed an image covering more
+    /// codewords than a block can correct
+    ///
+    /// # Errors
+    /// - `ImageError::ImageOcclusion` if the `QRCode` would not scan
+    /// - Same errors as [`ImageBuilder::to_bytes`]
+    pub fn try_to_bytes(&self, qr: &QRCode) -> Result<Vec<u8>, ImageError> {
+        self.check_occlusion(qr)?;
+        self.to_bytes(qr)
+    }
 }
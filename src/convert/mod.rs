@@ -54,6 +54,7 @@ pub enum Shape {
 
 /// Different possible Shapes to represent modules in a [`crate::QRCode`]
 #[cfg(not(feature = "wasm-bindgen"))]
+#[allow(unknown_lints, unpredictable_function_pointer_comparisons)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
 pub enum Shape {
     /// Square Shape
@@ -220,6 +221,11 @@ impl From<SvgError> for ConvertError {
     fn from(err: SvgError) -> Self {
         match err {
             SvgError::SvgError(svg_err) => Self::Svg(svg_err),
+            SvgError::ImageOcclusion(report) => Self::Svg(format!(
+                "Image covers more codewords than ECL {} can correct",
+                report.ecl
+            )),
+            SvgError::QRCodeError(qr_err) => Self::Svg(qr_err.to_string()),
             #[cfg(not(feature = "wasm-bindgen"))]
             SvgError::IoError(io_err) => Self::Io(io_err),
         }
@@ -234,6 +240,11 @@ impl From<ImageError> for ConvertError {
             ImageError::EncodingError(image_err) => Self::Image(image_err),
             ImageError::ImageError(image_err) => Self::Image(image_err),
             ImageError::IoError(io_err) => Self::Io(io_err),
+            ImageError::ImageOcclusion(report) => Self::Image(format!(
+                "Image covers more codewords than ECL {} can correct",
+                report.ecl
+            )),
+            ImageError::QRCodeError(qr_err) => Self::Image(qr_err.to_string()),
         }
     }
 }
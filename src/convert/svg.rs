@@ -21,7 +21,10 @@
 //! # }
 //! ```
 
-use crate::{QRCode, Version};
+use crate::module::ModuleType;
+use crate::placement::BiRange;
+use crate::qr::QRCodeError;
+use crate::{hardcode, QRBuilder, QRCode, Version, ECL};
 
 use super::{Builder, Color, ImageBackgroundShape, ModuleFunction, Shape};
 
@@ -42,18 +45,29 @@ pub struct SvgBuilder {
     dot_color: Color,
 
     // Image Embedding
-    /// Image to embed in the svg, can be a path or a base64 string
-    image: Option<String>,
+    /// Image to embed in the svg, a path, a base64 string or a vector overlay
+    image: Option<Embed>,
     /// Background color for the image, default is #FFFFFF
     image_background_color: Color,
     /// Background shape for the image, default is square
     image_background_shape: ImageBackgroundShape,
+    /// Erases the modules under the image in its background shape, default is a square
+    shaped_erase: bool,
     /// Size of the image, default is ~1/3 of the svg
     image_size: Option<(f64, f64)>,
     /// Position of the image, default is center
     image_position: Option<(f64, f64)>,
 }
 
+/// Content embedded in the center of the svg
+#[derive(Debug, Clone)]
+enum Embed {
+    /// Image, written as `<image href>`
+    Href(String),
+    /// Swiss cross of the QR-bill, 7x7 mm on a 46x46 mm QR Code
+    SwissCross,
+}
+
 #[derive(Debug)]
 /// Possible errors when converting to SVG
 pub enum SvgError {
@@ -62,6 +76,48 @@ pub enum SvgError {
     IoError(std::io::Error),
     /// Error while creating svg
     SvgError(String),
+    /// The embedded image destroys more codewords than a block can correct
+    ImageOcclusion(OcclusionReport),
+    /// Error while building the [`QRCode`] in [`SvgBuilder::raise_ecl`]
+    QRCodeError(QRCodeError),
+}
+
+/// Codewords of a Reed-Solomon block covered by the embedded image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockOcclusion {
+    /// Covered data codewords
+    pub data: usize,
+    /// Covered error correction codewords
+    pub ecc: usize,
+    /// Number of codewords the block can correct
+    pub capacity: usize,
+}
+
+impl BlockOcclusion {
+    /// Returns true if the block can correct all its covered codewords
+    #[must_use]
+    pub fn is_recoverable(&self) -> bool {
+        self.data + self.ecc <= self.capacity
+    }
+}
+
+/// Damage done by the embedded image to a [`QRCode`], see [`SvgBuilder::verify_occlusion`]
+#[derive(Debug, Clone)]
+pub struct OcclusionReport {
+    /// Error correction level of the `QRCode`
+    pub ecl: ECL,
+    /// Number of data & error correction modules covered by the image
+    pub modules: usize,
+    /// Each Reed-Solomon block, in the order of the spec
+    pub blocks: Vec<BlockOcclusion>,
+}
+
+impl OcclusionReport {
+    /// Returns true if every block can correct its covered codewords
+    #[must_use]
+    pub fn is_recoverable(&self) -> bool {
+        self.blocks.iter().all(BlockOcclusion::is_recoverable)
+    }
 }
 
 /// Creates a Builder instance
@@ -78,6 +134,7 @@ impl Default for SvgBuilder {
             image: None,
             image_background_color: [255; 4].into(),
             image_background_shape: ImageBackgroundShape::Square,
+            shaped_erase: false,
             image_size: None,
             image_position: None,
         }
@@ -113,7 +170,7 @@ impl Builder for SvgBuilder {
     }
 
     fn image(&mut self, image: String) -> &mut Self {
-        self.image = Some(image);
+        self.image = Some(Embed::Href(image));
         self
     }
 
@@ -185,22 +242,35 @@ impl SvgBuilder {
         (border_size, placed_coord, border_size - gap)
     }
 
-    fn image(&self, n: usize) -> String {
-        if self.image.is_none() {
-            return String::new();
-        }
-
-        let image = self.image.as_ref().unwrap();
-        let mut out = String::with_capacity(image.len() + 100);
+    /// Returns the position, the size of the border and the size of the image
+    fn image_area(&self, width: usize, height: usize) -> ((f64, f64), f64, f64) {
+        let centered = |border_size: f64| {
+            (
+                ((self.margin * 2 + width) as f64 - border_size) / 2f64,
+                ((self.margin * 2 + height) as f64 - border_size) / 2f64,
+            )
+        };
 
-        let (mut border_size, mut placed_coord, mut image_size) =
-            Self::image_placement(self.image_background_shape, self.margin, n);
+        let (mut border_size, mut placed_coord, mut image_size) = match self.image {
+            Some(Embed::SwissCross) => {
+                // The cross carries its own white border
+                let border_size = width as f64 * SWISS_CROSS_RATIO;
+                (border_size, centered(border_size), border_size)
+            }
+            _ if width == height => {
+                Self::image_placement(self.image_background_shape, self.margin, width)
+            }
+            _ => {
+                // Rectangular symbols have no hardcoded values, the image is centered and
+                // sized according to the height
+                let border_size = (height / 2 * 2 - 1) as f64;
+                (border_size, centered(border_size), border_size - 2f64)
+            }
+        };
 
         if let Some((override_size, gap)) = self.image_size {
             border_size = override_size + gap * 2f64;
-            let mut placed_coord_x = (self.margin * 2 + n) as f64 - border_size;
-            placed_coord_x /= 2f64;
-            placed_coord = (placed_coord_x, placed_coord_x);
+            placed_coord = centered(border_size);
             image_size = override_size;
         }
 
@@ -208,12 +278,32 @@ impl SvgBuilder {
             placed_coord = (x - border_size / 2f64, y - border_size / 2f64);
         }
 
+        (placed_coord, border_size, image_size)
+    }
+
+    fn image(&self, width: usize, height: usize) -> String {
+        let image = match &self.image {
+            Some(image) => image,
+            None => return String::new(),
+        };
+
+        let mut out = String::with_capacity(300);
+
+        let (placed_coord, border_size, image_size) = self.image_area(width, height);
+
+        // The modules under the image background are erased in a square, unless `shaped_erase`
+        let rounding = match self.image_background_shape {
+            ImageBackgroundShape::Circle if self.shaped_erase => r#" rx="1000px""#,
+            ImageBackgroundShape::RoundedSquare if self.shaped_erase => r#" rx="1px""#,
+            _ => "",
+        };
         out.push_str(&format!(
-            r#"<rect x="{0:.2}" y="{1:.2}" width="{2:.2}" height="{2:.2}" fill="{3}"/>"#,
+            r#"<rect x="{0:.2}" y="{1:.2}" width="{2:.2}" height="{2:.2}" fill="{3}"{4}/>"#,
             placed_coord.0,
             placed_coord.1,
             border_size,
-            self.background_color.to_str()
+            self.background_color.to_str(),
+            rounding
         ));
 
         let format = match self.image_background_shape {
@@ -232,21 +322,103 @@ impl SvgBuilder {
             .replace("{0}", &placed_coord.0.to_string())
             .replace("{1}", &placed_coord.1.to_string())
             .replace("{2}", &border_size.to_string())
-            .replace("{3}", &self.image_background_color.to_str());
+            .replace("{3}", self.image_background_color.to_str());
 
         out.push_str(&format);
 
-        out.push_str(&format!(
-            r#"<image x="{0:.2}" y="{1:.2}" width="{2:.2}" height="{2:.2}" href="{3}" />"#,
+        let image_coord = (
             placed_coord.0 + (border_size - image_size) / 2f64,
             placed_coord.1 + (border_size - image_size) / 2f64,
-            image_size,
-            image
-        ));
+        );
+        match image {
+            Embed::Href(href) => out.push_str(&format!(
+                r#"<image x="{0:.2}" y="{1:.2}" width="{2:.2}" height="{2:.2}" href="{3}" />"#,
+                image_coord.0, image_coord.1, image_size, href
+            )),
+            Embed::SwissCross => out.push_str(&self.swiss_cross_overlay(image_coord, image_size)),
+        }
 
         out
     }
 
+    /// Returns the Swiss cross of the QR-bill, referring to 6.4.2.3 of the Swiss Implementation
+    /// Guidelines: a square in the module color, with a cross in the image background color
+    fn swiss_cross_overlay(&self, (x, y): (f64, f64), size: f64) -> String {
+        // Proportions of the reference artwork, drawn on a 19.8 units grid
+        let unit = size / 19.8;
+        let (square, arm, thickness) = (18.4 * unit, 11f64 * unit, 3.3 * unit);
+        let rect = |x: f64, y: f64, width: f64, height: f64, color: &Color| {
+            format!(
+                r#"<rect x="{x:.3}" y="{y:.3}" width="{width:.3}" height="{height:.3}" fill="{}"/>"#,
+                color.to_str()
+            )
+        };
+
+        let (inset, arm_inset, thickness_inset) = (
+            (size - square) / 2f64,
+            (size - arm) / 2f64,
+            (size - thickness) / 2f64,
+        );
+        [
+            rect(x + inset, y + inset, square, square, &self.dot_color),
+            rect(
+                x + thickness_inset,
+                y + arm_inset,
+                thickness,
+                arm,
+                &self.image_background_color,
+            ),
+            rect(
+                x + arm_inset,
+                y + thickness_inset,
+                arm,
+                thickness,
+                &self.image_background_color,
+            ),
+        ]
+        .concat()
+    }
+
+    /// Embeds the Swiss cross of the QR-bill, see [`crate::payload::SwissQrBill`]: a vector
+    /// overlay instead of an image, sized 7/46 of the QR Code, that is 7x7 mm once the QR
+    /// Code (without margin) is printed 46x46 mm
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::convert::svg::SvgBuilder;
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("SPC").build().unwrap();
+    /// let svg = SvgBuilder::default().swiss_cross().to_str(&qrcode);
+    /// assert!(!svg.contains("<image"));
+    /// ```
+    pub fn swiss_cross(&mut self) -> &mut Self {
+        self.image = Some(Embed::SwissCross);
+        self.image_background_shape = ImageBackgroundShape::Square;
+        self
+    }
+
+    /// Erases the modules under the image in its background shape instead of a square, so a
+    /// circle or rounded square background leaves the modules in its corners visible
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::convert::{svg::SvgBuilder, Builder, ImageBackgroundShape};
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// let svg = SvgBuilder::default()
+    ///     .image(String::from("logo.png"))
+    ///     .image_background_shape(ImageBackgroundShape::Circle)
+    ///     .shaped_erase(true)
+    ///     .to_str(&qrcode);
+    /// assert!(svg.contains(r#"rx="1000px"/><rect"#));
+    /// ```
+    pub fn shaped_erase(&mut self, shaped_erase: bool) -> &mut Self {
+        self.shaped_erase = shaped_erase;
+        self
+    }
+
     fn path(&self, qr: &QRCode) -> String {
         const DEFAULT_COMMAND: [ModuleFunction; 1] = [Shape::square];
         const DEFAULT_COMMAND_COLOR: [Option<Color>; 1] = [None];
@@ -263,12 +435,12 @@ impl SvgBuilder {
             &DEFAULT_COMMAND
         };
 
-        let mut paths = vec![String::with_capacity(10 * qr.size * qr.size); commands.len()];
+        let mut paths = vec![String::with_capacity(10 * qr.size * qr.height); commands.len()];
         for path in paths.iter_mut() {
             path.push_str(r#"<path d=""#);
         }
 
-        for y in 0..qr.size {
+        for y in 0..qr.height {
             let line = &qr[y];
             for (x, &cell) in line.iter().enumerate() {
                 if !cell.value() {
@@ -285,7 +457,7 @@ impl SvgBuilder {
             let command_color = command_colors[i].as_ref().unwrap_or(&self.dot_color);
             // Allows to compare if two function pointers are the same
             // This works because there is no notion of Generics for `rounded_square`
-            if command as usize == Shape::rounded_square as usize {
+            if command as usize == Shape::rounded_square as ModuleFunction as usize {
                 paths[i].push_str(&format!(
                     r##"" stroke-width=".3" stroke-linejoin="round" stroke="{}"##,
                     command_color.to_str()
@@ -300,27 +472,208 @@ impl SvgBuilder {
 
     /// Return a string containing the svg for a qr code
     pub fn to_str(&self, qr: &QRCode) -> String {
-        let n = qr.size;
+        let (width, height) = (qr.size, qr.height);
 
-        let mut out = String::with_capacity(11 * n * n / 2);
+        let mut out = String::with_capacity(11 * width * height / 2);
         out.push_str(&format!(
-            r#"<svg viewBox="0 0 {0} {0}" xmlns="http://www.w3.org/2000/svg">"#,
-            self.margin * 2 + n
+            r#"<svg viewBox="0 0 {0} {1}" xmlns="http://www.w3.org/2000/svg">"#,
+            self.margin * 2 + width,
+            self.margin * 2 + height
         ));
 
         out.push_str(&format!(
-            r#"<rect width="{0}px" height="{0}px" fill="{1}"/>"#,
-            self.margin * 2 + n,
+            r#"<rect width="{0}px" height="{1}px" fill="{2}"/>"#,
+            self.margin * 2 + width,
+            self.margin * 2 + height,
             self.background_color.to_str()
         ));
 
         out.push_str(&self.path(qr));
-        out.push_str(&self.image(n));
+        out.push_str(&self.image(width, height));
 
         out.push_str("</svg>");
         out
     }
 
+    /// Counts the codewords of each Reed-Solomon block covered by the embedded image and its
+    /// background, referring to 7.5 of the spec. A codeword is covered as soon as one of its
+    /// modules is, even partially. The modules are erased in a square, so the corners left out
+    /// by a circle or rounded square background only count as readable with
+    /// [`SvgBuilder::shaped_erase`]. Without image, nothing is covered.
+    ///
+    /// Returns `None` for Micro QR & rMQR symbols.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::convert::{svg::SvgBuilder, Builder};
+    /// use fast_qr::{QRBuilder, ECL};
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").ecl(ECL::L).build().unwrap();
+    /// let mut builder = SvgBuilder::default();
+    /// builder.image(String::from("logo.png"));
+    ///
+    /// let report = builder.verify_occlusion(&qrcode).unwrap();
+    /// assert!(!report.is_recoverable());
+    /// ```
+    #[must_use]
+    pub fn verify_occlusion(&self, qr: &QRCode) -> Option<OcclusionReport> {
+        let (version, ecl) = match (qr.version, qr.ecl) {
+            (Some(version), Some(ecl)) if qr.micro.is_none() && qr.rmqr.is_none() => (version, ecl),
+            _ => return None,
+        };
+
+        let ((left, top), border_size, _) = self.image_area(qr.size, qr.height);
+        // Radius of the corners of the background, as drawn by `rx`, clamped by svg renderers
+        let radius = match self.image_background_shape {
+            _ if !self.shaped_erase => 0f64,
+            ImageBackgroundShape::Square => 0f64,
+            ImageBackgroundShape::Circle => border_size / 2f64,
+            ImageBackgroundShape::RoundedSquare => f64::min(1f64, border_size / 2f64),
+        };
+        // Distance from a module, starting at `start`, to the background without its corners
+        let distance = |start: f64, low: f64| {
+            let (low, high) = (low + radius, low + border_size - radius);
+            f64::max(f64::max(low - (start + 1f64), start - high), 0f64)
+        };
+        let covered = |x: usize, y: usize| {
+            let (x, y) = ((x + self.margin) as f64, (y + self.margin) as f64);
+            let (dx, dy) = (distance(x, left), distance(y, top));
+            self.image.is_some()
+                && x + 1f64 > left
+                && x < left + border_size
+                && y + 1f64 > top
+                && y < top + border_size
+                && (dx * dx + dy * dy < radius * radius || (dx == 0f64 && dy == 0f64))
+        };
+
+        // Codewords in placement order, referring to 7.7.3 of the spec
+        let codewords = version.max_bytes();
+        let mut destroyed = vec![false; codewords];
+        let (mut modules, mut idx, mut rev) = (0, 0, true);
+        for x in (0..6).chain(7..qr.size).rev().step_by(2) {
+            let y_range = if rev {
+                BiRange::Backwards((0..qr.size).rev())
+            } else {
+                BiRange::Forward(0..qr.size)
+            };
+
+            for y in y_range {
+                for x in [x, x - 1] {
+                    if qr[y][x].module_type() != ModuleType::Data {
+                        continue;
+                    }
+                    if covered(x, y) {
+                        modules += 1;
+                        if idx / 8 < codewords {
+                            destroyed[idx / 8] = true;
+                        }
+                    }
+                    idx += 1;
+                }
+            }
+
+            rev = !rev;
+        }
+
+        // Assigns the interleaved codewords back to their blocks, referring to 7.6 of the spec
+        let ecc = hardcode::get_polynomial(version, ecl).len() - 1;
+        let [(g1_count, g1_size), (g2_count, g2_size)] = hardcode::ecc_to_groups(ecl, version);
+        let sizes = core::iter::repeat(g1_size)
+            .take(g1_count)
+            .chain(core::iter::repeat(g2_size).take(g2_count))
+            .collect::<Vec<_>>();
+
+        let capacity = (ecc - misdecode_protection(version, ecl)) / 2;
+        let mut blocks = vec![
+            BlockOcclusion {
+                data: 0,
+                ecc: 0,
+                capacity,
+            };
+            sizes.len()
+        ];
+
+        let mut destroyed = destroyed.into_iter();
+        for i in 0..core::cmp::max(g1_size, g2_size) {
+            for (block, &size) in blocks.iter_mut().zip(sizes.iter()) {
+                if i < size && destroyed.next().unwrap() {
+                    block.data += 1;
+                }
+            }
+        }
+        for _ in 0..ecc {
+            for block in blocks.iter_mut() {
+                if destroyed.next().unwrap() {
+                    block.ecc += 1;
+                }
+            }
+        }
+
+        Some(OcclusionReport {
+            ecl,
+            modules,
+            blocks,
+        })
+    }
+
+    /// Returns the svg for a qr code, refusing to embed an image covering more codewords
+    /// than a block can correct
+    ///
+    /// # Errors
+    /// - `SvgError::ImageOcclusion` if the `QRCode` would not scan, see
+    ///   [`SvgBuilder::verify_occlusion`]
+    pub fn try_to_str(&self, qr: &QRCode) -> Result<String, SvgError> {
+        match self.verify_occlusion(qr) {
+            Some(report) if !report.is_recoverable() => Err(SvgError::ImageOcclusion(report)),
+            _ => Ok(self.to_str(qr)),
+        }
+    }
+
+    /// Builds the [`QRCode`] with the lowest `ECL`, starting from the one of `builder`, whose
+    /// codewords covered by the embedded image can all be corrected. Raising the `ECL` can
+    /// require a bigger version, which also moves the image.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::convert::{svg::SvgBuilder, Builder};
+    /// use fast_qr::{QRBuilder, ECL};
+    ///
+    /// let mut builder = SvgBuilder::default();
+    /// builder.image(String::from("logo.png"));
+    ///
+    /// let qrcode = builder
+    ///     .raise_ecl(QRBuilder::new("https://example.com/").ecl(ECL::L))
+    ///     .unwrap();
+    /// assert!(matches!(qrcode.ecl, Some(ECL::Q | ECL::H)));
+    /// ```
+    ///
+    /// # Errors
+    /// - `SvgError::QRCodeError` if `builder` fails to build
+    /// - `SvgError::ImageOcclusion` with the report for the highest `ECL` if none is enough
+    pub fn raise_ecl(&self, builder: &QRBuilder) -> Result<QRCode, SvgError> {
+        let mut builder = builder.clone();
+        let mut qr = builder.build().map_err(SvgError::QRCodeError)?;
+
+        loop {
+            let report = match self.verify_occlusion(&qr) {
+                Some(report) if !report.is_recoverable() => report,
+                _ => return Ok(qr),
+            };
+
+            let next = match report.ecl {
+                ECL::L => ECL::M,
+                ECL::M => ECL::Q,
+                ECL::Q => ECL::H,
+                ECL::H => return Err(SvgError::ImageOcclusion(report)),
+            };
+
+            qr = match builder.ecl(next).build() {
+                Ok(qr) => qr,
+                Err(_) => return Err(SvgError::ImageOcclusion(report)),
+            };
+        }
+    }
+
     /// Saves the svg for a qr code to a file
     #[cfg(not(feature = "wasm-bindgen"))]
     pub fn to_file(&self, qr: &QRCode, file: &str) -> Result<(), SvgError> {
@@ -335,3 +688,16 @@ impl SvgBuilder {
         Ok(())
     }
 }
+
+/// Size of the Swiss cross relative to the QR Code, 7 mm for 46 mm
+const SWISS_CROSS_RATIO: f64 = 7f64 / 46f64;
+
+/// Error correction codewords reserved for misdecode protection, referring to Table 9 of the spec
+const fn misdecode_protection(version: Version, ecl: ECL) -> usize {
+    match (version, ecl) {
+        (Version::V01, ECL::L) => 3,
+        (Version::V01, ECL::M) | (Version::V02, ECL::L) => 2,
+        (Version::V01, _) | (Version::V03, ECL::L) => 1,
+        _ => 0,
+    }
+}
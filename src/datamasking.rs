@@ -2,7 +2,8 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
-use crate::module::ModuleType;
+use crate::default::Canvas;
+use crate::module::{Module, ModuleType};
 use crate::QRCode;
 
 /// The different mask patterns. The mask pattern should only be applied to
@@ -29,7 +30,7 @@ pub enum Mask {
 
 /// Mask function nb°**0**, `Mask::Checkerboard`.
 fn mask_checkerboard(qr: &mut QRCode) {
-    for row in 0..qr.size {
+    for row in 0..qr.height {
         for column in (row & 1..qr.size).step_by(2) {
             let module = &mut qr[row][column];
             if module.module_type() == ModuleType::Data {
@@ -41,7 +42,7 @@ fn mask_checkerboard(qr: &mut QRCode) {
 
 /// Mask function nb°**1**, `Mask::HorizontalLines`.
 fn mask_horizontal(qr: &mut QRCode) {
-    for row in (0..qr.size).step_by(2) {
+    for row in (0..qr.height).step_by(2) {
         for column in 0..qr.size {
             let module = &mut qr[row][column];
             if module.module_type() == ModuleType::Data {
@@ -53,7 +54,7 @@ fn mask_horizontal(qr: &mut QRCode) {
 
 /// Mask function nb°**2**, `Mask::VerticalLines`.
 fn mask_vertical(qr: &mut QRCode) {
-    for row in 0..qr.size {
+    for row in 0..qr.height {
         for column in (0..qr.size).step_by(3) {
             let module = &mut qr[row][column];
             if module.module_type() == ModuleType::Data {
@@ -65,7 +66,7 @@ fn mask_vertical(qr: &mut QRCode) {
 
 /// Mask function nb°**3**, `Mask::DiagonalLines`.
 fn mask_diagonal(qr: &mut QRCode) {
-    for row in 0..qr.size {
+    for row in 0..qr.height {
         let start = (3 - row % 3) % 3;
         for column in (start..qr.size).step_by(3) {
             let module = &mut qr[row][column];
@@ -78,7 +79,7 @@ fn mask_diagonal(qr: &mut QRCode) {
 
 /// Mask function nb°**4**, `Mask::LargeCheckerboard`.
 fn mask_large_checkerboard(qr: &mut QRCode) {
-    for row in 0..qr.size {
+    for row in 0..qr.height {
         let start = ((row >> 1) & 1) * 3; // ((row / 2) % 2) * 3;
         for column in (start..qr.size).step_by(6) {
             for i in column..core::cmp::min(qr.size, column + 3) {
@@ -159,6 +160,37 @@ fn mask_meadow(qr: &mut QRCode) {
     }
 }
 
+/// Checks if `mask` toggles the module at `row`, `column`, `y` being the row and `x` the
+/// column in the formulas of [`Mask`]
+pub(crate) const fn is_toggled(mask: Mask, row: usize, column: usize) -> bool {
+    let (y, x) = (row, column);
+
+    match mask {
+        Mask::Checkerboard => (x + y) % 2 == 0,
+        Mask::HorizontalLines => y % 2 == 0,
+        Mask::VerticalLines => x % 3 == 0,
+        Mask::DiagonalLines => (x + y) % 3 == 0,
+        Mask::LargeCheckerboard => (x / 3 + y / 2) % 2 == 0,
+        Mask::Fields => (x * y) % 2 + (x * y) % 3 == 0,
+        Mask::Diamonds => ((x * y) % 2 + (x * y) % 3) % 2 == 0,
+        Mask::Meadow => ((x + y) % 2 + (x * y) % 3) % 2 == 0,
+    }
+}
+
+/// Applies `mask` on any `Canvas`, one module at a time. Applying it twice removes it.
+pub(crate) fn mask_canvas<C: Canvas>(qr: &mut C, mask: Mask) {
+    let n = qr.width();
+
+    for row in 0..n {
+        for column in 0..n {
+            let module = qr.get(row, column);
+            if module.module_type() == ModuleType::Data && is_toggled(mask, row, column) {
+                qr.set(row, column, Module::data(!module.value()));
+            }
+        }
+    }
+}
+
 /// Applies the function at `mask_nb` on `mat`
 pub fn mask(qr: &mut QRCode, mask: Mask) {
     match mask {
@@ -0,0 +1,597 @@
+//! Locates a `QRCode` in a grayscale image and samples its modules.
+//!
+//! The image is binarized with local thresholds, finder patterns are found by their 1:1:3:1:1
+//! runs, then the grid is sampled through a perspective transform, refined with the bottom right
+//! alignment pattern when there is one.
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use super::{from_modules, DecodeError, Decoded};
+
+/// Side of the blocks sharing a threshold
+const BLOCK: usize = 8;
+/// Blocks with a smaller contrast are considered uniform
+const MIN_DYNAMIC_RANGE: u32 = 24;
+/// Finder patterns kept to look for a triangle
+const MAX_FINDERS: usize = 8;
+/// Triangles of finder patterns tried before giving up
+const MAX_TRIANGLES: usize = 6;
+
+/// Binarized image, `true` being dark
+struct Bitmap {
+    bits: Vec<bool>,
+    width: usize,
+    height: usize,
+}
+
+impl Bitmap {
+    /// Returns the pixel under `(x, y)`, outside of the image is light
+    fn dark(&self, x: f64, y: f64) -> bool {
+        if x < 0f64 || y < 0f64 {
+            return false;
+        }
+
+        let (x, y) = (x as usize, y as usize);
+        x < self.width && y < self.height && self.bits[y * self.width + x]
+    }
+
+    /// Returns the pixels of a row, or of a column if `vertical`
+    fn line(&self, vertical: bool, index: usize) -> Vec<bool> {
+        if vertical {
+            (0..self.height)
+                .map(|y| self.bits[y * self.width + index])
+                .collect()
+        } else {
+            self.bits[index * self.width..(index + 1) * self.width].to_vec()
+        }
+    }
+}
+
+/// Thresholds each block of pixels against the average of its 5x5 neighbour blocks, uniform
+/// blocks borrowing the threshold of their neighbours
+fn binarize(luma: &[u8], width: usize, height: usize) -> Bitmap {
+    let (blocks_x, blocks_y) = ((width + BLOCK - 1) / BLOCK, (height + BLOCK - 1) / BLOCK);
+    let mut black_points = vec![0u32; blocks_x * blocks_y];
+
+    for by in 0..blocks_y {
+        for bx in 0..blocks_x {
+            let (mut sum, mut min, mut max, mut count) = (0u32, u32::MAX, 0u32, 0u32);
+            for y in by * BLOCK..core::cmp::min((by + 1) * BLOCK, height) {
+                for x in bx * BLOCK..core::cmp::min((bx + 1) * BLOCK, width) {
+                    let pixel = u32::from(luma[y * width + x]);
+                    sum += pixel;
+                    min = min.min(pixel);
+                    max = max.max(pixel);
+                    count += 1;
+                }
+            }
+
+            let mut average = sum / count;
+            if max - min <= MIN_DYNAMIC_RANGE {
+                average = min / 2;
+                if by > 0 && bx > 0 {
+                    let neighbours = (black_points[(by - 1) * blocks_x + bx]
+                        + 2 * black_points[by * blocks_x + bx - 1]
+                        + black_points[(by - 1) * blocks_x + bx - 1])
+                        / 4;
+                    if min < neighbours {
+                        average = neighbours;
+                    }
+                }
+            }
+            black_points[by * blocks_x + bx] = average;
+        }
+    }
+
+    let mut bits = vec![false; width * height];
+    for by in 0..blocks_y {
+        for bx in 0..blocks_x {
+            let (mut sum, mut count) = (0, 0);
+            for y in by.saturating_sub(2)..core::cmp::min(by + 3, blocks_y) {
+                for x in bx.saturating_sub(2)..core::cmp::min(bx + 3, blocks_x) {
+                    sum += black_points[y * blocks_x + x];
+                    count += 1;
+                }
+            }
+            let threshold = sum / count;
+
+            for y in by * BLOCK..core::cmp::min((by + 1) * BLOCK, height) {
+                for x in bx * BLOCK..core::cmp::min((bx + 1) * BLOCK, width) {
+                    bits[y * width + x] = u32::from(luma[y * width + x]) <= threshold;
+                }
+            }
+        }
+    }
+
+    Bitmap {
+        bits,
+        width,
+        height,
+    }
+}
+
+/// Run of pixels of the same color
+#[derive(Clone, Copy)]
+struct Run {
+    dark: bool,
+    start: usize,
+    len: usize,
+}
+
+/// Splits a line into runs. A run less than half as long as both its neighbours is merged with
+/// them, which closes the gaps between round or thin modules.
+fn runs(line: &[bool]) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::new();
+
+    for (i, &dark) in line.iter().enumerate() {
+        match runs.last_mut() {
+            Some(run) if run.dark == dark => run.len += 1,
+            _ => runs.push(Run {
+                dark,
+                start: i,
+                len: 1,
+            }),
+        }
+
+        // Only merges a run once the following one is complete
+        let next_complete = line.get(i + 1).map_or(true, |&next| next != dark);
+        let n = runs.len();
+        if next_complete && n >= 3 {
+            let (before, gap, after) = (runs[n - 3], runs[n - 2], runs[n - 1]);
+            if gap.len * 2 < core::cmp::min(before.len, after.len) {
+                runs.truncate(n - 2);
+                runs[n - 3].len += gap.len + after.len;
+            }
+        }
+    }
+
+    runs
+}
+
+/// Returns the center and the length of a 1:1:3:1:1 dark/light pattern centered on run `i`
+fn finder_runs(runs: &[Run], i: usize) -> Option<(f64, f64)> {
+    if i < 2 || i + 2 >= runs.len() || !runs[i].dark {
+        return None;
+    }
+
+    let window = &runs[i - 2..=i + 2];
+    let total = window.iter().map(|run| run.len).sum::<usize>() as f64;
+    let module = total / 7f64;
+    let variance = module / 2f64;
+
+    let ratios = window
+        .iter()
+        .zip([1f64, 1f64, 3f64, 1f64, 1f64])
+        .all(|(run, ratio)| (run.len as f64 - module * ratio).abs() < variance * ratio);
+    if !ratios {
+        return None;
+    }
+
+    let center = runs[i].start as f64 + runs[i].len as f64 / 2f64;
+    Some((center, total))
+}
+
+/// Finds the pattern crossing `position` of a line
+fn finder_at(line: &[bool], position: usize) -> Option<(f64, f64)> {
+    let runs = runs(line);
+    let i = runs
+        .iter()
+        .position(|run| run.start <= position && position < run.start + run.len)?;
+
+    finder_runs(&runs, i)
+}
+
+/// Center & module size of a finder pattern, `count` being the number of times it was found
+#[derive(Clone, Copy, Debug)]
+struct Finder {
+    x: f64,
+    y: f64,
+    module: f64,
+    count: usize,
+}
+
+impl Finder {
+    fn distance(&self, other: &Finder) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+/// Scans every row and column for finder patterns, cross-checking them along the other axis
+fn find_finders(bitmap: &Bitmap) -> Vec<Finder> {
+    let mut finders: Vec<Finder> = Vec::new();
+
+    for vertical in [false, true] {
+        let lines = if vertical {
+            bitmap.width
+        } else {
+            bitmap.height
+        };
+
+        for index in 0..lines {
+            let line = bitmap.line(vertical, index);
+            let runs = runs(&line);
+
+            for i in 2..runs.len().saturating_sub(2) {
+                let (along, _) = match finder_runs(&runs, i) {
+                    Some(found) => found,
+                    None => continue,
+                };
+
+                // Cross-checks along the other axis, then back along the first one
+                let across = bitmap.line(!vertical, along as usize);
+                let (center, cross_total) = match finder_at(&across, index) {
+                    Some(found) => found,
+                    None => continue,
+                };
+                let line = bitmap.line(vertical, center as usize);
+                let (along, total) = match finder_at(&line, along as usize) {
+                    Some(found) => found,
+                    None => continue,
+                };
+
+                if total.max(cross_total) > 1.5 * total.min(cross_total) {
+                    continue;
+                }
+
+                let (x, y) = if vertical {
+                    (center, along)
+                } else {
+                    (along, center)
+                };
+                let found = Finder {
+                    x,
+                    y,
+                    module: (total + cross_total) / 14f64,
+                    count: 1,
+                };
+
+                let close = finders.iter_mut().find(|finder| {
+                    finder.distance(&found) < finder.module.max(found.module) * 2f64
+                        && (finder.module - found.module).abs() < finder.module.max(found.module)
+                });
+                match close {
+                    Some(finder) => {
+                        let count = finder.count as f64;
+                        finder.x = (finder.x * count + found.x) / (count + 1f64);
+                        finder.y = (finder.y * count + found.y) / (count + 1f64);
+                        finder.module = (finder.module * count + found.module) / (count + 1f64);
+                        finder.count += 1;
+                    }
+                    None => finders.push(found),
+                }
+            }
+        }
+    }
+
+    finders.sort_by_key(|finder| core::cmp::Reverse(finder.count));
+    finders.truncate(MAX_FINDERS);
+    finders
+}
+
+/// Returns the triangles of finder patterns looking like the corners of a `QRCode`, best first,
+/// as `[top left, top right, bottom left]`
+fn triangles(finders: &[Finder]) -> Vec<[Finder; 3]> {
+    let mut triangles = Vec::new();
+
+    for i in 0..finders.len() {
+        for j in i + 1..finders.len() {
+            for k in j + 1..finders.len() {
+                let mut corners = [finders[i], finders[j], finders[k]];
+
+                let modules = corners.iter().map(|finder| finder.module);
+                let (min, max) =
+                    modules.fold((f64::MAX, 0f64), |(min, max), m| (min.min(m), max.max(m)));
+                if max > 1.4 * min {
+                    continue;
+                }
+
+                // The top left corner is opposite the longest side
+                let sides = [
+                    corners[1].distance(&corners[2]),
+                    corners[0].distance(&corners[2]),
+                    corners[0].distance(&corners[1]),
+                ];
+                let top_left = (0..3)
+                    .max_by(|&a, &b| sides[a].partial_cmp(&sides[b]).unwrap_or(Ordering::Equal))
+                    .unwrap();
+                corners.swap(0, top_left);
+
+                let [tl, mut tr, mut bl] = corners;
+                let cross = (tr.x - tl.x) * (bl.y - tl.y) - (tr.y - tl.y) * (bl.x - tl.x);
+                if cross < 0f64 {
+                    core::mem::swap(&mut tr, &mut bl);
+                }
+
+                let (a, b) = (tl.distance(&tr), tl.distance(&bl));
+                let hypotenuse = tr.distance(&bl);
+                let legs = a.max(b) / a.min(b);
+                let pythagoras = (hypotenuse * hypotenuse - a * a - b * b).abs() / (a * a + b * b);
+                if legs > 1.4 || pythagoras > 0.3 || a.min(b) < 10f64 * min {
+                    continue;
+                }
+
+                let score = (legs - 1f64) + pythagoras + (max / min - 1f64);
+                triangles.push((score, [tl, tr, bl]));
+            }
+        }
+    }
+
+    triangles.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+    triangles
+        .into_iter()
+        .take(MAX_TRIANGLES)
+        .map(|(_, triangle)| triangle)
+        .collect()
+}
+
+/// Measures the size of a finder pattern along the direction of another one, referring to the
+/// pixels, not the module size estimated when scanning
+fn module_towards(bitmap: &Bitmap, from: &Finder, to: &Finder) -> f64 {
+    let distance = from.distance(to);
+    let (dx, dy) = ((to.x - from.x) / distance, (to.y - from.y) / distance);
+    let reach = (from.module * 5f64) as isize;
+
+    let line = (-reach..=reach)
+        .map(|t| bitmap.dark(from.x + dx * t as f64, from.y + dy * t as f64))
+        .collect::<Vec<_>>();
+
+    match finder_at(&line, reach as usize) {
+        Some((_, total)) => total / 7f64,
+        None => from.module,
+    }
+}
+
+/// Valid sizes close to `estimate`, closest first
+fn dimensions(estimate: f64) -> Vec<usize> {
+    let mut dimensions = (21..=177)
+        .step_by(4)
+        .filter(|&n| (n as f64 - estimate).abs() <= 8f64)
+        .collect::<Vec<_>>();
+    dimensions.sort_by(|&a, &b| {
+        (a as f64 - estimate)
+            .abs()
+            .partial_cmp(&(b as f64 - estimate).abs())
+            .unwrap_or(Ordering::Equal)
+    });
+    dimensions.truncate(4);
+    dimensions
+}
+
+/// Projective transform, mapping `(x, y, 1)` with a 3x3 matrix
+#[derive(Clone, Copy)]
+struct Transform([[f64; 3]; 3]);
+
+impl Transform {
+    /// Maps the unit square to a quadrilateral, corners going (0, 0), (1, 0), (1, 1), (0, 1)
+    fn square_to_quad(quad: [(f64, f64); 4]) -> Transform {
+        let [(x0, y0), (x1, y1), (x2, y2), (x3, y3)] = quad;
+        let (dx3, dy3) = (x0 - x1 + x2 - x3, y0 - y1 + y2 - y3);
+
+        let (g, h) = if dx3.abs() < 1e-9 && dy3.abs() < 1e-9 {
+            (0f64, 0f64)
+        } else {
+            let (dx1, dx2, dy1, dy2) = (x1 - x2, x3 - x2, y1 - y2, y3 - y2);
+            let denominator = dx1 * dy2 - dx2 * dy1;
+            (
+                (dx3 * dy2 - dx2 * dy3) / denominator,
+                (dx1 * dy3 - dx3 * dy1) / denominator,
+            )
+        };
+
+        Transform([
+            [x1 - x0 + g * x1, x3 - x0 + h * x3, x0],
+            [y1 - y0 + g * y1, y3 - y0 + h * y3, y0],
+            [g, h, 1f64],
+        ])
+    }
+
+    /// Inverse up to a factor, which is enough for a projective transform
+    fn adjugate(self) -> Transform {
+        let m = self.0;
+        let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| {
+            m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+        };
+
+        Transform([
+            [
+                cofactor(1, 2, 1, 2),
+                -cofactor(0, 2, 1, 2),
+                cofactor(0, 1, 1, 2),
+            ],
+            [
+                -cofactor(1, 2, 0, 2),
+                cofactor(0, 2, 0, 2),
+                -cofactor(0, 1, 0, 2),
+            ],
+            [
+                cofactor(1, 2, 0, 1),
+                -cofactor(0, 2, 0, 1),
+                cofactor(0, 1, 0, 1),
+            ],
+        ])
+    }
+
+    fn then(self, other: Transform) -> Transform {
+        let (a, b) = (other.0, self.0);
+        let mut out = [[0f64; 3]; 3];
+        for (i, row) in out.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+            }
+        }
+        Transform(out)
+    }
+
+    /// Maps the `from` quadrilateral to the `to` quadrilateral
+    fn quad_to_quad(from: [(f64, f64); 4], to: [(f64, f64); 4]) -> Transform {
+        Transform::square_to_quad(from)
+            .adjugate()
+            .then(Transform::square_to_quad(to))
+    }
+
+    fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        let m = self.0;
+        let w = m[2][0] * x + m[2][1] * y + m[2][2];
+        (
+            (m[0][0] * x + m[0][1] * y + m[0][2]) / w,
+            (m[1][0] * x + m[1][1] * y + m[1][2]) / w,
+        )
+    }
+}
+
+/// Looks for the bottom right alignment pattern around `(x, y)`, `u` & `v` being one module
+/// along the rows and columns of the `QRCode`. The search widens when nothing is found close to
+/// the estimate, as perspective moves the pattern away from it.
+fn find_alignment(
+    bitmap: &Bitmap,
+    (x, y): (f64, f64),
+    u: (f64, f64),
+    v: (f64, f64),
+) -> Option<(f64, f64)> {
+    let module = u.0.hypot(u.1).min(v.0.hypot(v.1));
+    let step = (module / 3f64).max(1f64);
+
+    // Dark center, light ring at one module and dark ring at two modules
+    let score = |cx: f64, cy: f64| {
+        let mut score = usize::from(bitmap.dark(cx, cy));
+        for (i, j) in [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ] {
+            let (i, j) = (f64::from(i), f64::from(j));
+            let ring = |k: f64| (cx + (u.0 * i + v.0 * j) * k, cy + (u.1 * i + v.1 * j) * k);
+            let (lx, ly) = ring(1f64);
+            let (dx, dy) = ring(2f64);
+            score += usize::from(!bitmap.dark(lx, ly)) + usize::from(bitmap.dark(dx, dy));
+        }
+        score
+    };
+
+    for modules in [4f64, 8f64] {
+        let reach = (modules * module / step) as isize;
+        let mut positions = Vec::new();
+        for i in -reach..=reach {
+            for j in -reach..=reach {
+                let (cx, cy) = (x + i as f64 * step, y + j as f64 * step);
+                positions.push((score(cx, cy), cx, cy));
+            }
+        }
+
+        // All checks but two pass
+        let best = positions.iter().map(|&(score, _, _)| score).max()?;
+        if best < 15 {
+            continue;
+        }
+
+        // Averages the best positions around the closest one to the estimate
+        let distance = |&(_, cx, cy): &(usize, f64, f64)| (cx - x).hypot(cy - y);
+        positions.retain(|&(score, _, _)| score == best);
+        let closest = *positions.iter().min_by(|a, b| {
+            distance(a)
+                .partial_cmp(&distance(b))
+                .unwrap_or(Ordering::Equal)
+        })?;
+        let around = positions
+            .iter()
+            .filter(|&&(_, cx, cy)| (cx - closest.1).hypot(cy - closest.2) <= module)
+            .collect::<Vec<_>>();
+
+        let count = around.len() as f64;
+        let (sx, sy) = around
+            .iter()
+            .fold((0f64, 0f64), |(sx, sy), &&(_, x, y)| (sx + x, sy + y));
+        return Some((sx / count, sy / count));
+    }
+
+    None
+}
+
+/// Samples the center of every module
+fn sample(bitmap: &Bitmap, transform: &Transform, dimension: usize) -> Vec<bool> {
+    let mut modules = Vec::with_capacity(dimension * dimension);
+    for y in 0..dimension {
+        for x in 0..dimension {
+            let (px, py) = transform.apply(x as f64 + 0.5, y as f64 + 0.5);
+            modules.push(bitmap.dark(px, py));
+        }
+    }
+    modules
+}
+
+/// Tries every size close to the one estimated from the finder patterns
+fn decode_triangle(bitmap: &Bitmap, [tl, tr, bl]: [Finder; 3]) -> Result<Decoded, DecodeError> {
+    let module_x = (module_towards(bitmap, &tl, &tr) + module_towards(bitmap, &tr, &tl)) / 2f64;
+    let module_y = (module_towards(bitmap, &tl, &bl) + module_towards(bitmap, &bl, &tl)) / 2f64;
+    let estimate = (tl.distance(&tr) / module_x + tl.distance(&bl) / module_y) / 2f64 + 7f64;
+
+    let mut error = DecodeError::NotFound;
+    for dimension in dimensions(estimate) {
+        let n = dimension as f64;
+        let between = n - 7f64;
+        let u = ((tr.x - tl.x) / between, (tr.y - tl.y) / between);
+        let v = ((bl.x - tl.x) / between, (bl.y - tl.y) / between);
+
+        let finders = [
+            (3.5, 3.5),
+            (n - 3.5, 3.5),
+            (n - 3.5, n - 3.5),
+            (3.5, n - 3.5),
+        ];
+        let corner = (tr.x + bl.x - tl.x, tr.y + bl.y - tl.y);
+        let mut attempts = vec![Transform::quad_to_quad(
+            finders,
+            [(tl.x, tl.y), (tr.x, tr.y), corner, (bl.x, bl.y)],
+        )];
+
+        if dimension > 21 {
+            let t = between - 3f64;
+            let estimate = (tl.x + (u.0 + v.0) * t, tl.y + (u.1 + v.1) * t);
+            if let Some(alignment) = find_alignment(bitmap, estimate, u, v) {
+                let from = [
+                    (3.5, 3.5),
+                    (n - 3.5, 3.5),
+                    (n - 6.5, n - 6.5),
+                    (3.5, n - 3.5),
+                ];
+                let to = [(tl.x, tl.y), (tr.x, tr.y), alignment, (bl.x, bl.y)];
+                attempts.insert(0, Transform::quad_to_quad(from, to));
+            }
+        }
+
+        for transform in attempts {
+            match from_modules(&sample(bitmap, &transform, dimension), dimension) {
+                Ok(decoded) => return Ok(decoded),
+                Err(err) => error = err,
+            }
+        }
+    }
+
+    Err(error)
+}
+
+/// Finds and decodes a `QRCode` in a `width x height` grayscale image
+pub(super) fn decode(luma: &[u8], width: usize, height: usize) -> Result<Decoded, DecodeError> {
+    let bitmap = binarize(luma, width, height);
+    let finders = find_finders(&bitmap);
+
+    let mut error = DecodeError::NotFound;
+    for triangle in triangles(&finders) {
+        match decode_triangle(&bitmap, triangle) {
+            Ok(decoded) => return Ok(decoded),
+            Err(err) => error = err,
+        }
+    }
+
+    Err(error)
+}
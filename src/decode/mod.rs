@@ -0,0 +1,570 @@
+//! Decodes [`QRCode`] matrices back into their data, referring to 12 of the spec.
+//!
+//! Format & version information are read with error correction, the data is unmasked, its
+//! blocks de-interleaved and corrected with Reed-Solomon, then the segments are parsed back into
+//! bytes. Only regular `QRCode`s (versions 1 to 40) can be decoded.
+//!
+//! Images are read with [`from_luma`], [`from_rgba`] or, with the `image` feature, [`from_pixmap`]:
+//! the `QRCode` is located by its finder patterns and its modules are sampled through a perspective
+//! transform before being decoded. Reading images needs the `std` feature, for floating point math.
+//!
+//! ```rust
+//! use fast_qr::decode;
+//! use fast_qr::QRBuilder;
+//!
+//! let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+//! let decoded = decode::from_qrcode(&qrcode).unwrap();
+//!
+//! assert_eq!(decoded.data, b"https://example.com/");
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::datamasking::{self, Mask};
+use crate::encode::{self, Mode};
+use crate::module::ModuleType;
+use crate::placement::BiRange;
+use crate::{default, hardcode, polynomials, QRCode, Version, ECL};
+
+#[cfg(feature = "std")]
+mod detect;
+
+/// Characters of the alphanumeric mode, by value
+const ALPHANUMERIC: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// Masks, by the number stored in the format information
+const MASKS: [Mask; 8] = [
+    Mask::Checkerboard,
+    Mask::HorizontalLines,
+    Mask::VerticalLines,
+    Mask::DiagonalLines,
+    Mask::LargeCheckerboard,
+    Mask::Fields,
+    Mask::Diamonds,
+    Mask::Meadow,
+];
+
+/// Error when a matrix could not be decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Matrix is not square, its size is not `version * 4 + 17`, or a pixel buffer does not
+    /// match its dimensions
+    InvalidSize,
+    /// No `QRCode` was located in the image
+    NotFound,
+    /// Neither copy of the format information could be corrected
+    FormatInformation,
+    /// Neither copy of the version information could be corrected, or it does not match the size
+    VersionInformation,
+    /// A block has more errors than its error correction codewords can correct
+    TooManyErrors,
+    /// Corrected data holds an unknown mode indicator or a truncated segment
+    InvalidData,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeError::InvalidSize => f.write_str("Matrix size is not a QRCode size"),
+            DecodeError::NotFound => f.write_str("No QRCode found in the image"),
+            DecodeError::FormatInformation => f.write_str("Format information is unreadable"),
+            DecodeError::VersionInformation => f.write_str("Version information is unreadable"),
+            DecodeError::TooManyErrors => f.write_str("Too many errors to correct"),
+            DecodeError::InvalidData => f.write_str("Data segments are invalid"),
+        }
+    }
+}
+
+/// Content and parameters of a decoded `QRCode`
+#[derive(Debug, Clone)]
+pub struct Decoded {
+    /// Data of all segments, ECI, Structured Append and FNC1 headers are not included. In FNC1
+    /// mode, `%` of alphanumeric segments are decoded back to GS separators.
+    pub data: Vec<u8>,
+    /// Version of the `QRCode`
+    pub version: Version,
+    /// Error correction level read from the format information
+    pub ecl: ECL,
+    /// Mask read from the format information
+    pub mask: Mask,
+    /// Number of codewords corrected by Reed-Solomon
+    pub corrected: usize,
+}
+
+/// Decodes a [`QRCode`]
+///
+/// # Errors
+/// - `DecodeError::InvalidSize` for Micro QR & rMQR symbols
+/// - Any error of [`from_modules`]
+pub fn from_qrcode(qr: &QRCode) -> Result<Decoded, DecodeError> {
+    if qr.size != qr.height {
+        return Err(DecodeError::InvalidSize);
+    }
+
+    let modules = qr.data[..qr.size * qr.size]
+        .iter()
+        .map(|module| module.value())
+        .collect::<Vec<_>>();
+
+    from_modules(&modules, qr.size)
+}
+
+/// Finds and decodes a `QRCode` in a grayscale image, one byte per pixel, row by row
+///
+/// ```rust
+/// use fast_qr::decode;
+/// use fast_qr::QRBuilder;
+///
+/// let qrcode = QRBuilder::new("Hello").build().unwrap();
+///
+/// // Renders each module as 4x4 pixels, with a quiet zone of 4 modules
+/// let width = (qrcode.size + 8) * 4;
+/// let mut pixels = vec![255u8; width * width];
+/// for y in 0..qrcode.size * 4 {
+///     for x in 0..qrcode.size * 4 {
+///         if qrcode[y / 4][x / 4].value() {
+///             pixels[(y + 16) * width + x + 16] = 0;
+///         }
+///     }
+/// }
+///
+/// let decoded = decode::from_luma(&pixels, width, width).unwrap();
+/// assert_eq!(decoded.data, b"Hello");
+/// ```
+///
+/// # Errors
+/// - `DecodeError::InvalidSize` if `pixels` is not `width x height`
+/// - `DecodeError::NotFound` if no finder patterns are located
+/// - Any error of [`from_modules`] for the best candidate
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn from_luma(pixels: &[u8], width: usize, height: usize) -> Result<Decoded, DecodeError> {
+    if width == 0 || height == 0 || pixels.len() != width * height {
+        return Err(DecodeError::InvalidSize);
+    }
+
+    detect::decode(pixels, width, height)
+}
+
+/// Finds and decodes a `QRCode` in an RGBA image, four bytes per pixel, row by row.
+/// Transparent pixels are composited over white.
+///
+/// # Errors
+/// - `DecodeError::InvalidSize` if `pixels` is not `width x height x 4`
+/// - Any error of [`from_luma`]
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn from_rgba(pixels: &[u8], width: usize, height: usize) -> Result<Decoded, DecodeError> {
+    if pixels.len() != width * height * 4 {
+        return Err(DecodeError::InvalidSize);
+    }
+
+    let luma = pixels
+        .chunks_exact(4)
+        .map(|pixel| {
+            let alpha = u32::from(pixel[3]);
+            let over_white = |c: u8| u32::from(c) * alpha / 255 + 255 - alpha;
+            luma(
+                over_white(pixel[0]),
+                over_white(pixel[1]),
+                over_white(pixel[2]),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    from_luma(&luma, width, height)
+}
+
+/// Finds and decodes a `QRCode` in a [`Pixmap`](resvg::tiny_skia::Pixmap), such as the ones
+/// rendered by [`ImageBuilder::to_pixmap`](crate::convert::image::ImageBuilder::to_pixmap)
+///
+/// # Errors
+/// - Any error of [`from_luma`]
+#[cfg(feature = "image")]
+#[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+pub fn from_pixmap(pixmap: &resvg::tiny_skia::Pixmap) -> Result<Decoded, DecodeError> {
+    // Pixmaps are premultiplied by their alpha
+    let luma = pixmap
+        .data()
+        .chunks_exact(4)
+        .map(|pixel| {
+            let transparency = 255 - u32::from(pixel[3]);
+            let over_white = |c: u8| u32::from(c) + transparency;
+            luma(
+                over_white(pixel[0]),
+                over_white(pixel[1]),
+                over_white(pixel[2]),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    from_luma(&luma, pixmap.width() as usize, pixmap.height() as usize)
+}
+
+/// Luminance of a color, in 8 bits fixed point
+#[cfg(feature = "std")]
+fn luma(r: u32, g: u32, b: u32) -> u8 {
+    ((r * 77 + g * 150 + b * 29) >> 8) as u8
+}
+
+/// Decodes a `size x size` matrix, row by row, `true` being a dark module
+///
+/// # Errors
+/// - `DecodeError::InvalidSize` if `modules` is not `size x size` or `size` is not a `QRCode` size
+/// - `DecodeError::FormatInformation` or `DecodeError::VersionInformation` if they are unreadable
+/// - `DecodeError::TooManyErrors` if a block can't be corrected
+/// - `DecodeError::InvalidData` if the corrected data is not valid
+pub fn from_modules(modules: &[bool], size: usize) -> Result<Decoded, DecodeError> {
+    if modules.len() != size * size || !(21..=177).contains(&size) || size % 4 != 1 {
+        return Err(DecodeError::InvalidSize);
+    }
+
+    let version = Version::from_n(size);
+    let module = |y: usize, x: usize| modules[y * size + x];
+
+    let (ecl, mask) = read_format_information(&module, size)?;
+    if version as usize >= Version::V07 as usize {
+        read_version_information(&module, size, version)?;
+    }
+
+    let mut qr = default::create_matrix(version);
+    for y in 0..size {
+        for x in 0..size {
+            if qr[y][x].module_type() == ModuleType::Data {
+                qr[y][x].set(module(y, x));
+            }
+        }
+    }
+    datamasking::mask(&mut qr, mask);
+
+    let codewords = read_codewords(&qr, version.max_bytes());
+    let (data, corrected) = deinterleave(&codewords, version, ecl)?;
+    let data = parse_segments(&data, version)?;
+
+    Ok(Decoded {
+        data,
+        version,
+        ecl,
+        mask,
+        corrected,
+    })
+}
+
+/// Returns the candidate with the fewest different bits, if at most 3 bits differ from one of
+/// the copies
+fn closest<I: Iterator<Item = (u32, T)>, T>(copies: [u32; 2], candidates: I) -> Option<T> {
+    candidates
+        .map(|(bits, value)| {
+            let distance = copies.iter().map(|copy| (copy ^ bits).count_ones());
+            (distance.min().unwrap(), value)
+        })
+        .filter(|&(distance, _)| distance <= 3)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, value)| value)
+}
+
+/// Reads both copies of the format information, referring to 7.9 of the spec
+fn read_format_information<F: Fn(usize, usize) -> bool>(
+    module: &F,
+    n: usize,
+) -> Result<(ECL, Mask), DecodeError> {
+    let mut copies = [0u32; 2];
+    let mut set = |bit: usize, first: (usize, usize), second: (usize, usize)| {
+        copies[0] |= u32::from(module(first.0, first.1)) << bit;
+        copies[1] |= u32::from(module(second.0, second.1)) << bit;
+    };
+
+    for i in 0..=5 {
+        set(i + 9, (8, 5 - i), (n - 6 + i, 8));
+        set(i, (i, 8), (8, n - i - 1));
+    }
+    set(8, (8, 7), (n - 7, 8));
+    set(7, (8, 8), (8, n - 8));
+    set(6, (7, 8), (8, n - 7));
+
+    let candidates = [ECL::L, ECL::M, ECL::Q, ECL::H]
+        .into_iter()
+        .flat_map(|ecl| {
+            MASKS.into_iter().map(move |mask| {
+                let bits = hardcode::ecm_to_format_information(ecl, mask);
+                (u32::from(bits), (ecl, mask))
+            })
+        });
+
+    closest(copies, candidates).ok_or(DecodeError::FormatInformation)
+}
+
+/// Reads both copies of the version information, referring to 7.10 of the spec
+fn read_version_information<F: Fn(usize, usize) -> bool>(
+    module: &F,
+    n: usize,
+    version: Version,
+) -> Result<(), DecodeError> {
+    let mut copies = [0u32; 2];
+    for i in 0..=2 {
+        for j in 0..=5 {
+            copies[0] |= u32::from(module(j, n - 11 + i)) << (j * 3 + i);
+            copies[1] |= u32::from(module(n - 11 + i, j)) << (j * 3 + i);
+        }
+    }
+
+    let candidates = (7..=40).map(|v| {
+        let version = Version::from_n(v * 4 + 17);
+        (version.information(), version)
+    });
+
+    match closest(copies, candidates) {
+        Some(read) if read as usize == version as usize => Ok(()),
+        _ => Err(DecodeError::VersionInformation),
+    }
+}
+
+/// Reads the codewords of an unmasked matrix, in the order they were placed
+fn read_codewords(qr: &QRCode, count: usize) -> Vec<u8> {
+    let mut codewords = vec![0u8; count];
+    let mut idx = 0;
+    let mut rev = true;
+
+    // 0, 2, 4, 7, 9, .., N (skipping 6)
+    for x in (0..6).chain(7..qr.size).rev().step_by(2) {
+        let y_range = if rev {
+            BiRange::Backwards((0..qr.size).rev())
+        } else {
+            BiRange::Forward(0..qr.size)
+        };
+
+        for y in y_range {
+            for x in [x, x - 1] {
+                if qr[y][x].module_type() == ModuleType::Data && idx < count * 8 {
+                    codewords[idx / 8] |= u8::from(qr[y][x].value()) << (7 - idx % 8);
+                    idx += 1;
+                }
+            }
+        }
+
+        rev = !rev;
+    }
+
+    codewords
+}
+
+/// Splits the codewords into their blocks, corrects them and returns the data codewords with the
+/// number of corrected codewords, referring to 7.6 of the spec
+fn deinterleave(
+    codewords: &[u8],
+    version: Version,
+    ecl: ECL,
+) -> Result<(Vec<u8>, usize), DecodeError> {
+    let ecc = hardcode::get_polynomial(version, ecl).len() - 1;
+    let [(g1_count, g1_size), (g2_count, g2_size)] = hardcode::ecc_to_groups(ecl, version);
+
+    let sizes = core::iter::repeat(g1_size)
+        .take(g1_count)
+        .chain(core::iter::repeat(g2_size).take(g2_count))
+        .collect::<Vec<_>>();
+    let mut blocks = sizes
+        .iter()
+        .map(|size| Vec::with_capacity(size + ecc))
+        .collect::<Vec<_>>();
+
+    let mut codewords = codewords.iter();
+    for i in 0..core::cmp::max(g1_size, g2_size) {
+        for (block, &size) in blocks.iter_mut().zip(sizes.iter()) {
+            if i < size {
+                block.push(*codewords.next().unwrap());
+            }
+        }
+    }
+    for _ in 0..ecc {
+        for block in blocks.iter_mut() {
+            block.push(*codewords.next().unwrap());
+        }
+    }
+
+    let mut data = Vec::with_capacity(g1_count * g1_size + g2_count * g2_size);
+    let mut corrected = 0;
+    for block in blocks.iter_mut() {
+        corrected += polynomials::correct(block, ecc).ok_or(DecodeError::TooManyErrors)?;
+        data.extend_from_slice(&block[..block.len() - ecc]);
+    }
+
+    Ok((data, corrected))
+}
+
+/// Reads bits from data codewords, most significant bit first
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl BitReader<'_> {
+    const fn remaining(&self) -> usize {
+        self.data.len() * 8 - self.position
+    }
+
+    fn read(&mut self, bits: usize) -> Result<usize, DecodeError> {
+        if bits > self.remaining() {
+            return Err(DecodeError::InvalidData);
+        }
+
+        let mut value = 0;
+        for _ in 0..bits {
+            let bit = self.data[self.position / 8] >> (7 - self.position % 8) & 1;
+            value = value << 1 | usize::from(bit);
+            self.position += 1;
+        }
+
+        Ok(value)
+    }
+}
+
+/// Parses the segments back into bytes, referring to 7.4 of the spec
+fn parse_segments(data: &[u8], version: Version) -> Result<Vec<u8>, DecodeError> {
+    let mut reader = BitReader { data, position: 0 };
+    let mut out = Vec::new();
+    let mut fnc1 = false;
+
+    while reader.remaining() >= 4 {
+        let mode = match reader.read(4)? {
+            0b0000 => break,
+            0b0001 => Mode::Numeric,
+            0b0010 => Mode::Alphanumeric,
+            0b0100 => Mode::Byte,
+            0b1000 => Mode::Kanji,
+            0b0111 => {
+                // ECI designator is 1, 2 or 3 bytes long, depending on its first bits
+                let first = reader.read(8)? as u8;
+                match first.leading_ones() {
+                    0 => (),
+                    1 => {
+                        reader.read(8)?;
+                    }
+                    2 => {
+                        reader.read(16)?;
+                    }
+                    _ => return Err(DecodeError::InvalidData),
+                }
+                continue;
+            }
+            0b0011 => {
+                reader.read(16)?;
+                continue;
+            }
+            0b0101 => {
+                fnc1 = true;
+                continue;
+            }
+            0b1001 => {
+                reader.read(8)?;
+                fnc1 = true;
+                continue;
+            }
+            _ => return Err(DecodeError::InvalidData),
+        };
+
+        let count = reader.read(hardcode::cci_bits(version, mode))?;
+        match mode {
+            Mode::Numeric => parse_numeric(&mut reader, count, &mut out)?,
+            Mode::Alphanumeric => parse_alphanumeric(&mut reader, count, fnc1, &mut out)?,
+            Mode::Byte => {
+                for _ in 0..count {
+                    out.push(reader.read(8)? as u8);
+                }
+            }
+            Mode::Kanji => {
+                for _ in 0..count {
+                    let value = reader.read(13)?;
+                    let code = ((value / 0xC0) << 8) | (value % 0xC0);
+                    let code = if code < 0x1F00 {
+                        code + 0x8140
+                    } else {
+                        code + 0xC140
+                    };
+                    out.extend_from_slice(&(code as u16).to_be_bytes());
+                }
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Parses `count` digits, 3 digits in 10 bits
+fn parse_numeric(
+    reader: &mut BitReader,
+    count: usize,
+    out: &mut Vec<u8>,
+) -> Result<(), DecodeError> {
+    let mut left = count;
+    while left > 0 {
+        let (digits, bits) = match left {
+            1 => (1, 4),
+            2 => (2, 7),
+            _ => (3, 10),
+        };
+
+        let value = reader.read(bits)?;
+        if value >= 10usize.pow(digits) {
+            return Err(DecodeError::InvalidData);
+        }
+        out.extend_from_slice(format!("{value:0width$}", width = digits as usize).as_bytes());
+        left -= digits as usize;
+    }
+
+    Ok(())
+}
+
+/// Parses `count` characters, 2 characters in 11 bits. In FNC1 mode, `%%` is a `%` and a single
+/// `%` a GS separator.
+fn parse_alphanumeric(
+    reader: &mut BitReader,
+    count: usize,
+    fnc1: bool,
+    out: &mut Vec<u8>,
+) -> Result<(), DecodeError> {
+    let mut chars = Vec::with_capacity(count);
+    let mut left = count;
+    while left > 0 {
+        if left == 1 {
+            let value = reader.read(6)?;
+            chars.push(*ALPHANUMERIC.get(value).ok_or(DecodeError::InvalidData)?);
+            break;
+        }
+
+        let value = reader.read(11)?;
+        if value >= 45 * 45 {
+            return Err(DecodeError::InvalidData);
+        }
+        chars.push(ALPHANUMERIC[value / 45]);
+        chars.push(ALPHANUMERIC[value % 45]);
+        left -= 2;
+    }
+
+    if !fnc1 {
+        out.extend_from_slice(&chars);
+        return Ok(());
+    }
+
+    let mut chars = chars.into_iter().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            (b'%', Some(b'%')) => {
+                chars.next();
+                out.push(b'%');
+            }
+            (b'%', _) => out.push(encode::GS),
+            _ => out.push(c),
+        }
+    }
+
+    Ok(())
+}
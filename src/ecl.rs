@@ -4,7 +4,7 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
-use std::fmt::Write;
+use core::fmt::Write;
 
 /// Error Correction Coding has 4 levels
 #[derive(Copy, Clone, Debug)]
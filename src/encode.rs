@@ -6,38 +6,534 @@
 use crate::compact::CompactQR;
 use crate::ecl::ECL;
 use crate::hardcode;
+use crate::qr::QRCodeError;
 use crate::version::Version;
+use alloc::vec;
+use alloc::vec::Vec;
 
-/// Enum for the 3 encoding mode
+/// Enum for the 4 encoding mode
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Mode {
+    /// Digits only (0-9), 3 digits in 10 bits
     Numeric,
+    /// Digits, uppercase letters and ` $%*+-./:`, 2 characters in 11 bits
     Alphanumeric,
+    /// Any byte, 8 bits each
     Byte,
+    /// Shift JIS double-byte characters, 13 bits each
+    Kanji,
 }
 
-/// Encodes the string according the mode and version
-pub fn encode(input: &[u8], ecl: ECL, mode: Mode, version: Version) -> CompactQR {
-    let cci_bits = hardcode::cci_bits(version, mode);
+/// Part of a [`crate::QRCode`] bitstream: data encoded with a single [`Mode`], or a header
+/// changing how the following data is interpreted.
+///
+/// Segments are encoded as is, which gives a deterministic output whatever the input.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::{QRBuilder, Segment};
+///
+/// let qrcode = QRBuilder::segments(vec![
+///     Segment::numeric("0123456789"),
+///     Segment::alphanumeric(" ORDER"),
+///     Segment::eci(26),
+///     Segment::bytes("🚀"),
+/// ])
+/// .build();
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Segment {
+    /// Data encoded with said `Mode`, Shift JIS pairs for `Mode::Kanji`
+    Data(Mode, Vec<u8>),
+    /// Extended Channel Interpretation assignment number (0 to 999999), referring to 8.4.1 of the spec
+    Eci(u32),
+    /// Structured Append header linking up to 16 symbols, referring to 9 of the spec.
+    /// It must be the first segment of the symbol.
+    StructuredAppend {
+        /// Position of the symbol, from 0 to `total - 1`
+        index: u8,
+        /// Number of symbols, from 1 to 16
+        total: u8,
+        /// XOR of every byte of the whole data, see [`Segment::parity`]
+        parity: u8,
+    },
+    /// FNC1 in first position, the data follows the GS1 Application Identifiers standard,
+    /// referring to 7.4.8 of the spec.
+    ///
+    /// In alphanumeric segments following it, `%` stands for FNC1 (the GS separator) and
+    /// `%%` for a literal `%`. Byte segments use the GS byte (0x1D).
+    Fnc1First,
+    /// FNC1 in second position, the data follows an industry specification identified
+    /// by its application indicator, referring to 7.4.8 of the spec.
+    ///
+    /// The indicator is either a two-digit number (0 to 99), or a letter as its ASCII
+    /// value + 100 (i.e. 165 for `A`).
+    Fnc1Second(u8),
+}
+
+impl Segment {
+    /// Creates a numeric segment, only digits (0-9) are valid
+    #[must_use]
+    pub fn numeric<I: Into<Vec<u8>>>(data: I) -> Self {
+        Segment::Data(Mode::Numeric, data.into())
+    }
+
+    /// Creates an alphanumeric segment, only digits, uppercase letters and ` $%*+-./:` are valid
+    #[must_use]
+    pub fn alphanumeric<I: Into<Vec<u8>>>(data: I) -> Self {
+        Segment::Data(Mode::Alphanumeric, data.into())
+    }
+
+    /// Creates a byte segment, any byte is valid
+    #[must_use]
+    pub fn bytes<I: Into<Vec<u8>>>(data: I) -> Self {
+        Segment::Data(Mode::Byte, data.into())
+    }
+
+    /// Creates a kanji segment, only Shift JIS double-byte characters are valid
+    #[must_use]
+    pub fn kanji<I: Into<Vec<u8>>>(data: I) -> Self {
+        Segment::Data(Mode::Kanji, data.into())
+    }
+
+    /// Creates an ECI segment, the following segments are read using said assignment number
+    /// (i.e. 26 for UTF-8)
+    #[must_use]
+    pub const fn eci(assignment: u32) -> Self {
+        Segment::Eci(assignment)
+    }
+
+    /// Creates a Structured Append header, for the symbol at `index` out of `total`
+    #[must_use]
+    pub const fn structured_append(index: u8, total: u8, parity: u8) -> Self {
+        Segment::StructuredAppend {
+            index,
+            total,
+            parity,
+        }
+    }
+
+    /// Creates an FNC1 first position header, for GS1 data
+    #[must_use]
+    pub const fn fnc1_first() -> Self {
+        Segment::Fnc1First
+    }
+
+    /// Creates an FNC1 second position header, for data following said `application_indicator`
+    #[must_use]
+    pub const fn fnc1_second(application_indicator: u8) -> Self {
+        Segment::Fnc1Second(application_indicator)
+    }
+
+    /// Computes the Structured Append parity of the whole `data`, split across symbols
+    #[must_use]
+    pub fn parity(data: &[u8]) -> u8 {
+        data.iter().fold(0, |parity, &byte| parity ^ byte)
+    }
+
+    /// Returns the data of the segment, empty for headers
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        match self {
+            Segment::Data(_, data) => data,
+            _ => &[],
+        }
+    }
+
+    /// Returns the number of bits needed to encode the segment for said `version`
+    pub(crate) fn bits(&self, version: Version) -> usize {
+        match self {
+            Segment::Data(mode, data) => {
+                4 + hardcode::cci_bits(version, *mode) + payload_bits(*mode, data)
+            }
+            Segment::Eci(assignment) => 4 + eci_bits(*assignment),
+            Segment::StructuredAppend { .. } => 20,
+            Segment::Fnc1First => 4,
+            Segment::Fnc1Second(_) => 12,
+        }
+    }
+}
+
+/// Returns the number of bits of `data` encoded with `mode`, without mode indicator
+/// and character count indicator
+pub(crate) const fn payload_bits(mode: Mode, data: &[u8]) -> usize {
+    chars_bits(mode, char_count(mode, data))
+}
+
+/// Returns the number of bits of `len` characters encoded with `mode`, without mode
+/// indicator and character count indicator
+pub(crate) const fn chars_bits(mode: Mode, len: usize) -> usize {
+    match mode {
+        Mode::Numeric => len / 3 * 10 + [0, 4, 7][len % 3],
+        Mode::Alphanumeric => len / 2 * 11 + len % 2 * 6,
+        Mode::Byte => len * 8,
+        Mode::Kanji => len * 13,
+    }
+}
+
+/// Returns the number of characters, as written in the character count indicator
+pub(crate) const fn char_count(mode: Mode, data: &[u8]) -> usize {
+    match mode {
+        Mode::Kanji => data.len() / 2,
+        _ => data.len(),
+    }
+}
+
+/// Returns the number of bits of an ECI assignment number, referring to Table 4 of the spec
+const fn eci_bits(assignment: u32) -> usize {
+    match assignment {
+        0..=127 => 8,
+        128..=16_383 => 16,
+        _ => 24,
+    }
+}
+
+/// Returns the number of bits needed to encode all `segments` for said `version`
+pub fn segments_bits(segments: &[Segment], version: Version) -> usize {
+    segments.iter().map(|segment| segment.bits(version)).sum()
+}
 
+/// Checks every segment can be encoded, returning the first offending character.
+///
+/// Indexes count the data of all segments, as if they were a single input.
+pub fn check_segments(segments: &[Segment]) -> Result<(), QRCodeError> {
+    let mut offset = 0;
+
+    for segment in segments {
+        match segment {
+            Segment::Data(mode, data) => {
+                if let Some(index) = find_invalid(*mode, data) {
+                    let (index, byte) = (offset + index, data[index]);
+                    return Err(QRCodeError::InvalidCharacter { index, byte });
+                }
+                offset += data.len();
+            }
+            Segment::Eci(assignment) if *assignment > 999_999 => {
+                return Err(QRCodeError::InvalidEci(*assignment));
+            }
+            Segment::StructuredAppend { index, total, .. }
+                if !(1..=16).contains(total) || index >= total =>
+            {
+                return Err(QRCodeError::InvalidStructuredAppend);
+            }
+            &Segment::Fnc1Second(indicator) if !is_application_indicator(indicator) => {
+                return Err(QRCodeError::InvalidApplicationIndicator(indicator));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks `indicator` is a two-digit number, or a letter + 100
+const fn is_application_indicator(indicator: u8) -> bool {
+    match indicator {
+        0..=99 => true,
+        _ => (indicator - 100).is_ascii_alphabetic(),
+    }
+}
+
+/// Returns the position of the first byte `mode` can't encode
+pub(crate) fn find_invalid(mode: Mode, input: &[u8]) -> Option<usize> {
+    match mode {
+        Mode::Numeric => input.iter().position(|c| !c.is_ascii_digit()),
+        Mode::Alphanumeric => input.iter().position(|&c| !is_qr_alphanumeric(c)),
+        Mode::Byte => None,
+        Mode::Kanji => {
+            let index = input.chunks(2).position(|pair| !is_qr_kanji(pair))? * 2;
+            let lead_valid = matches!(input[index], 0x81..=0x9F | 0xE0..=0xEB);
+
+            // Points to the trailing byte, unless the lead byte is invalid or alone
+            if lead_valid && index + 1 < input.len() {
+                Some(index + 1)
+            } else {
+                Some(index)
+            }
+        }
+    }
+}
+
+/// Returns `input` as a single segment of `mode`. In FNC1 mode, GS bytes (0x1D) of
+/// alphanumeric input are written `%`.
+///
+/// # Errors
+/// - `QRCodeError::InvalidCharacter` if `mode` can't encode a character of `input`
+pub fn forced_segment(input: &[u8], mode: Mode, fnc1: bool) -> Result<Segment, QRCodeError> {
+    if let Some(index) = find_forced_invalid(input, mode, fnc1) {
+        return Err(QRCodeError::InvalidCharacter {
+            index,
+            byte: input[index],
+        });
+    }
+
+    match mode {
+        Mode::Alphanumeric if fnc1 => Ok(Segment::Data(mode, fnc1_alphanumeric(input))),
+        _ => Ok(Segment::Data(mode, input.to_vec())),
+    }
+}
+
+/// Same as `find_invalid`, GS bytes (0x1D) being valid alphanumeric characters in FNC1 mode
+pub(crate) fn find_forced_invalid(input: &[u8], mode: Mode, fnc1: bool) -> Option<usize> {
+    match mode {
+        Mode::Alphanumeric if fnc1 => input
+            .iter()
+            .position(|&c| !is_qr_alphanumeric(c) && c != GS),
+        _ => find_invalid(mode, input),
+    }
+}
+
+/// Finds the smallest `Version` holding `segments` at `ecl`
+pub fn segments_version(segments: &[Segment], ecl: ECL) -> Option<Version> {
+    (Version::V01.size()..=Version::V40.size())
+        .step_by(4)
+        .map(Version::from_n)
+        .find(|&version| segments_bits(segments, version) <= hardcode::data_bits(version, ecl))
+}
+
+/// Encodes the segments according to their mode and version
+pub fn encode(segments: &[Segment], ecl: ECL, version: Version) -> CompactQR {
     let mut compact = CompactQR::from_version(version);
 
+    for segment in segments {
+        encode_segment(&mut compact, segment, version);
+    }
+    finish(&mut compact, ecl, version);
+
+    compact
+}
+
+/// Encodes a single segment according to its mode and version
+pub(crate) fn encode_segment(compact: &mut CompactQR, segment: &Segment, version: Version) {
+    let (mode, input) = match segment {
+        Segment::Data(mode, data) => (*mode, &data[..]),
+        Segment::Eci(assignment) => return encode_eci(compact, *assignment),
+        &Segment::StructuredAppend {
+            index,
+            total,
+            parity,
+        } => return encode_structured_append(compact, index, total, parity),
+        Segment::Fnc1First => return compact.push_bits(0b0101, 4),
+        Segment::Fnc1Second(indicator) => {
+            compact.push_bits(0b1001, 4);
+            return compact.push_bits(*indicator as usize, 8);
+        }
+    };
+    let cci_bits = hardcode::cci_bits(version, mode);
+
     match mode {
-        Mode::Numeric => encode_numeric(&mut compact, input, cci_bits),
-        Mode::Alphanumeric => encode_alphanumeric(&mut compact, input, cci_bits),
-        Mode::Byte => encode_byte(&mut compact, input, cci_bits),
+        Mode::Numeric => encode_numeric(compact, input, cci_bits),
+        Mode::Alphanumeric => encode_alphanumeric(compact, input, cci_bits),
+        Mode::Byte => encode_byte(compact, input, cci_bits),
+        Mode::Kanji => encode_kanji(compact, input, cci_bits),
     };
+}
 
+/// Terminates the data and pads it up to the capacity of `version` at `ecl`
+pub(crate) fn finish(compact: &mut CompactQR, ecl: ECL, version: Version) {
     let data_bits = hardcode::data_bits(version, ecl);
 
-    add_terminator(&mut compact, data_bits);
-    pad_to_8(&mut compact);
+    add_terminator(compact, data_bits);
+    pad_to_8(compact);
     compact.fill();
+}
 
-    compact
+/// Finds the smallest `Version` holding `input` at `ecl`, along with the
+/// segments giving the shortest bitstream for that version.
+///
+/// `prefix` segments (i.e. ECI headers) are placed in front, their bits are accounted for.
+/// With an FNC1 header, GS bytes (0x1D) in `input` are FNC1 separators.
+pub fn best_segments(
+    input: &[u8],
+    ecl: ECL,
+    prefix: &[Segment],
+) -> Option<(Version, Vec<Segment>)> {
+    use Version::{V01, V09, V10, V26, V27, V40};
+
+    // Digits only can't do better than a single numeric segment
+    if prefix.is_empty() && best_encoding(input) == Mode::Numeric {
+        let version = Version::get(Mode::Numeric, ecl, input.len())?;
+        return Some((version, vec![Segment::numeric(input)]));
+    }
+
+    // Character count indicators only change at V10 and V27 (Table 3 of the spec)
+    for (first, last) in [(V01, V09), (V10, V26), (V27, V40)] {
+        let segments = prefixed_segments(input, first, prefix);
+        for n in (first.size()..=last.size()).step_by(4) {
+            let version = Version::from_n(n);
+            if segments_bits(&segments, version) <= hardcode::data_bits(version, ecl) {
+                return Some((version, segments));
+            }
+        }
+    }
+
+    None
+}
+
+/// Same as `optimal_segments`, with `prefix` segments in front
+pub fn prefixed_segments(input: &[u8], version: Version, prefix: &[Segment]) -> Vec<Segment> {
+    let fnc1 = prefix
+        .iter()
+        .any(|segment| matches!(segment, Segment::Fnc1First | Segment::Fnc1Second(_)));
+    let segments = optimal_segments(input, version, fnc1);
+
+    match prefix {
+        [] => segments,
+        _ => prefix.iter().cloned().chain(segments).collect(),
+    }
+}
+
+/// Splits `input` into the segments giving the shortest bitstream for said
+/// `version`, referring to Annex J of the spec.
+///
+/// Numeric and alphanumeric states also track how many characters are left
+/// in the current group (3 digits in 10 bits, 2 characters in 11 bits), so
+/// the computed costs are exact bit counts.
+///
+/// In FNC1 mode (`fnc1` set), GS bytes (0x1D) are also alphanumeric, written `%`,
+/// while a literal `%` is written `%%`.
+pub fn optimal_segments(input: &[u8], version: Version, fnc1: bool) -> Vec<Segment> {
+    /// States: 3 numeric, 2 alphanumeric, byte and kanji
+    const STATES: usize = 7;
+    const MODES: [Mode; STATES] = [
+        Mode::Numeric,
+        Mode::Numeric,
+        Mode::Numeric,
+        Mode::Alphanumeric,
+        Mode::Alphanumeric,
+        Mode::Byte,
+        Mode::Kanji,
+    ];
+    /// Bits added by one more character, for each state
+    const COSTS: [u32; STATES] = [4, 3, 3, 6, 5, 8, 13];
+    /// State reached after one more character, for each state
+    const NEXT: [usize; STATES] = [1, 2, 0, 4, 3, 5, 6];
+    /// State before the first character of a new segment, indexed by `Mode`
+    const FIRST: [usize; 4] = [0, 3, 5, 6];
+    /// Parent of a state starting the very first segment
+    const START: u8 = u8::MAX;
+    /// Parents from this value start a new segment after state `parent - NEW`
+    const NEW: u8 = STATES as u8;
+
+    let n = input.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let width = |state: usize| if MODES[state] == Mode::Kanji { 2 } else { 1 };
+
+    // Valid UTF-8 stays in byte mode, see `best_encoding`
+    let kanji_allowed = core::str::from_utf8(input).is_err();
+
+    let mut costs = vec![[u32::MAX; STATES]; n + 1];
+    let mut parents = vec![[START; STATES]; n + 1];
+
+    for i in 0..n {
+        let (new_parent, new_cost) = if i == 0 {
+            (START, 0)
+        } else {
+            let state = (0..STATES).min_by_key(|&state| costs[i][state]).unwrap();
+            (state as u8 + NEW, costs[i][state])
+        };
+
+        let c = input[i];
+        let allowed = [
+            c.is_ascii_digit(),
+            is_qr_alphanumeric(c) || (fnc1 && c == GS),
+            true,
+            kanji_allowed && i + 1 < n && is_qr_kanji(&input[i..i + 2]),
+        ];
+
+        // A literal `%` takes two alphanumeric characters in FNC1 mode
+        let step = |state: usize| match MODES[state] {
+            Mode::Alphanumeric if fnc1 && c == b'%' => {
+                (COSTS[state] + COSTS[NEXT[state]], NEXT[NEXT[state]])
+            }
+            _ => (COSTS[state], NEXT[state]),
+        };
+
+        // Continue the current segment
+        for state in 0..STATES {
+            if !allowed[MODES[state] as usize] || costs[i][state] == u32::MAX {
+                continue;
+            }
+
+            let (bits, next) = step(state);
+            let next_i = i + width(state);
+            let cost = costs[i][state] + bits;
+            if cost < costs[next_i][next] {
+                costs[next_i][next] = cost;
+                parents[next_i][next] = state as u8;
+            }
+        }
+
+        // Start a new segment
+        for state in FIRST {
+            let mode = MODES[state];
+            if !allowed[mode as usize] {
+                continue;
+            }
+
+            let (bits, next) = step(state);
+            let next_i = i + width(state);
+            let header = 4 + hardcode::cci_bits(version, mode) as u32;
+            let cost = new_cost + header + bits;
+            if cost < costs[next_i][next] {
+                costs[next_i][next] = cost;
+                parents[next_i][next] = new_parent;
+            }
+        }
+    }
+
+    let mut state = (0..STATES).min_by_key(|&state| costs[n][state]).unwrap();
+
+    let mut segments = Vec::new();
+    let mut end = n;
+    let mut i = n;
+
+    loop {
+        let parent = parents[i][state];
+        i -= width(state);
+
+        if parent < NEW {
+            state = parent as usize;
+            continue;
+        }
+
+        let data = match MODES[state] {
+            Mode::Alphanumeric if fnc1 => fnc1_alphanumeric(&input[i..end]),
+            _ => input[i..end].to_vec(),
+        };
+        segments.push(Segment::Data(MODES[state], data));
+        end = i;
+
+        if parent == START {
+            break;
+        }
+        state = (parent - NEW) as usize;
+    }
+
+    segments.reverse();
+    segments
 }
 
-/// Find the best encoding (Numeric -> Alnum -> Byte)
+/// Group separator, standing for FNC1 in the data of FNC1 mode
+pub(crate) const GS: u8 = 0x1D;
+
+/// Writes GS bytes as `%` and literal `%` as `%%`, for alphanumeric segments in FNC1 mode
+pub(crate) fn fnc1_alphanumeric(input: &[u8]) -> Vec<u8> {
+    fnc1_characters(input).collect()
+}
+
+/// Same as `fnc1_alphanumeric`, without collecting the characters
+pub(crate) fn fnc1_characters(input: &[u8]) -> impl Iterator<Item = u8> + Clone + '_ {
+    input.iter().flat_map(|&c| {
+        let first = if c == GS { b'%' } else { c };
+        core::iter::once(first).chain((c == b'%').then(|| b'%'))
+    })
+}
+
+/// Find the best encoding (Numeric -> Alnum -> Kanji -> Byte)
 pub fn best_encoding(input: &[u8]) -> Mode {
     fn try_encode_numeric(input: &[u8], i: usize) -> Mode {
         for &c in input.iter().skip(i) {
@@ -51,17 +547,33 @@ pub fn best_encoding(input: &[u8]) -> Mode {
     fn try_encode_alphanumeric(input: &[u8], i: usize) -> Mode {
         for &c in input.iter().skip(i) {
             if !is_qr_alphanumeric(c) {
-                return Mode::Byte;
+                return try_encode_kanji(input);
             }
         }
         Mode::Alphanumeric
     }
 
+    // Valid UTF-8 is kept in Byte mode: a UTF-8 japanese string can also
+    // look like Shift JIS, and scanners would decode it as such
+    fn try_encode_kanji(input: &[u8]) -> Mode {
+        if core::str::from_utf8(input).is_ok() || !is_qr_kanji(input) {
+            return Mode::Byte;
+        }
+        Mode::Kanji
+    }
+
     try_encode_numeric(input, 0)
 }
 
 /// Encodes numeric strings (i.e. "123456789"), referring to 8.4.2 of the spec.
 pub(crate) fn encode_numeric(compact: &mut CompactQR, input: &[u8], cci_bits: usize) {
+    compact.push_bits(0b0001, 4);
+    compact.push_bits(input.len(), cci_bits);
+    push_numeric(compact, input);
+}
+
+/// Pushes digits, 3 in 10 bits
+fn push_numeric(compact: &mut CompactQR, input: &[u8]) {
     #[derive(Clone, Copy)]
     enum NumericEncoding {
         Single,
@@ -77,9 +589,6 @@ pub(crate) fn encode_numeric(compact: &mut CompactQR, input: &[u8], cci_bits: us
         }
     }
 
-    compact.push_bits(0b0001, 4);
-    compact.push_bits(input.len(), cci_bits);
-
     let mut i = 0;
     let len = input.len() - input.len() % 3;
 
@@ -117,15 +626,17 @@ pub(crate) fn encode_numeric(compact: &mut CompactQR, input: &[u8], cci_bits: us
 pub(crate) fn encode_alphanumeric(compact: &mut CompactQR, input: &[u8], cci_bits: usize) {
     compact.push_bits(0b0010, 4);
     compact.push_bits(input.len(), cci_bits);
+    push_alphanumeric(compact, input.iter().copied());
+}
 
-    let even_size = input.len() - input.len() % 2;
-    for chunk in input.chunks_exact(2) {
-        let a = ascii_to_alphanumeric(chunk[0]);
-        let b = ascii_to_alphanumeric(chunk[1]);
-        compact.push_bits(a * 45 + b, 11);
-    }
-    if even_size != input.len() {
-        compact.push_bits(ascii_to_alphanumeric(*input.last().unwrap()), 6);
+/// Pushes alphanumeric characters, 2 in 11 bits
+pub(crate) fn push_alphanumeric<I: Iterator<Item = u8>>(compact: &mut CompactQR, mut input: I) {
+    while let Some(a) = input.next() {
+        let a = ascii_to_alphanumeric(a);
+        match input.next() {
+            Some(b) => compact.push_bits(a * 45 + ascii_to_alphanumeric(b), 11),
+            None => compact.push_bits(a, 6),
+        }
     }
 }
 
@@ -136,6 +647,50 @@ pub(crate) fn encode_byte(compact: &mut CompactQR, input: &[u8], cci_bits: usize
     compact.push_u8_slice(input);
 }
 
+/// Encodes Shift JIS double-byte strings (i.e. "点茗"), referring to 8.4.5 of the spec.
+pub(crate) fn encode_kanji(compact: &mut CompactQR, input: &[u8], cci_bits: usize) {
+    compact.push_bits(0b1000, 4);
+    compact.push_bits(input.len() / 2, cci_bits);
+    push_kanji(compact, input);
+}
+
+/// Pushes Shift JIS double-byte characters, 13 bits each
+fn push_kanji(compact: &mut CompactQR, input: &[u8]) {
+    for chunk in input.chunks_exact(2) {
+        compact.push_bits(shift_jis_to_kanji(chunk[0], chunk[1]), 13);
+    }
+}
+
+/// Pushes `input` encoded with `mode`, without mode indicator and character count indicator
+pub(crate) fn push_payload(compact: &mut CompactQR, mode: Mode, input: &[u8]) {
+    match mode {
+        Mode::Numeric => push_numeric(compact, input),
+        Mode::Alphanumeric => push_alphanumeric(compact, input.iter().copied()),
+        Mode::Byte => compact.push_u8_slice(input),
+        Mode::Kanji => push_kanji(compact, input),
+    }
+}
+
+/// Encodes an ECI header (i.e. 26 for UTF-8), referring to 8.4.1 of the spec.
+pub(crate) fn encode_eci(compact: &mut CompactQR, assignment: u32) {
+    let assignment = assignment as usize;
+
+    compact.push_bits(0b0111, 4);
+    match assignment {
+        0..=127 => compact.push_bits(assignment, 8),
+        128..=16_383 => compact.push_bits(0b10 << 14 | assignment, 16),
+        _ => compact.push_bits(0b110 << 21 | assignment, 24),
+    }
+}
+
+/// Encodes a Structured Append header, referring to 9 of the spec.
+pub(crate) fn encode_structured_append(compact: &mut CompactQR, index: u8, total: u8, parity: u8) {
+    compact.push_bits(0b0011, 4);
+    compact.push_bits(index as usize, 4);
+    compact.push_bits(total as usize - 1, 4);
+    compact.push_bits(parity as usize, 8);
+}
+
 /// Adds needed terminator padding, terminating the data `BitString`, referring to 8.4.8 of the spec.
 fn add_terminator(compact: &mut CompactQR, data_bits: usize) {
     let len = data_bits - compact.len();
@@ -176,9 +731,37 @@ pub(crate) fn ascii_to_alphanumeric(c: u8) -> usize {
     }
 }
 
+/// Converts a Shift JIS double-byte character to it's 13 bits value \
+/// 0x8140..=0x9FFC: subtract 0x8140, 0xE040..=0xEBBF: subtract 0xC140 \
+/// then `msb * 0xC0 + lsb`, referring to 8.4.5 of the spec.
+const fn shift_jis_to_kanji(msb: u8, lsb: u8) -> usize {
+    let value = ((msb as usize) << 8) | lsb as usize;
+    let value = if value <= 0x9FFC {
+        value - 0x8140
+    } else {
+        value - 0xC140
+    };
+
+    (value >> 8) * 0xC0 + (value & 0xFF)
+}
+
+/// Checks if `input` is only made of Shift JIS double-byte characters
+/// from the ranges 0x8140..=0x9FFC and 0xE040..=0xEBBF, referring to 8.4.5 of the spec.
+pub(crate) fn is_qr_kanji(input: &[u8]) -> bool {
+    if input.is_empty() || input.len() % 2 != 0 {
+        return false;
+    }
+
+    input.chunks_exact(2).all(|chunk| {
+        matches!((chunk[0], chunk[1]),
+            (0x81..=0x9F | 0xE0..=0xEA, 0x40..=0x7E | 0x80..=0xFC)
+            | (0xEB, 0x40..=0x7E | 0x80..=0xBF))
+    })
+}
+
 /// Checks if character c is alphanumeric: 0-9, A-Z, $%*./:+-?.= [space] \
 /// referring to 7.1 of the spec.
-const fn is_qr_alphanumeric(c: u8) -> bool {
+pub(crate) const fn is_qr_alphanumeric(c: u8) -> bool {
     matches!(c,
         b'A'..=b'Z'
         | b'0'..=b'9'
@@ -0,0 +1,386 @@
+//! Creates GS1 element strings, to be encoded in GS1 QR Codes with an FNC1 first position header.
+//!
+//! Each element is an Application Identifier (AI) followed by its value, i.e. `01` for a GTIN
+//! or `17` for an expiration date. Values are checked against the AI syntax (length, character
+//! set, check digit and dates), referring to the GS1 General Specifications.
+//!
+//! # Example
+//! ```rust
+//! use fast_qr::gs1::Gs1Builder;
+//!
+//! let qrcode = Gs1Builder::new()
+//!     .element("01", "09506000134352")
+//!     .element("17", "201231")
+//!     .element("10", "ABC123")
+//!     .qr_builder()
+//!     .unwrap()
+//!     .build();
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Formatter;
+
+use crate::encode::GS;
+use crate::QRBuilder;
+
+/// Contains the errors of an invalid GS1 element string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Gs1Error {
+    /// No element was added
+    Empty,
+    /// The AI is not a known Application Identifier
+    UnknownAi(String),
+    /// The value is too short or too long for its AI
+    InvalidLength(String),
+    /// The value contains a character its AI doesn't allow
+    InvalidCharacter {
+        /// Application Identifier of the element
+        ai: String,
+        /// Position of the character in the value
+        index: usize,
+    },
+    /// The check digit of the value is wrong
+    InvalidCheckDigit(String),
+    /// The value contains an invalid YYMMDD date
+    InvalidDate(String),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Gs1Error {}
+
+impl core::fmt::Display for Gs1Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Gs1Error::Empty => f.write_str("GS1 element string without element"),
+            Gs1Error::UnknownAi(ai) => write!(f, "Unknown Application Identifier ({ai})"),
+            Gs1Error::InvalidLength(ai) => write!(f, "Invalid value length for ({ai})"),
+            Gs1Error::InvalidCharacter { ai, index } => {
+                write!(f, "Invalid character at index {index} for ({ai})")
+            }
+            Gs1Error::InvalidCheckDigit(ai) => write!(f, "Invalid check digit for ({ai})"),
+            Gs1Error::InvalidDate(ai) => write!(f, "Invalid date for ({ai})"),
+        }
+    }
+}
+
+/// Characters allowed in a part of a value
+#[derive(Clone, Copy)]
+enum Charset {
+    /// Digits only
+    Numeric,
+    /// GS1 AI encodable character set 82
+    Cset82,
+}
+
+/// Extra validation of a part of a value
+#[derive(Clone, Copy)]
+enum Rule {
+    None,
+    /// GS1 mod-10 check digit as the last digit
+    CheckDigit,
+    /// YYMMDD date, `DD` can be `00`
+    Date,
+}
+
+/// Part of a value, of `min` to `max` characters
+#[derive(Clone, Copy)]
+struct Part {
+    charset: Charset,
+    min: usize,
+    max: usize,
+    rule: Rule,
+}
+
+/// Fixed length numeric
+const fn n(len: usize) -> Part {
+    Part {
+        charset: Charset::Numeric,
+        min: len,
+        max: len,
+        rule: Rule::None,
+    }
+}
+
+/// Fixed length numeric ending with a check digit
+const fn n_cd(len: usize) -> Part {
+    Part {
+        rule: Rule::CheckDigit,
+        ..n(len)
+    }
+}
+
+/// Numeric, from `min` to `max` digits
+const fn n_var(min: usize, max: usize) -> Part {
+    Part {
+        charset: Charset::Numeric,
+        min,
+        max,
+        rule: Rule::None,
+    }
+}
+
+/// Character set 82, from `min` to `max` characters
+const fn x_var(min: usize, max: usize) -> Part {
+    Part {
+        charset: Charset::Cset82,
+        min,
+        max,
+        rule: Rule::None,
+    }
+}
+
+/// YYMMDD date
+const DATE: Part = Part {
+    rule: Rule::Date,
+    ..n(6)
+};
+
+/// Syntax of the Application Identifiers of `len` digits starting with `prefix`,
+/// followed by one of `next` if not empty
+struct Syntax {
+    prefix: &'static str,
+    next: &'static str,
+    len: usize,
+    parts: &'static [Part],
+}
+
+/// Syntax of the Application Identifiers starting with `prefix`
+const fn ai(prefix: &'static str, len: usize, parts: &'static [Part]) -> Syntax {
+    Syntax {
+        prefix,
+        next: "",
+        len,
+        parts,
+    }
+}
+
+/// Syntax of the Application Identifiers starting with `prefix` then one of `next`
+const fn ais(
+    prefix: &'static str,
+    next: &'static str,
+    len: usize,
+    parts: &'static [Part],
+) -> Syntax {
+    Syntax {
+        prefix,
+        next,
+        len,
+        parts,
+    }
+}
+
+/// Common Application Identifiers, referring to 3.2 of the GS1 General Specifications
+#[rustfmt::skip]
+const SYNTAXES: &[Syntax] = &[
+    ai("00", 2, &[n_cd(18)]),                   // SSCC
+    ai("01", 2, &[n_cd(14)]),                   // GTIN
+    ai("02", 2, &[n_cd(14)]),                   // CONTENT
+    ai("10", 2, &[x_var(1, 20)]),               // BATCH/LOT
+    ai("11", 2, &[DATE]),                       // PROD DATE
+    ai("12", 2, &[DATE]),                       // DUE DATE
+    ai("13", 2, &[DATE]),                       // PACK DATE
+    ai("15", 2, &[DATE]),                       // BEST BEFORE
+    ai("16", 2, &[DATE]),                       // SELL BY
+    ai("17", 2, &[DATE]),                       // USE BY
+    ai("20", 2, &[n(2)]),                       // VARIANT
+    ai("21", 2, &[x_var(1, 20)]),               // SERIAL
+    ai("22", 2, &[x_var(1, 20)]),               // CPV
+    ai("235", 3, &[x_var(1, 28)]),              // TPX
+    ai("240", 3, &[x_var(1, 30)]),              // ADDITIONAL ID
+    ai("241", 3, &[x_var(1, 30)]),              // CUST. PART No.
+    ai("242", 3, &[n_var(1, 6)]),               // MTO VARIANT
+    ai("243", 3, &[x_var(1, 20)]),              // PCN
+    ai("250", 3, &[x_var(1, 30)]),              // SECONDARY SERIAL
+    ai("251", 3, &[x_var(1, 30)]),              // REF. TO SOURCE
+    ai("253", 3, &[n_cd(13), x_var(0, 17)]),    // GDTI
+    ai("254", 3, &[x_var(1, 20)]),              // GLN EXTENSION COMPONENT
+    ai("255", 3, &[n_cd(13), n_var(0, 12)]),    // GCN
+    ai("30", 2, &[n_var(1, 8)]),                // VAR. COUNT
+    ais("31", "0123456", 4, &[n(6)]),           // Trade measures, the last digit
+    ais("32", "0123456789", 4, &[n(6)]),        // is the decimal point position
+    ais("33", "01234567", 4, &[n(6)]),          // Logistic measures
+    ais("34", "0123456789", 4, &[n(6)]),
+    ais("35", "01234567", 4, &[n(6)]),
+    ais("36", "0123456789", 4, &[n(6)]),
+    ai("37", 2, &[n_var(1, 8)]),                // COUNT
+    ai("390", 4, &[n_var(1, 15)]),              // AMOUNT
+    ai("391", 4, &[n(3), n_var(1, 15)]),        // AMOUNT with ISO currency
+    ai("392", 4, &[n_var(1, 15)]),              // PRICE
+    ai("393", 4, &[n(3), n_var(1, 15)]),        // PRICE with ISO currency
+    ai("400", 3, &[x_var(1, 30)]),              // ORDER NUMBER
+    ai("401", 3, &[x_var(1, 30)]),              // GINC
+    ai("402", 3, &[n_cd(17)]),                  // GSIN
+    ai("403", 3, &[x_var(1, 30)]),              // ROUTE
+    ais("41", "01234567", 3, &[n_cd(13)]),      // GLNs (SHIP TO LOC, BILL TO, ...)
+    ai("420", 3, &[x_var(1, 20)]),              // SHIP TO POST
+    ai("421", 3, &[n(3), x_var(1, 9)]),         // SHIP TO POST with ISO country
+    ai("422", 3, &[n(3)]),                      // ORIGIN
+    ai("7003", 4, &[n(10)]),                    // EXPIRY TIME
+    ai("8004", 4, &[x_var(1, 30)]),             // GIAI
+    ai("8006", 4, &[n_cd(14), n(4)]),           // ITIP
+    ai("8017", 4, &[n_cd(18)]),                 // GSRN - PROVIDER
+    ai("8018", 4, &[n_cd(18)]),                 // GSRN - RECIPIENT
+    ai("8020", 4, &[x_var(1, 25)]),             // REF No.
+    ai("90", 2, &[x_var(1, 30)]),               // INTERNAL
+    ais("9", "123456789", 2, &[x_var(1, 90)]),  // INTERNAL
+];
+
+/// AIs whose value has a predefined length, they don't need an FNC1 separator after them,
+/// referring to Figure 7.8.5-2 of the GS1 General Specifications
+const PREDEFINED: [&str; 22] = [
+    "00", "01", "02", "03", "04", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "31",
+    "32", "33", "34", "35", "36", "41",
+];
+
+/// Finds the syntax of said Application Identifier
+fn syntax(ai: &str) -> Option<&'static Syntax> {
+    SYNTAXES.iter().find(|syntax| {
+        ai.len() == syntax.len
+            && ai.bytes().all(|c| c.is_ascii_digit())
+            && ai.starts_with(syntax.prefix)
+            && (syntax.next.is_empty() || syntax.next.contains(&ai[syntax.prefix.len()..][..1]))
+    })
+}
+
+/// Checks the GS1 mod-10 check digit, the last one of `digits`
+fn is_check_digit_valid(digits: &[u8]) -> bool {
+    let (&check, digits) = match digits.split_last() {
+        Some(split) => split,
+        None => return false,
+    };
+
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| u32::from(digit - b'0') * if i % 2 == 0 { 3 } else { 1 })
+        .sum();
+
+    u32::from(check - b'0') == (10 - sum % 10) % 10
+}
+
+/// Checks a YYMMDD date, `DD` being `00` when only the month matters
+fn is_date_valid(date: &[u8]) -> bool {
+    let number = |i: usize| u32::from(date[i] - b'0') * 10 + u32::from(date[i + 1] - b'0');
+    let (year, month, day) = (number(0), number(2), number(4));
+
+    let days = match month {
+        2 if year % 4 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return false,
+    };
+
+    day <= days
+}
+
+/// Checks a character is part of GS1 AI encodable character set 82
+const fn is_cset82(c: u8) -> bool {
+    matches!(c, b'!' | b'"' | b'%'..=b'?' | b'A'..=b'Z' | b'_' | b'a'..=b'z')
+}
+
+/// Checks `value` follows the syntax of `ai`
+fn check_element(ai: &str, value: &str) -> Result<(), Gs1Error> {
+    let syntax = syntax(ai).ok_or_else(|| Gs1Error::UnknownAi(ai.to_owned()))?;
+    let mut rest = value.as_bytes();
+    let mut offset = 0;
+
+    for part in syntax.parts {
+        if rest.len() < part.min {
+            return Err(Gs1Error::InvalidLength(ai.to_owned()));
+        }
+        let (current, next) = rest.split_at(core::cmp::min(rest.len(), part.max));
+
+        let valid = |&c: &u8| match part.charset {
+            Charset::Numeric => c.is_ascii_digit(),
+            Charset::Cset82 => is_cset82(c),
+        };
+        if let Some(index) = current.iter().position(|c| !valid(c)) {
+            return Err(Gs1Error::InvalidCharacter {
+                ai: ai.to_owned(),
+                index: offset + index,
+            });
+        }
+
+        match part.rule {
+            Rule::CheckDigit if !is_check_digit_valid(current) => {
+                return Err(Gs1Error::InvalidCheckDigit(ai.to_owned()));
+            }
+            Rule::Date if !is_date_valid(current) => {
+                return Err(Gs1Error::InvalidDate(ai.to_owned()));
+            }
+            _ => {}
+        }
+
+        offset += current.len();
+        rest = next;
+    }
+
+    match rest {
+        [] => Ok(()),
+        _ => Err(Gs1Error::InvalidLength(ai.to_owned())),
+    }
+}
+
+/// Builder for GS1 element strings
+#[derive(Clone, Debug, Default)]
+pub struct Gs1Builder {
+    elements: Vec<(String, String)>,
+}
+
+impl Gs1Builder {
+    /// Creates an empty element string
+    #[must_use]
+    pub fn new() -> Self {
+        Gs1Builder::default()
+    }
+
+    /// Adds an element, its Application Identifier `ai` (i.e. `"01"`) followed by its `value`
+    pub fn element<A: Into<String>, V: Into<String>>(&mut self, ai: A, value: V) -> &mut Self {
+        self.elements.push((ai.into(), value.into()));
+        self
+    }
+
+    /// Returns the element string, elements without a predefined length being followed by
+    /// a GS byte (0x1D) unless last
+    ///
+    /// # Errors
+    /// - `Gs1Error::Empty` if no element was added
+    /// - Other `Gs1Error` variants if an element doesn't follow its AI syntax
+    pub fn data(&self) -> Result<Vec<u8>, Gs1Error> {
+        if self.elements.is_empty() {
+            return Err(Gs1Error::Empty);
+        }
+
+        let mut data = Vec::new();
+        for (i, (ai, value)) in self.elements.iter().enumerate() {
+            check_element(ai, value)?;
+
+            data.extend_from_slice(ai.as_bytes());
+            data.extend_from_slice(value.as_bytes());
+
+            let predefined = PREDEFINED.contains(&&ai[..2]);
+            if !predefined && i + 1 < self.elements.len() {
+                data.push(GS);
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Returns a [`QRBuilder`] for the element string, with an FNC1 first position header
+    ///
+    /// # Errors
+    /// Same errors as [`Gs1Builder::data`]
+    pub fn qr_builder(&self) -> Result<QRBuilder, Gs1Error> {
+        let mut builder = QRBuilder::new(self.data()?);
+        builder.fnc1_first();
+        Ok(builder)
+    }
+}
@@ -317,6 +317,11 @@ pub const fn cci_bits(version: Version, mode: Mode) -> usize {
             v if (v as usize) >= (V10 as usize) => 16,
             _ => 8,
         },
+        Mode::Kanji => match version {
+            v if (v as usize) >= (V27 as usize) => 12,
+            v if (v as usize) >= (V10 as usize) => 10,
+            _ => 8,
+        },
     }
 }
 
@@ -402,6 +407,81 @@ pub const fn get_polynomial(version: Version, ecl: ECL) -> &'static [u8] {
     }
 }
 
+/// Fetches the Micro QR **format information** of the `symbol` number (0 for M1 to 7 for M4-Q)
+/// and Micro QR `mask` number, referring to Table C.1 of the spec
+pub const fn micro_format_information(symbol: usize, mask: usize) -> u16 {
+    #[rustfmt::skip]
+    const FORMAT: [u16; 32] = [
+        0x4445, 0x4172, 0x4E2B, 0x4B1C, 0x55AE, 0x5099, 0x5FC0, 0x5AF7,
+        0x6793, 0x62A4, 0x6DFD, 0x68CA, 0x7678, 0x734F, 0x7C16, 0x7921,
+        0x06DE, 0x03E9, 0x0CB0, 0x0987, 0x1735, 0x1202, 0x1D5B, 0x186C,
+        0x2508, 0x203F, 0x2F66, 0x2A51, 0x34E3, 0x31D4, 0x3E8D, 0x3BBA,
+    ];
+
+    FORMAT[symbol << 2 | mask]
+}
+
+/// Returns the Micro QR **dividing polynomial** producing `ecc` error correction codewords
+pub const fn micro_polynomial(ecc: usize) -> &'static [u8] {
+    match ecc {
+        2 => &[0, 25, 1],
+        5 => &[0, 113, 164, 166, 119, 10],
+        6 => &[0, 166, 0, 134, 5, 176, 15],
+        8 => &[0, 175, 238, 208, 249, 215, 252, 196, 28],
+        10 => &[0, 251, 67, 46, 61, 118, 70, 64, 94, 32, 45],
+        14 => &[
+            0, 199, 249, 155, 48, 190, 124, 218, 137, 216, 87, 207, 59, 22, 91,
+        ],
+        _ => unreachable!(),
+    }
+}
+
+/// Returns the rMQR **dividing polynomial** producing `ecc` error correction codewords, referring
+/// to Annex A of ISO/IEC 23941
+pub const fn rmqr_polynomial(ecc: usize) -> &'static [u8] {
+    match ecc {
+        7 => &[0, 87, 229, 146, 149, 238, 102, 21],
+        8 => &[0, 175, 238, 208, 249, 215, 252, 196, 28],
+        9 => &[0, 95, 246, 137, 231, 235, 149, 11, 123, 36],
+        10 => &[0, 251, 67, 46, 61, 118, 70, 64, 94, 32, 45],
+        12 => &[0, 102, 43, 98, 121, 187, 113, 198, 143, 131, 87, 157, 66],
+        14 => &[
+            0, 199, 249, 155, 48, 190, 124, 218, 137, 216, 87, 207, 59, 22, 91,
+        ],
+        16 => &[
+            0, 120, 104, 107, 109, 102, 161, 76, 3, 91, 191, 147, 169, 182, 194, 225, 120,
+        ],
+        18 => &[
+            0, 215, 234, 158, 94, 184, 97, 118, 170, 79, 187, 152, 148, 252, 179, 5, 98, 96, 153,
+        ],
+        20 => &[
+            0, 17, 60, 79, 50, 61, 163, 26, 187, 202, 180, 221, 225, 83, 239, 156, 164, 212, 212,
+            188, 190,
+        ],
+        22 => &[
+            0, 210, 171, 247, 242, 93, 230, 14, 109, 221, 53, 200, 74, 8, 172, 98, 80, 219, 134,
+            160, 105, 165, 231,
+        ],
+        24 => &[
+            0, 229, 121, 135, 48, 211, 117, 251, 126, 159, 180, 169, 152, 192, 226, 228, 218, 111,
+            0, 117, 232, 87, 96, 227, 21,
+        ],
+        26 => &[
+            0, 173, 125, 158, 2, 103, 182, 118, 17, 145, 201, 111, 28, 165, 53, 161, 21, 245, 142,
+            13, 102, 48, 227, 153, 145, 218, 70,
+        ],
+        28 => &[
+            0, 168, 223, 200, 104, 224, 234, 108, 180, 110, 190, 195, 147, 205, 27, 232, 201, 21,
+            43, 245, 87, 42, 195, 212, 119, 242, 37, 9, 123,
+        ],
+        30 => &[
+            0, 41, 173, 145, 152, 216, 31, 179, 182, 50, 48, 110, 86, 239, 96, 222, 125, 42, 173,
+            226, 193, 224, 130, 156, 37, 251, 216, 238, 40, 192, 180,
+        ],
+        _ => unreachable!(),
+    }
+}
+
 /// Contains the score for **light/dark module ratio**, referring 8.8.2 (Table 24) of the spec.
 pub const PERCENT_SCORE: [u8; 100] = [
     90, 90, 90, 90, 90, 80, 80, 80, 80, 80, 70, 70, 70, 70, 70, 60, 60, 60, 60, 60, 50, 50, 50, 50,
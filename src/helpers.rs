@@ -2,6 +2,9 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+use alloc::format;
+use alloc::string::String;
+
 use crate::module::Module;
 use crate::QRCode;
 
@@ -43,14 +46,14 @@ pub fn print_matrix_with_margin(qr: &QRCode) -> String {
     out.push_str(&format!("{BOTTOM}\n"));
 
     // Black background
-    for i in (0..qr.size - 1).step_by(2) {
+    for i in (0..qr.height - 1).step_by(2) {
         let line = print_line(&qr[i], &qr[i + 1], qr.size);
         out.push(BLOCK);
         out.push_str(&line);
         out.push_str(&format!("{BLOCK}\n"));
     }
 
-    let line = print_line(&qr[qr.size - 1], &[Module::empty(false); 177], qr.size);
+    let line = print_line(&qr[qr.height - 1], &[Module::empty(false); 177], qr.size);
     out.push(BLOCK);
     out.push_str(&line);
     out.push(BLOCK);
@@ -1,4 +1,5 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![warn(missing_docs)]
 //! # Easy to use fast QRCode generator
 //!
@@ -7,10 +8,8 @@
 //! ## Converts [`QRCode`] to Unicode
 //!
 //! ```rust
-//! # use fast_qr::convert::ConvertError;
 //! use fast_qr::qr::QRBuilder;
 //!
-//! # fn main() -> Result<(), ConvertError> {
 //! // QRBuilder::new can fail if content is too big for version,
 //! // please check before unwrapping.
 //! let qrcode = QRBuilder::new("https://example.com/")
@@ -19,18 +18,18 @@
 //!
 //! let str = qrcode.to_str(); // .print() exists
 //! println!("{}", str);
-//!
-//! #     Ok(())
-//! # }
 //! ```
 //!
 //! ## Converts [`QRCode`] to SVG
 //!
 //! ```rust
+//! # #[cfg(feature = "svg")]
 //! # use fast_qr::convert::ConvertError;
+//! # #[cfg(feature = "svg")]
 //! use fast_qr::convert::{svg::SvgBuilder, Builder, Shape};
 //! use fast_qr::qr::QRBuilder;
 //!
+//! # #[cfg(feature = "svg")]
 //! # fn main() -> Result<(), ConvertError> {
 //! // QRBuilder::new can fail if content is too big for version,
 //! // please check before unwrapping.
@@ -45,15 +44,20 @@
 //!
 //! #     Ok(())
 //! # }
+//! # #[cfg(not(feature = "svg"))]
+//! # fn main() {}
 //! ```
 //!
 //! ## Converts [`QRCode`] to an image
 //!
 //! ```rust
+//! # #[cfg(feature = "image")]
 //! # use fast_qr::convert::ConvertError;
+//! # #[cfg(feature = "image")]
 //! use fast_qr::convert::{image::ImageBuilder, Builder, Shape};
 //! use fast_qr::qr::QRBuilder;
 //!
+//! # #[cfg(feature = "image")]
 //! # fn main() -> Result<(), ConvertError> {
 //! // QRBuilder::new can fail if content is too big for version,
 //! // please check before unwrapping.
@@ -70,31 +74,62 @@
 //!
 //! #     Ok(())
 //! # }
+//! # #[cfg(not(feature = "image"))]
+//! # fn main() {}
 //! ```
+//!
+//! ## `no_std`
+//!
+//! Without the default `std` feature, the crate is `#![no_std]` and only needs `alloc`:
+//! building, decoding matrices and masking are available, while printing, reading images
+//! and converting to SVG or images are not.
+//!
+//! ```toml
+//! fast_qr = { version = "0.11", default-features = false }
+//! ```
+//!
+//! Where heap allocation is not allowed, [`QRBuilder::build_into`] writes the matrix into a
+//! buffer provided by the caller, one bit per module, without allocating.
+
+extern crate alloc;
 
 pub use crate::datamasking::Mask;
 pub use crate::ecl::ECL;
+pub use crate::encode::{Mode, Segment};
+pub use crate::micro::{MicroQRBuilder, MicroVersion};
 pub use crate::module::{Module, ModuleType};
 pub use crate::qr::{QRBuilder, QRCode};
+pub use crate::rmqr::{RMQRBuilder, RMQRVersion};
+pub use crate::score::{MaskEvaluation, MaskPenalty, MaskReport};
 pub use crate::version::Version;
 
+pub mod capacity;
 mod compact;
 #[doc(hidden)]
 pub mod datamasking;
 
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod convert;
+pub mod decode;
 mod default;
 mod ecl;
 mod encode;
+pub mod gs1;
 mod hardcode;
 #[cfg(not(feature = "wasm-bindgen"))]
 mod helpers;
+mod micro;
 mod module;
+mod packed;
+pub mod payload;
 mod placement;
 mod polynomials;
 #[macro_use]
 pub mod qr;
+mod rmqr;
 mod score;
+mod structured_append;
 mod version;
 
 #[cfg(test)]
@@ -0,0 +1,366 @@
+//! Creates Micro QR Code symbols (M1 to M4), referring to the Micro QR parts of the spec.
+//!
+//! Micro QR uses a single finder pattern and only needs a 2 modules quiet zone, which makes it
+//! fit small part markings. Data is encoded in a single segment.
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use crate::compact::CompactQR;
+use crate::datamasking::{self, Mask};
+use crate::encode::{self, Mode};
+use crate::module::{Module, ModuleType};
+use crate::placement::BiRange;
+use crate::qr::QRCodeError;
+use crate::{default, hardcode, polynomials, QRCode, ECL};
+
+/// Enum containing all possible Micro QR versions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MicroVersion {
+    /// Version M1, 11x11 modules, numeric only and error detection only
+    M1 = 0,
+    /// Version M2, 13x13 modules, numeric and alphanumeric, `ECL::L` or `ECL::M`
+    M2 = 1,
+    /// Version M3, 15x15 modules, `ECL::L` or `ECL::M`
+    M3 = 2,
+    /// Version M4, 17x17 modules, `ECL::L`, `ECL::M` or `ECL::Q`
+    M4 = 3,
+}
+
+impl MicroVersion {
+    const ALL: [MicroVersion; 4] = [
+        MicroVersion::M1,
+        MicroVersion::M2,
+        MicroVersion::M3,
+        MicroVersion::M4,
+    ];
+
+    /// Returns the width & height of the symbol, from 11 (M1) to 17 (M4)
+    #[must_use]
+    pub const fn size(self) -> usize {
+        self as usize * 2 + 11
+    }
+
+    /// Returns the number of bits of the mode indicator, referring to Table 2 of the spec
+    const fn mode_bits(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the number of bits of the terminator, referring to Table 2 of the spec
+    const fn terminator_bits(self) -> usize {
+        self as usize * 2 + 3
+    }
+
+    /// Returns the number of bits of the character count indicator, `None` if `mode` is not
+    /// available, referring to Table 3 of the spec
+    const fn cci_bits(self, mode: Mode) -> Option<usize> {
+        let version = self as usize;
+
+        match mode {
+            Mode::Numeric => Some(version + 3),
+            Mode::Alphanumeric if version >= 1 => Some(version + 2),
+            Mode::Byte if version >= 2 => Some(version + 2),
+            Mode::Kanji if version >= 2 => Some(version + 1),
+            _ => None,
+        }
+    }
+
+    /// Returns the symbol number, the number of data bits and of error correction codewords,
+    /// referring to Table 9 of the spec. `None` if `ecl` is not available.
+    ///
+    /// M1 only has error detection, it is used when no `ecl` is asked for.
+    const fn symbol(self, ecl: Option<ECL>) -> Option<(usize, usize, usize)> {
+        match (self, ecl) {
+            (MicroVersion::M1, None) => Some((0, 20, 2)),
+            (MicroVersion::M2, None | Some(ECL::L)) => Some((1, 40, 5)),
+            (MicroVersion::M2, Some(ECL::M)) => Some((2, 32, 6)),
+            (MicroVersion::M3, None | Some(ECL::L)) => Some((3, 84, 6)),
+            (MicroVersion::M3, Some(ECL::M)) => Some((4, 68, 8)),
+            (MicroVersion::M4, None | Some(ECL::L)) => Some((5, 128, 8)),
+            (MicroVersion::M4, Some(ECL::M)) => Some((6, 112, 10)),
+            (MicroVersion::M4, Some(ECL::Q)) => Some((7, 80, 14)),
+            _ => None,
+        }
+    }
+}
+
+/// Micro QR masks, numbered 0 to 3, referring to Table 10 of the spec
+const MASKS: [Mask; 4] = [
+    Mask::HorizontalLines,
+    Mask::LargeCheckerboard,
+    Mask::Diamonds,
+    Mask::Meadow,
+];
+
+/// Creates the empty matrix, with function patterns and format information placeholders
+fn create_matrix(version: MicroVersion) -> QRCode {
+    let size = version.size();
+    let mut qr = QRCode::default(size);
+
+    default::create_finder_pattern(&mut qr, 0, 0);
+
+    for i in 0..=7 {
+        qr[7][i] = Module::empty(Module::LIGHT);
+        qr[i][7] = Module::empty(Module::LIGHT);
+    }
+
+    // Timing patterns run along the top and left edges
+    for i in 8..size {
+        let value = i % 2 == 0;
+        qr[0][i] = Module::timing(value);
+        qr[i][0] = Module::timing(value);
+    }
+
+    for i in 1..=8 {
+        qr[8][i] = Module::format(Module::LIGHT);
+        qr[i][8] = Module::format(Module::LIGHT);
+    }
+
+    qr
+}
+
+/// Places the data on the matrix, by pairs of columns from the bottom right corner
+fn place_on_matrix_data(qr: &mut QRCode, structure: &CompactQR) {
+    let data = structure.get_data();
+
+    let mut rev = true;
+    let mut idx = 0;
+
+    // Column 0 holds the timing pattern
+    for x in (2..qr.size).rev().step_by(2) {
+        let y_range = if rev {
+            BiRange::Backwards((0..qr.size).rev())
+        } else {
+            BiRange::Forward(0..qr.size)
+        };
+
+        for y in y_range {
+            for x in [x, x - 1] {
+                if qr[y][x].module_type() == ModuleType::Data {
+                    let c = data[idx / 8] & (1 << (7 - idx % 8));
+                    idx += 1;
+                    qr[y][x].set(c != 0);
+                }
+            }
+        }
+
+        rev = !rev;
+    }
+
+    #[cfg(debug_assertions)]
+    assert_eq!(idx, structure.len());
+}
+
+/// Sums the dark modules of the right and bottom edges, referring to 7.8.3.2 of the spec.
+/// The greater, the better.
+fn score(qr: &QRCode) -> u32 {
+    let n = qr.size;
+
+    let right = (1..n).filter(|&i| qr[i][n - 1].value()).count();
+    let bottom = (1..n).filter(|&i| qr[n - 1][i].value()).count();
+
+    let (low, high) = if right <= bottom {
+        (right, bottom)
+    } else {
+        (bottom, right)
+    };
+
+    (low * 16 + high) as u32
+}
+
+/// Encodes `input` as a single segment, followed by the terminator and padding, referring to
+/// 7.4 of the spec. In M1 and M3, the last data codeword is only 4 bits long.
+fn encode(
+    input: &[u8],
+    mode: Mode,
+    version: MicroVersion,
+    cci_bits: usize,
+    data_bits: usize,
+) -> CompactQR {
+    let mut compact = CompactQR::with_len(data_bits);
+
+    compact.push_bits(mode as usize, version.mode_bits());
+    compact.push_bits(encode::char_count(mode, input), cci_bits);
+    encode::push_payload(&mut compact, mode, input);
+
+    let terminator = core::cmp::min(version.terminator_bits(), data_bits - compact.len());
+    compact.push_bits(0, terminator);
+
+    let pad = core::cmp::min((8 - compact.len() % 8) % 8, data_bits - compact.len());
+    compact.push_bits(0, pad);
+
+    for &pad in [0b1110_1100, 0b0001_0001].iter().cycle() {
+        if data_bits - compact.len() < 8 {
+            break;
+        }
+        compact.push_u8(pad);
+    }
+    compact.push_bits(0, data_bits - compact.len());
+
+    compact
+}
+
+/// Generates the whole Micro QR matrix for `input`
+fn create(
+    input: &[u8],
+    mode: Mode,
+    version: MicroVersion,
+    ecl: Option<ECL>,
+    mask: Option<usize>,
+) -> QRCode {
+    let (symbol, data_bits, ecc) = version.symbol(ecl).unwrap();
+    let cci_bits = version.cci_bits(mode).unwrap();
+
+    let compact = encode(input, mode, version, cci_bits, data_bits);
+    let data = &compact.get_data()[..(data_bits + 7) / 8];
+
+    let polynomial = hardcode::micro_polynomial(ecc);
+    let division = polynomials::division(data, polynomial);
+    let error = &division[256 - polynomial.len()..255];
+
+    // The 4 bits data codeword of M1 and M3 only has its high nibble placed
+    let mut structure = CompactQR::with_len(data_bits + ecc * 8);
+    structure.push_u8_slice(&data[..data_bits / 8]);
+    if data_bits % 8 != 0 {
+        structure.push_bits(usize::from(data[data_bits / 8] >> 4), 4);
+    }
+    structure.push_u8_slice(error);
+
+    let mut qr = create_matrix(version);
+    place_on_matrix_data(&mut qr, &structure);
+
+    let mask = mask.unwrap_or_else(|| {
+        (0..MASKS.len())
+            .min_by_key(|&i| {
+                let mut copy = qr.clone();
+                datamasking::mask(&mut copy, MASKS[i]);
+                Reverse(score(&copy))
+            })
+            .unwrap()
+    });
+
+    let format_info = hardcode::micro_format_information(symbol, mask);
+    for i in 0..8 {
+        qr[i + 1][8] = Module::format(format_info >> i & 1 != 0);
+        qr[8][i + 1] = Module::format(format_info >> (14 - i) & 1 != 0);
+    }
+    datamasking::mask(&mut qr, MASKS[mask]);
+
+    QRCode {
+        micro: Some(version),
+        ecl: match version {
+            MicroVersion::M1 => None,
+            _ => Some(ecl.unwrap_or(ECL::L)),
+        },
+        mask: Some(MASKS[mask]),
+        mode: Some(mode),
+        ..qr
+    }
+}
+
+/// Builder struct, makes it easier to create a Micro QR [`QRCode`].
+///
+/// The smallest version holding the data is picked, M1 only being used when no `ecl` is
+/// asked for. Micro QR only needs a 2 modules quiet zone, see [`crate::convert::Builder::margin`].
+///
+/// # Example
+/// ```rust
+/// use fast_qr::{MicroQRBuilder, MicroVersion, ECL};
+///
+/// let qr = MicroQRBuilder::new("12345").build().unwrap();
+/// assert_eq!(qr.micro, Some(MicroVersion::M1));
+///
+/// let qr = MicroQRBuilder::new("PART-0042").ecl(ECL::M).build().unwrap();
+/// assert_eq!(qr.micro, Some(MicroVersion::M3));
+/// ```
+pub struct MicroQRBuilder {
+    input: Vec<u8>,
+    ecl: Option<ECL>,
+    version: Option<MicroVersion>,
+    mask: Option<Mask>,
+}
+
+impl MicroQRBuilder {
+    /// Creates an instance of `MicroQRBuilder` with default parameters
+    #[must_use]
+    pub fn new<I: Into<Vec<u8>>>(input: I) -> MicroQRBuilder {
+        MicroQRBuilder {
+            input: input.into(),
+            ecl: None,
+            version: None,
+            mask: None,
+        }
+    }
+
+    /// Forces the Encoding Level, `ECL::H` is not available in Micro QR
+    pub fn ecl(&mut self, ecl: ECL) -> &mut Self {
+        self.ecl = Some(ecl);
+        self
+    }
+
+    /// Forces the version
+    pub fn version(&mut self, version: MicroVersion) -> &mut Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Forces the mask, only `Mask::HorizontalLines`, `Mask::LargeCheckerboard`,
+    /// `Mask::Diamonds` and `Mask::Meadow` are available in Micro QR
+    pub fn mask(&mut self, mask: Mask) -> &mut Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Computes a Micro QR [`QRCode`] with given parameters
+    ///
+    /// # Errors
+    /// - `QRCodeError::EncodedData` if `input` is too large for Micro QR
+    /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small or can't encode
+    ///   the mode of `input`
+    /// - `QRCodeError::UnsupportedEcl` if `ecl` is not available for the version(s)
+    /// - `QRCodeError::UnsupportedMask` if `mask` is not a Micro QR mask
+    pub fn build(&self) -> Result<QRCode, QRCodeError> {
+        let mask = match self.mask {
+            Some(mask) => match MASKS.iter().position(|&m| m as usize == mask as usize) {
+                Some(index) => Some(index),
+                None => return Err(QRCodeError::UnsupportedMask),
+            },
+            None => None,
+        };
+
+        let mode = encode::best_encoding(&self.input);
+        let mut supported = false;
+
+        for version in MicroVersion::ALL {
+            if matches!(self.version, Some(forced) if forced != version) {
+                continue;
+            }
+
+            let data_bits = match version.symbol(self.ecl) {
+                Some((_, data_bits, _)) => data_bits,
+                None => continue,
+            };
+            supported = true;
+
+            let cci_bits = match version.cci_bits(mode) {
+                Some(cci_bits) => cci_bits,
+                None => continue,
+            };
+
+            let count = encode::char_count(mode, &self.input);
+            let bits = version.mode_bits() + cci_bits + encode::payload_bits(mode, &self.input);
+            if count < 1 << cci_bits && bits <= data_bits {
+                return Ok(create(&self.input, mode, version, self.ecl, mask));
+            }
+        }
+
+        match (supported, self.version) {
+            (false, _) => Err(QRCodeError::UnsupportedEcl),
+            (true, Some(_)) => Err(QRCodeError::SpecifiedVersion),
+            (true, None) => Err(QRCodeError::EncodedData),
+        }
+    }
+}
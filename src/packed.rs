@@ -0,0 +1,225 @@
+//! Generates a `QRCode` without allocating, as a packed bit matrix written into a buffer
+//! provided by the caller, see [`crate::QRBuilder::build_into`]
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use crate::compact::CompactQR;
+use crate::datamasking::{self, Mask};
+use crate::default::{self, Canvas};
+use crate::encode::{self, Mode, Segment};
+use crate::module::{Module, ModuleType};
+use crate::qr::QRCodeError;
+use crate::score::{self, MaskEvaluation};
+use crate::{hardcode, placement, polynomials, Version, ECL};
+
+/// Number of bytes of the largest matrix, a V40 of 177 rows of 23 bytes
+pub const MAX_LEN: usize = 177 * stride(177);
+
+/// Returns the number of bytes of a row of `size` modules
+pub const fn stride(size: usize) -> usize {
+    (size + 7) / 8
+}
+
+/// Matrix of one bit per module, the leftmost module of each row in the most significant bit
+struct PackedMatrix<'a> {
+    /// Values of the modules, dark being `1`
+    bits: &'a mut [u8],
+    /// Modules outside of the data, left unmasked
+    reserved: [u8; MAX_LEN],
+    size: usize,
+}
+
+impl<'a> PackedMatrix<'a> {
+    /// Creates a light matrix of `size` modules per side in `bits`
+    fn new(bits: &'a mut [u8], size: usize) -> Self {
+        bits.fill(0);
+
+        PackedMatrix {
+            bits,
+            reserved: [0; MAX_LEN],
+            size,
+        }
+    }
+
+    /// Returns the byte index and the bit mask of the module at row `y`, column `x`
+    const fn position(&self, y: usize, x: usize) -> (usize, u8) {
+        (y * stride(self.size) + x / 8, 0x80 >> (x % 8))
+    }
+}
+
+impl Canvas for PackedMatrix<'_> {
+    fn width(&self) -> usize {
+        self.size
+    }
+
+    /// Modules outside of the data are all `ModuleType::Empty`, telling them apart from data
+    /// being enough to place, mask and score the matrix
+    fn get(&self, y: usize, x: usize) -> Module {
+        let (index, bit) = self.position(y, x);
+        let value = self.bits[index] & bit != 0;
+
+        if self.reserved[index] & bit != 0 {
+            Module::empty(value)
+        } else {
+            Module::data(value)
+        }
+    }
+
+    fn set(&mut self, y: usize, x: usize, module: Module) {
+        let (index, bit) = self.position(y, x);
+
+        if module.value() {
+            self.bits[index] |= bit;
+        } else {
+            self.bits[index] &= !bit;
+        }
+
+        if module.module_type() == ModuleType::Data {
+            self.reserved[index] &= !bit;
+        } else {
+            self.reserved[index] |= bit;
+        }
+    }
+}
+
+/// Data encoded by `build_into`, borrowed from the builder
+pub(crate) struct Payload<'a> {
+    /// ECI and FNC1 headers, placed first
+    pub headers: [Option<&'a Segment>; 2],
+    /// Segments encoded as is, the ones given to [`crate::QRBuilder::segments`]
+    pub segments: &'a [Segment],
+    /// Input encoded as a single segment of said mode
+    pub input: Option<(Mode, &'a [u8])>,
+    /// FNC1 mode, GS bytes (0x1D) of alphanumeric input being written `%`
+    pub fnc1: bool,
+}
+
+impl<'a> Payload<'a> {
+    /// Returns the segments encoded before the input
+    fn segments(&self) -> impl Iterator<Item = &'a Segment> {
+        let segments = self.segments;
+        self.headers.into_iter().flatten().chain(segments)
+    }
+
+    /// Returns the number of characters of the input, as written in the character count
+    /// indicator
+    fn char_count(&self, mode: Mode, input: &[u8]) -> usize {
+        match mode {
+            Mode::Alphanumeric if self.fnc1 => encode::fnc1_characters(input).count(),
+            _ => encode::char_count(mode, input),
+        }
+    }
+
+    /// Checks every segment and the input can be encoded
+    ///
+    /// # Errors
+    /// - Same errors as [`encode::check_segments`]
+    pub fn check(&self) -> Result<(), QRCodeError> {
+        encode::check_segments(self.segments)?;
+        for header in self.headers.iter().flatten() {
+            encode::check_segments(core::slice::from_ref(*header))?;
+        }
+
+        match self.input {
+            Some((mode, input)) => match encode::find_forced_invalid(input, mode, self.fnc1) {
+                Some(index) => Err(QRCodeError::InvalidCharacter {
+                    index,
+                    byte: input[index],
+                }),
+                None => Ok(()),
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the number of bits needed to encode the payload for said `version`
+    pub fn bits(&self, version: Version) -> usize {
+        let segments = self
+            .segments()
+            .map(|segment| segment.bits(version))
+            .sum::<usize>();
+
+        match self.input {
+            Some((mode, input)) => {
+                let len = self.char_count(mode, input);
+                segments + 4 + hardcode::cci_bits(version, mode) + encode::chars_bits(mode, len)
+            }
+            None => segments,
+        }
+    }
+
+    /// Finds the smallest `Version` holding the payload at `ecl`
+    pub fn version(&self, ecl: ECL) -> Option<Version> {
+        (Version::V01.size()..=Version::V40.size())
+            .step_by(4)
+            .map(Version::from_n)
+            .find(|&version| self.bits(version) <= hardcode::data_bits(version, ecl))
+    }
+
+    /// Encodes the payload for said `version`
+    fn encode(&self, compact: &mut CompactQR, version: Version) {
+        for segment in self.segments() {
+            encode::encode_segment(compact, segment, version);
+        }
+
+        let (mode, input) = match self.input {
+            Some(input) => input,
+            None => return,
+        };
+        let cci_bits = hardcode::cci_bits(version, mode);
+
+        match mode {
+            Mode::Numeric => encode::encode_numeric(compact, input, cci_bits),
+            Mode::Alphanumeric if self.fnc1 => {
+                compact.push_bits(0b0010, 4);
+                compact.push_bits(self.char_count(mode, input), cci_bits);
+                encode::push_alphanumeric(compact, encode::fnc1_characters(input));
+            }
+            Mode::Alphanumeric => encode::encode_alphanumeric(compact, input, cci_bits),
+            Mode::Byte => encode::encode_byte(compact, input, cci_bits),
+            Mode::Kanji => encode::encode_kanji(compact, input, cci_bits),
+        }
+    }
+}
+
+/// Returns the most compact single mode holding every character of `input`. In FNC1 mode, GS
+/// bytes (0x1D) are alphanumeric.
+pub(crate) fn single_mode(input: &[u8], fnc1: bool) -> Mode {
+    match encode::best_encoding(input) {
+        Mode::Byte | Mode::Kanji
+            if fnc1 && encode::find_forced_invalid(input, Mode::Alphanumeric, true).is_none() =>
+        {
+            Mode::Alphanumeric
+        }
+        mode => mode,
+    }
+}
+
+/// Writes the symbol of `payload` at `ecl` and `version` into `bits`, of exactly
+/// `stride(size) * size` bytes
+pub(crate) fn generate(
+    bits: &mut [u8],
+    payload: &Payload,
+    ecl: ECL,
+    version: Version,
+    mask: Option<Mask>,
+    evaluation: MaskEvaluation,
+) {
+    let mut compact = CompactQR::from_version(version);
+    payload.encode(&mut compact, version);
+    encode::finish(&mut compact, ecl, version);
+    let structure = polynomials::structure(compact.get_data(), ecl, version);
+
+    let mut qr = PackedMatrix::new(bits, version.size());
+    default::draw_function_patterns(&mut qr, version);
+    placement::place_on_matrix_data(&mut qr, &structure);
+
+    let mask = match mask {
+        Some(mask) => mask,
+        None => score::report_in_place(&mut qr, ecl, evaluation).winner,
+    };
+
+    default::create_matrix_format_info(&mut qr, ecl, mask);
+    datamasking::mask_canvas(&mut qr, mask);
+}
@@ -0,0 +1,170 @@
+//! Bitcoin payment requests, as BIP21 URIs: `BITCOIN:<address>?amount=...`
+//!
+//! The scheme is written in uppercase, as are bech32 addresses (BIP173), so that the address
+//! stays in the alphanumeric mode. Base58 addresses are case-sensitive, written as is.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use super::percent_encoded;
+use crate::QRBuilder;
+
+/// Alphabet of the data part of bech32 addresses, referring to BIP173
+const BECH32: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Contains the errors of an invalid BIP21 payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitcoinError {
+    /// Address neither in base58 nor in bech32, or with mixed case. Checksums are left to
+    /// wallets.
+    InvalidAddress,
+    /// Amount not in bitcoins with at most 8 decimals, from 0.00000001 to 21000000
+    InvalidAmount,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BitcoinError {}
+
+impl Display for BitcoinError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BitcoinError::InvalidAddress => f.write_str("Invalid bitcoin address"),
+            BitcoinError::InvalidAmount => f.write_str("Invalid amount"),
+        }
+    }
+}
+
+/// Returns the address as written in the URI: bech32 addresses in uppercase, base58 ones as
+/// is, or `None` for an invalid address
+fn address(address: &str) -> Option<String> {
+    let lowercase = address.to_ascii_lowercase();
+    let bech32 = ["bc1", "tb1", "bcrt1"]
+        .iter()
+        .find(|hrp| lowercase.starts_with(*hrp))
+        .map(|hrp| &lowercase[hrp.len()..]);
+
+    match bech32 {
+        Some(data) => {
+            let single_case = address == lowercase || address == address.to_ascii_uppercase();
+            let valid = single_case
+                && (14..=90).contains(&address.len())
+                && data.bytes().all(|c| BECH32.contains(&c));
+            Some(address.to_ascii_uppercase()).filter(|_| valid)
+        }
+        None => {
+            let base58 = |c: u8| c.is_ascii_alphanumeric() && !b"0OIl".contains(&c);
+            let valid = (26..=35).contains(&address.len()) && address.bytes().all(base58);
+            Some(String::from(address)).filter(|_| valid)
+        }
+    }
+}
+
+/// Returns true for an amount in bitcoins: up to 8 decimals, from 1 satoshi to 21 million
+fn is_amount(amount: &str) -> bool {
+    let (units, decimals) = amount.split_once('.').unwrap_or((amount, ""));
+
+    let digits = |part: &str| part.bytes().all(|c| c.is_ascii_digit());
+    let valid = (1..=8).contains(&units.len())
+        && digits(units)
+        && decimals.len() <= 8
+        && digits(decimals)
+        && !amount.ends_with('.');
+
+    // Amount in satoshis, 1e-8 bitcoin
+    let satoshis = [units, &format!("{decimals:0<8}")].concat().parse::<u64>();
+    valid && matches!(satoshis, Ok(1..=2_100_000_000_000_000))
+}
+
+/// Builder for BIP21 payment requests: `BITCOIN:<address>?amount=<amount>&label=<label>`
+///
+/// # Example
+/// ```rust
+/// use fast_qr::payload::Bitcoin;
+///
+/// let data = Bitcoin::new("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq")
+///     .amount("0.0005")
+///     .label("Jane Doe")
+///     .data()
+///     .unwrap();
+/// assert_eq!(
+///     data,
+///     "BITCOIN:BC1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZWF5MDQ?amount=0.0005&label=Jane%20Doe"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Bitcoin {
+    address: String,
+    amount: Option<String>,
+    label: Option<String>,
+    message: Option<String>,
+}
+
+impl Bitcoin {
+    /// Creates a payment request to `address`, in base58 or bech32
+    #[must_use]
+    pub fn new<S: Into<String>>(address: S) -> Self {
+        Bitcoin {
+            address: address.into(),
+            amount: None,
+            label: None,
+            message: None,
+        }
+    }
+
+    /// Sets the amount in bitcoins, with `.` as decimal separator (e.g. `"0.0005"`)
+    pub fn amount<S: Into<String>>(&mut self, amount: S) -> &mut Self {
+        self.amount = Some(amount.into());
+        self
+    }
+
+    /// Sets the label, the name of the recipient
+    pub fn label<S: Into<String>>(&mut self, label: S) -> &mut Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Sets the message, describing the payment
+    pub fn message<S: Into<String>>(&mut self, message: S) -> &mut Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Returns the BIP21 URI, the label and message being percent-encoded
+    ///
+    /// # Errors
+    /// - `BitcoinError` if the address or the amount is invalid
+    pub fn data(&self) -> Result<String, BitcoinError> {
+        let address = address(self.address.trim()).ok_or(BitcoinError::InvalidAddress)?;
+        if !self.amount.as_deref().map_or(true, is_amount) {
+            return Err(BitcoinError::InvalidAmount);
+        }
+
+        let mut parameters = Vec::with_capacity(3);
+        if let Some(amount) = &self.amount {
+            parameters.push(format!("amount={amount}"));
+        }
+        if let Some(label) = &self.label {
+            parameters.push(format!("label={}", percent_encoded(label, &[])));
+        }
+        if let Some(message) = &self.message {
+            parameters.push(format!("message={}", percent_encoded(message, &[])));
+        }
+
+        let mut data = format!("BITCOIN:{address}");
+        if !parameters.is_empty() {
+            data.push('?');
+            data.push_str(&parameters.join("&"));
+        }
+        Ok(data)
+    }
+
+    /// Returns a [`QRBuilder`] for the payload
+    ///
+    /// # Errors
+    /// Same errors as [`Bitcoin::data`]
+    pub fn qr_builder(&self) -> Result<QRBuilder, BitcoinError> {
+        Ok(QRBuilder::new(Vec::from(self.data()?)))
+    }
+}
@@ -0,0 +1,370 @@
+//! Contact cards: vCard, referring to RFC 2426 (3.0) and RFC 6350 (4.0), and MeCard
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Write};
+
+use super::{escape, impl_payload, phone_number};
+
+/// Characters escaped with a backslash in vCard text values, newlines being written `\n`
+const VCARD_SPECIAL: [char; 3] = ['\\', ';', ','];
+
+/// Characters escaped with a backslash in MeCard fields
+const MECARD_SPECIAL: [char; 4] = ['\\', ';', ',', ':'];
+
+/// Postal address, its empty fields being left out
+#[derive(Clone, Debug, Default)]
+pub struct Address {
+    /// Street and number
+    pub street: String,
+    /// City
+    pub city: String,
+    /// State or province
+    pub region: String,
+    /// Postal code
+    pub postal_code: String,
+    /// Country name
+    pub country: String,
+}
+
+impl Address {
+    /// Returns the fields, in the order of vCard and MeCard
+    fn fields(&self) -> [&str; 5] {
+        [
+            &self.street,
+            &self.city,
+            &self.region,
+            &self.postal_code,
+            &self.country,
+        ]
+    }
+}
+
+/// Version of a [`VCard`]
+#[derive(Clone, Copy, Debug)]
+pub enum VCardVersion {
+    /// vCard 3.0, RFC 2426, the most widely supported
+    V3,
+    /// vCard 4.0, RFC 6350
+    V4,
+}
+
+impl Default for VCardVersion {
+    fn default() -> Self {
+        VCardVersion::V3
+    }
+}
+
+/// vCard contact payload, lines ending with CRLF
+///
+/// Lines are not folded at 75 characters, scanners reading them whole.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::payload::VCard;
+///
+/// let vcard = VCard::new("Jane Doe")
+///     .name("Doe", "Jane")
+///     .organization("Acme, Inc.")
+///     .phone("+1 (555) 123-4567")
+///     .to_string();
+///
+/// assert_eq!(
+///     vcard,
+///     "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Jane Doe\r\nN:Doe;Jane;;;\r\n\
+///      ORG:Acme\\, Inc.\r\nTEL:+15551234567\r\nEND:VCARD\r\n"
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct VCard {
+    version: VCardVersion,
+    formatted_name: String,
+    name: Option<(String, String)>,
+    organization: Option<String>,
+    title: Option<String>,
+    phones: Vec<String>,
+    emails: Vec<String>,
+    urls: Vec<String>,
+    address: Option<Address>,
+    note: Option<String>,
+}
+
+impl VCard {
+    /// Creates a vCard 3.0 for `formatted_name`, the name as displayed
+    #[must_use]
+    pub fn new<S: Into<String>>(formatted_name: S) -> Self {
+        VCard {
+            formatted_name: formatted_name.into(),
+            ..VCard::default()
+        }
+    }
+
+    /// Sets the version of the vCard
+    pub fn version(&mut self, version: VCardVersion) -> &mut Self {
+        self.version = version;
+        self
+    }
+
+    /// Sets the structured name. vCard 3.0 requires it: without it, `formatted_name` is
+    /// written as the family name.
+    pub fn name<F: Into<String>, G: Into<String>>(&mut self, family: F, given: G) -> &mut Self {
+        self.name = Some((family.into(), given.into()));
+        self
+    }
+
+    /// Sets the organization
+    pub fn organization<S: Into<String>>(&mut self, organization: S) -> &mut Self {
+        self.organization = Some(organization.into());
+        self
+    }
+
+    /// Sets the job title
+    pub fn title<S: Into<String>>(&mut self, title: S) -> &mut Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Adds a phone number, visual separators being dropped
+    pub fn phone<S: Into<String>>(&mut self, phone: S) -> &mut Self {
+        self.phones.push(phone.into());
+        self
+    }
+
+    /// Adds an email address
+    pub fn email<S: Into<String>>(&mut self, email: S) -> &mut Self {
+        self.emails.push(email.into());
+        self
+    }
+
+    /// Adds a website, control characters being dropped
+    pub fn url<S: Into<String>>(&mut self, url: S) -> &mut Self {
+        self.urls.push(url.into());
+        self
+    }
+
+    /// Sets the postal address
+    pub fn address(&mut self, address: Address) -> &mut Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Sets a free-form note
+    pub fn note<S: Into<String>>(&mut self, note: S) -> &mut Self {
+        self.note = Some(note.into());
+        self
+    }
+}
+
+/// Writes a vCard text value, escaping its special characters and newlines
+fn vcard_text(f: &mut Formatter<'_>, text: &str) -> core::fmt::Result {
+    for (i, line) in text.lines().enumerate() {
+        if i > 0 {
+            f.write_str("\\n")?;
+        }
+        escape(f, line, &VCARD_SPECIAL)?;
+    }
+
+    Ok(())
+}
+
+/// Writes a vCard property holding a single text value
+fn vcard_property(f: &mut Formatter<'_>, name: &str, text: &str) -> core::fmt::Result {
+    f.write_str(name)?;
+    f.write_str(":")?;
+    vcard_text(f, text)?;
+    f.write_str("\r\n")
+}
+
+impl Display for VCard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let version = match self.version {
+            VCardVersion::V3 => "3.0",
+            VCardVersion::V4 => "4.0",
+        };
+        write!(f, "BEGIN:VCARD\r\nVERSION:{version}\r\n")?;
+        vcard_property(f, "FN", &self.formatted_name)?;
+
+        let name = match (&self.name, self.version) {
+            (Some((family, given)), _) => Some((&family[..], &given[..])),
+            (None, VCardVersion::V3) => Some((&self.formatted_name[..], "")),
+            (None, VCardVersion::V4) => None,
+        };
+        if let Some((family, given)) = name {
+            f.write_str("N:")?;
+            vcard_text(f, family)?;
+            f.write_str(";")?;
+            vcard_text(f, given)?;
+            f.write_str(";;;\r\n")?;
+        }
+
+        if let Some(organization) = &self.organization {
+            vcard_property(f, "ORG", organization)?;
+        }
+        if let Some(title) = &self.title {
+            vcard_property(f, "TITLE", title)?;
+        }
+
+        for phone in &self.phones {
+            match self.version {
+                VCardVersion::V3 => f.write_str("TEL:")?,
+                VCardVersion::V4 => f.write_str("TEL;VALUE=uri:tel:")?,
+            }
+            phone_number(f, phone)?;
+            f.write_str("\r\n")?;
+        }
+        for email in &self.emails {
+            vcard_property(f, "EMAIL", email)?;
+        }
+        for url in &self.urls {
+            // URIs are not text values, nothing is escaped, but a line break would start a
+            // new property
+            f.write_str("URL:")?;
+            for c in url.chars().filter(|c| !c.is_control()) {
+                f.write_char(c)?;
+            }
+            f.write_str("\r\n")?;
+        }
+
+        if let Some(address) = &self.address {
+            // Post office box and extended address come first, left empty
+            f.write_str("ADR:;;")?;
+            for (i, field) in address.fields().iter().enumerate() {
+                if i > 0 {
+                    f.write_str(";")?;
+                }
+                vcard_text(f, field)?;
+            }
+            f.write_str("\r\n")?;
+        }
+        if let Some(note) = &self.note {
+            vcard_property(f, "NOTE", note)?;
+        }
+
+        f.write_str("END:VCARD\r\n")
+    }
+}
+
+impl_payload!(VCard);
+
+/// MeCard contact payload, a compact alternative to vCard: `MECARD:N:Doe,Jane;TEL:...;;`
+///
+/// # Example
+/// ```rust
+/// use fast_qr::payload::MeCard;
+///
+/// let mecard = MeCard::new("Doe", "Jane").phone("+1 555 123 4567").to_string();
+/// assert_eq!(mecard, "MECARD:N:Doe,Jane;TEL:+15551234567;;");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MeCard {
+    family: String,
+    given: String,
+    phones: Vec<String>,
+    emails: Vec<String>,
+    url: Option<String>,
+    address: Option<Address>,
+    birthday: Option<String>,
+    note: Option<String>,
+}
+
+impl MeCard {
+    /// Creates a MeCard for the `family` and `given` names, `given` can be empty
+    #[must_use]
+    pub fn new<F: Into<String>, G: Into<String>>(family: F, given: G) -> Self {
+        MeCard {
+            family: family.into(),
+            given: given.into(),
+            ..MeCard::default()
+        }
+    }
+
+    /// Adds a phone number, visual separators being dropped
+    pub fn phone<S: Into<String>>(&mut self, phone: S) -> &mut Self {
+        self.phones.push(phone.into());
+        self
+    }
+
+    /// Adds an email address
+    pub fn email<S: Into<String>>(&mut self, email: S) -> &mut Self {
+        self.emails.push(email.into());
+        self
+    }
+
+    /// Sets the website
+    pub fn url<S: Into<String>>(&mut self, url: S) -> &mut Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Sets the postal address
+    pub fn address(&mut self, address: Address) -> &mut Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Sets the birthday, as `YYYYMMDD`
+    pub fn birthday<S: Into<String>>(&mut self, birthday: S) -> &mut Self {
+        self.birthday = Some(birthday.into());
+        self
+    }
+
+    /// Sets a free-form note
+    pub fn note<S: Into<String>>(&mut self, note: S) -> &mut Self {
+        self.note = Some(note.into());
+        self
+    }
+}
+
+/// Writes a MeCard field, escaping its special characters
+fn mecard_field(f: &mut Formatter<'_>, name: &str, text: &str) -> core::fmt::Result {
+    f.write_str(name)?;
+    f.write_str(":")?;
+    escape(f, text, &MECARD_SPECIAL)?;
+    f.write_str(";")
+}
+
+impl Display for MeCard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("MECARD:N:")?;
+        escape(f, &self.family, &MECARD_SPECIAL)?;
+        if !self.given.is_empty() {
+            f.write_str(",")?;
+            escape(f, &self.given, &MECARD_SPECIAL)?;
+        }
+        f.write_str(";")?;
+
+        for phone in &self.phones {
+            f.write_str("TEL:")?;
+            phone_number(f, phone)?;
+            f.write_str(";")?;
+        }
+        for email in &self.emails {
+            mecard_field(f, "EMAIL", email)?;
+        }
+        if let Some(url) = &self.url {
+            mecard_field(f, "URL", url)?;
+        }
+
+        if let Some(address) = &self.address {
+            // Post office box and extended address come first, left empty
+            f.write_str("ADR:,,")?;
+            for (i, field) in address.fields().iter().enumerate() {
+                if i > 0 {
+                    f.write_str(",")?;
+                }
+                escape(f, field, &MECARD_SPECIAL)?;
+            }
+            f.write_str(";")?;
+        }
+        if let Some(birthday) = &self.birthday {
+            mecard_field(f, "BDAY", birthday)?;
+        }
+        if let Some(note) = &self.note {
+            mecard_field(f, "NOTE", note)?;
+        }
+
+        f.write_str(";")
+    }
+}
+
+impl_payload!(MeCard);
@@ -0,0 +1,418 @@
+//! Merchant-presented payments, as EMV QR codes (PIX, PromptPay, ...), referring to the
+//! EMVCo Merchant-Presented Mode specification (version 1.1)
+//!
+//! Each data object is written as a 2 digits ID, a 2 digits length and its value, templates
+//! being data objects whose value holds nested data objects.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use crate::QRBuilder;
+
+/// Maximum number of characters of an EMV QR payload
+pub const EMV_MAX_CHARS: usize = 512;
+
+/// Contains the errors of an invalid or malformed EMV QR payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmvError {
+    /// ID above 99, reserved for the payload format indicator (00) or the CRC (63), or a
+    /// merchant account information template outside of IDs 26 to 51
+    InvalidId(u8),
+    /// Value empty, longer than 99 characters, or not in the format of its ID
+    InvalidValue(u8),
+    /// Mandatory data object missing
+    MissingField(u8),
+    /// No merchant account information, from ID 02 to 51
+    MissingMerchantAccount,
+    /// Payload longer than 512 characters
+    TooLarge(usize),
+    /// Data object cut or with a non numeric ID or length, at the given character
+    Malformed(usize),
+    /// CRC missing or not matching the payload
+    InvalidCrc,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EmvError {}
+
+impl Display for EmvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EmvError::InvalidId(id) => write!(f, "Invalid data object ID {id}"),
+            EmvError::InvalidValue(id) => write!(f, "Invalid value for data object {id:02}"),
+            EmvError::MissingField(id) => write!(f, "Missing data object {id:02}"),
+            EmvError::MissingMerchantAccount => {
+                f.write_str("At least one merchant account information is required")
+            }
+            EmvError::TooLarge(len) => {
+                write!(
+                    f,
+                    "EMV QR payload of {len} characters, at most {EMV_MAX_CHARS} are allowed"
+                )
+            }
+            EmvError::Malformed(index) => write!(f, "Malformed data object at character {index}"),
+            EmvError::InvalidCrc => f.write_str("Missing or invalid CRC"),
+        }
+    }
+}
+
+/// Computes the CRC-16/CCITT-FALSE of `data`: polynomial 0x1021, initial value 0xFFFF
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFF_u16;
+    for &byte in data {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+
+    crc
+}
+
+/// Ordered list of data objects, the content of a template
+///
+/// # Example
+/// ```rust
+/// use fast_qr::payload::EmvTemplate;
+///
+/// let account = EmvTemplate::new()
+///     .field(0, "br.gov.bcb.pix")
+///     .field(1, "jane@example.com")
+///     .to_string();
+/// assert_eq!(account, "0014br.gov.bcb.pix0116jane@example.com");
+///
+/// let parsed = EmvTemplate::parse(&account).unwrap();
+/// assert_eq!(parsed.get(1), Some("jane@example.com"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EmvTemplate {
+    fields: Vec<(u8, String)>,
+    /// Nested templates of `fields`, kept to check their data objects as well
+    templates: Vec<(u8, EmvTemplate)>,
+}
+
+// Nested templates are compared through their encoded value, as parsed templates have none
+impl PartialEq for EmvTemplate {
+    fn eq(&self, other: &Self) -> bool {
+        self.fields == other.fields
+    }
+}
+
+impl Eq for EmvTemplate {}
+
+impl EmvTemplate {
+    /// Creates an empty template
+    #[must_use]
+    pub fn new() -> Self {
+        EmvTemplate::default()
+    }
+
+    /// Sets the data object `id`, from 00 to 99, replacing the previous value
+    pub fn field<S: Into<String>>(&mut self, id: u8, value: S) -> &mut Self {
+        let value = value.into();
+        self.templates.retain(|(template, _)| *template != id);
+
+        match self.fields.iter_mut().find(|(field, _)| *field == id) {
+            Some((_, previous)) => *previous = value,
+            None => self.fields.push((id, value)),
+        }
+        self
+    }
+
+    /// Sets the data object `id` to a nested template, whose errors are reported as well
+    pub fn template(&mut self, id: u8, template: &EmvTemplate) -> &mut Self {
+        self.field(id, template.to_string());
+        self.templates.push((id, template.clone()));
+        self
+    }
+
+    /// Checks the ID and the length of every data object, nested templates first
+    fn check(&self) -> Result<(), EmvError> {
+        for (id, value) in &self.fields {
+            if *id > 99 {
+                return Err(EmvError::InvalidId(*id));
+            }
+            if let Some((_, template)) = self.templates.iter().find(|(nested, _)| nested == id) {
+                template.check()?;
+            }
+            if !(1..=99).contains(&value.chars().count()) {
+                return Err(EmvError::InvalidValue(*id));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the value of the data object `id`, nested templates being encoded
+    #[must_use]
+    pub fn get(&self, id: u8) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| *field == id)
+            .map(|(_, value)| &value[..])
+    }
+
+    /// Returns every data object, in order
+    pub fn fields(&self) -> impl Iterator<Item = (u8, &str)> {
+        self.fields.iter().map(|(id, value)| (*id, &value[..]))
+    }
+
+    /// Reads the data objects of `data`, nested templates being left encoded: parse their
+    /// value again to read them
+    ///
+    /// # Errors
+    /// - `EmvError::Malformed` if a data object is cut or has a non numeric ID or length
+    pub fn parse(data: &str) -> Result<Self, EmvError> {
+        let chars = data.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+        let offset = |index: usize| chars.get(index).copied().unwrap_or(data.len());
+        let number = |index: usize| {
+            let digits = data.get(offset(index)..offset(index + 2))?;
+            match digits.as_bytes() {
+                [tens @ b'0'..=b'9', units @ b'0'..=b'9'] => {
+                    Some((tens - b'0') * 10 + units - b'0')
+                }
+                _ => None,
+            }
+        };
+
+        let mut template = EmvTemplate::new();
+        let mut index = 0;
+        while index < chars.len() {
+            let (id, len) = match (number(index), number(index + 2)) {
+                (Some(id), Some(len)) if index + 4 + usize::from(len) <= chars.len() => {
+                    (id, usize::from(len))
+                }
+                _ => return Err(EmvError::Malformed(index)),
+            };
+
+            let value = &data[offset(index + 4)..offset(index + 4 + len)];
+            template.fields.push((id, String::from(value)));
+            index += 4 + len;
+        }
+
+        Ok(template)
+    }
+}
+
+impl Display for EmvTemplate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for (id, value) in &self.fields {
+            write!(f, "{id:02}{:02}{value}", value.chars().count())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builder for EMV QR codes presented by merchants, read by banking and wallet apps
+///
+/// The payload format indicator (ID 00) comes first and the CRC (ID 63) last, both being
+/// written by [`EmvQr::data`]. The other data objects are sorted by ID.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::payload::{EmvQr, EmvTemplate};
+///
+/// let account = EmvTemplate::new()
+///     .field(0, "br.gov.bcb.pix")
+///     .field(1, "123e4567-e12b-12d1-a456-426655440000")
+///     .clone();
+///
+/// let data = EmvQr::new("Fulano de Tal", "BRASILIA")
+///     .merchant_account(26, &account)
+///     .merchant_category("0000")
+///     .currency("986")
+///     .country("BR")
+///     .additional_data(EmvTemplate::new().field(5, "***"))
+///     .data()
+///     .unwrap();
+/// assert!(data.ends_with("62070503***63041D3D"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EmvQr {
+    root: EmvTemplate,
+    /// IDs set with [`EmvQr::merchant_account`]
+    accounts: Vec<u8>,
+}
+
+impl EmvQr {
+    /// Creates a payload for the merchant `name` (up to 25 characters), in `city` (up to 15
+    /// characters)
+    #[must_use]
+    pub fn new<N: Into<String>, C: Into<String>>(name: N, city: C) -> Self {
+        let mut emv = EmvQr::default();
+        emv.root.field(59, name).field(60, city);
+        emv
+    }
+
+    /// Sets the point of initiation method (ID 01): static codes are reused, dynamic ones
+    /// are generated for a single transaction
+    pub fn dynamic(&mut self, dynamic: bool) -> &mut Self {
+        self.root.field(1, if dynamic { "12" } else { "11" });
+        self
+    }
+
+    /// Sets a merchant account information template, from ID 26 to 51 (IDs 02 to 25 are
+    /// reserved to card networks, set them with [`EmvQr::field`])
+    pub fn merchant_account(&mut self, id: u8, account: &EmvTemplate) -> &mut Self {
+        self.root.template(id, account);
+        self.accounts.push(id);
+        self
+    }
+
+    /// Sets the merchant category code (ID 52), 4 digits of ISO 18245
+    pub fn merchant_category<S: Into<String>>(&mut self, code: S) -> &mut Self {
+        self.root.field(52, code);
+        self
+    }
+
+    /// Sets the transaction currency (ID 53), 3 digits of ISO 4217 (e.g. `"986"` for BRL)
+    pub fn currency<S: Into<String>>(&mut self, code: S) -> &mut Self {
+        self.root.field(53, code);
+        self
+    }
+
+    /// Sets the transaction amount (ID 54), with `.` as decimal separator. Without amount, the
+    /// customer enters it.
+    pub fn amount<S: Into<String>>(&mut self, amount: S) -> &mut Self {
+        self.root.field(54, amount);
+        self
+    }
+
+    /// Sets the country code of the merchant (ID 58), 2 letters of ISO 3166-1
+    pub fn country<S: Into<String>>(&mut self, code: S) -> &mut Self {
+        self.root.field(58, code);
+        self
+    }
+
+    /// Sets the postal code of the merchant (ID 61)
+    pub fn postal_code<S: Into<String>>(&mut self, code: S) -> &mut Self {
+        self.root.field(61, code);
+        self
+    }
+
+    /// Sets the additional data field template (ID 62), e.g. the bill number (ID 01) or
+    /// the reference label (ID 05)
+    pub fn additional_data(&mut self, data: &EmvTemplate) -> &mut Self {
+        self.root.template(62, data);
+        self
+    }
+
+    /// Sets any other data object of the root template
+    pub fn field<S: Into<String>>(&mut self, id: u8, value: S) -> &mut Self {
+        self.root.field(id, value);
+        self.accounts.retain(|account| *account != id);
+        self
+    }
+
+    /// Sets any other template of the root template
+    pub fn template(&mut self, id: u8, template: &EmvTemplate) -> &mut Self {
+        self.root.template(id, template);
+        self.accounts.retain(|account| *account != id);
+        self
+    }
+
+    /// Checks every data object of the root template
+    fn check(&self) -> Result<(), EmvError> {
+        self.root.check()?;
+        if let Some(id) = self.accounts.iter().find(|id| !(26..=51).contains(*id)) {
+            return Err(EmvError::InvalidId(*id));
+        }
+
+        let digits = |value: &str, len: usize| {
+            value.len() == len && value.bytes().all(|c| c.is_ascii_digit())
+        };
+        for (id, value) in self.root.fields() {
+            let valid = match id {
+                0 | 63 => return Err(EmvError::InvalidId(id)),
+                1 => value == "11" || value == "12",
+                52 => digits(value, 4),
+                53 => digits(value, 3),
+                54 => {
+                    value.len() <= 13
+                        && value.bytes().all(|c| c.is_ascii_digit() || c == b'.')
+                        && value.matches('.').count() <= 1
+                        && value.bytes().any(|c| c.is_ascii_digit())
+                }
+                58 => value.len() == 2 && value.bytes().all(|c| c.is_ascii_uppercase()),
+                59 => value.chars().count() <= 25,
+                60 => value.chars().count() <= 15,
+                _ => true,
+            };
+            if !valid {
+                return Err(EmvError::InvalidValue(id));
+            }
+        }
+
+        for id in [52, 53, 58, 59, 60] {
+            if self.root.get(id).is_none() {
+                return Err(EmvError::MissingField(id));
+            }
+        }
+        if !self.root.fields().any(|(id, _)| (2..=51).contains(&id)) {
+            return Err(EmvError::MissingMerchantAccount);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the payload: the payload format indicator, the data objects sorted by ID, then
+    /// the CRC of everything before its value
+    ///
+    /// # Errors
+    /// - `EmvError::TooLarge` if the payload is longer than 512 characters
+    /// - Other `EmvError` variants if a data object is invalid or missing
+    pub fn data(&self) -> Result<String, EmvError> {
+        self.check()?;
+
+        let mut root = self.root.clone();
+        root.fields.sort_by_key(|(id, _)| *id);
+
+        let mut data = format!("000201{root}6304");
+        let crc = crc16(data.as_bytes());
+        data.push_str(&format!("{crc:04X}"));
+
+        let len = data.chars().count();
+        if len > EMV_MAX_CHARS {
+            return Err(EmvError::TooLarge(len));
+        }
+        Ok(data)
+    }
+
+    /// Reads the root template of an EMV QR payload, after checking its CRC
+    ///
+    /// # Errors
+    /// - `EmvError::Malformed` if a data object is cut or has a non numeric ID or length
+    /// - `EmvError::InvalidCrc` if the payload does not end with a matching CRC
+    pub fn parse(data: &str) -> Result<EmvTemplate, EmvError> {
+        let root = EmvTemplate::parse(data)?;
+
+        let crc = match root.fields.last() {
+            // `from_str_radix` also accepts a leading `+`
+            Some((63, crc)) if crc.len() == 4 && crc.bytes().all(|c| c.is_ascii_hexdigit()) => {
+                u16::from_str_radix(crc, 16).ok()
+            }
+            _ => None,
+        };
+        let checked = &data.as_bytes()[..data.len().saturating_sub(4)];
+        if crc != Some(crc16(checked)) {
+            return Err(EmvError::InvalidCrc);
+        }
+
+        Ok(root)
+    }
+
+    /// Returns a [`QRBuilder`] for the payload
+    ///
+    /// # Errors
+    /// Same errors as [`EmvQr::data`]
+    pub fn qr_builder(&self) -> Result<QRBuilder, EmvError> {
+        Ok(QRBuilder::new(Vec::from(self.data()?)))
+    }
+}
@@ -0,0 +1,263 @@
+//! SEPA credit transfers, as EPC QR codes (GiroCode), referring to EPC069-12
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Formatter;
+
+use super::{is_amount, is_creditor_reference, is_iban, is_text, normalize};
+use crate::{QRBuilder, ECL};
+
+/// Maximum number of bytes of an EPC payload
+pub const EPC_MAX_BYTES: usize = 331;
+
+/// Contains the errors of an invalid EPC payload, the first invalid field being reported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpcError {
+    /// Beneficiary name empty, longer than 70 characters or with a control character
+    InvalidName,
+    /// IBAN with an invalid length, character or check digits (mod 97)
+    InvalidIban,
+    /// BIC neither 8 nor 11 characters, or with an invalid bank or country code
+    InvalidBic,
+    /// Amount not in euros with at most 2 decimals, from 0.01 to 999999999.99
+    InvalidAmount,
+    /// Purpose code not made of 4 uppercase letters
+    InvalidPurpose,
+    /// Creditor reference not following ISO 11649 (`RF` and check digits, up to 25 characters)
+    InvalidReference,
+    /// Unstructured remittance information longer than 140 characters or with a control
+    /// character
+    InvalidText,
+    /// Both a creditor reference and unstructured remittance information, only one is allowed
+    BothRemittances,
+    /// Beneficiary to originator information longer than 70 characters or with a control
+    /// character
+    InvalidInformation,
+    /// Payload longer than 331 bytes
+    TooLarge(usize),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EpcError {}
+
+impl core::fmt::Display for EpcError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EpcError::InvalidName => {
+                f.write_str("Beneficiary name must be a line of 1 to 70 characters")
+            }
+            EpcError::InvalidIban => f.write_str("Invalid IBAN"),
+            EpcError::InvalidBic => f.write_str("Invalid BIC"),
+            EpcError::InvalidAmount => f.write_str("Invalid amount"),
+            EpcError::InvalidPurpose => f.write_str("Purpose code must be 4 uppercase letters"),
+            EpcError::InvalidReference => f.write_str("Invalid ISO 11649 creditor reference"),
+            EpcError::InvalidText => {
+                f.write_str("Remittance information must be a line of at most 140 characters")
+            }
+            EpcError::BothRemittances => {
+                f.write_str("Creditor reference and remittance information are exclusive")
+            }
+            EpcError::InvalidInformation => f.write_str(
+                "Beneficiary to originator information must be a line of at most 70 characters",
+            ),
+            EpcError::TooLarge(len) => {
+                write!(
+                    f,
+                    "EPC payload of {len} bytes, at most {EPC_MAX_BYTES} are allowed"
+                )
+            }
+        }
+    }
+}
+
+/// Checks an IBAN: country code, check digits and up to 30 alphanumeric characters
+fn check_iban(iban: &str) -> Result<(), EpcError> {
+    if is_iban(iban) {
+        Ok(())
+    } else {
+        Err(EpcError::InvalidIban)
+    }
+}
+
+/// Checks a BIC: bank and country codes, location and optional branch codes
+fn check_bic(bic: &str) -> Result<(), EpcError> {
+    let bytes = bic.as_bytes();
+    let valid = (bytes.len() == 8 || bytes.len() == 11)
+        && bytes[..6].iter().all(u8::is_ascii_uppercase)
+        && bytes[6..].iter().all(u8::is_ascii_alphanumeric);
+
+    if valid {
+        Ok(())
+    } else {
+        Err(EpcError::InvalidBic)
+    }
+}
+
+/// Builder for EPC QR codes, also known as GiroCode: a SEPA credit transfer read by banking
+/// apps, encoded in UTF-8 with version `002` of the format
+///
+/// # Example
+/// ```rust
+/// use fast_qr::payload::Epc;
+///
+/// let qrcode = Epc::new("Red Cross of Belgium", "BE72 0000 0000 1616")
+///     .bic("BPOTBEB1")
+///     .amount("1")
+///     .text("Urgency fund")
+///     .qr_builder()
+///     .unwrap()
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Epc {
+    name: String,
+    iban: String,
+    bic: Option<String>,
+    amount: Option<String>,
+    purpose: Option<String>,
+    reference: Option<String>,
+    text: Option<String>,
+    information: Option<String>,
+}
+
+impl Epc {
+    /// Creates a transfer to the beneficiary `name`, on `iban`. Spaces of the IBAN are removed.
+    #[must_use]
+    pub fn new<N: Into<String>, I: AsRef<str>>(name: N, iban: I) -> Self {
+        Epc {
+            name: name.into(),
+            iban: normalize(iban.as_ref()),
+            ..Epc::default()
+        }
+    }
+
+    /// Sets the BIC of the beneficiary bank, optional within the EEA
+    pub fn bic<S: AsRef<str>>(&mut self, bic: S) -> &mut Self {
+        self.bic = Some(normalize(bic.as_ref()));
+        self
+    }
+
+    /// Sets the amount in euros, with `.` as decimal separator (e.g. `"12.50"`)
+    pub fn amount<S: Into<String>>(&mut self, amount: S) -> &mut Self {
+        self.amount = Some(amount.into());
+        self
+    }
+
+    /// Sets the purpose of the transfer, an ISO 20022 code (e.g. `"CHAR"` for charity)
+    pub fn purpose<S: Into<String>>(&mut self, purpose: S) -> &mut Self {
+        self.purpose = Some(purpose.into());
+        self
+    }
+
+    /// Sets the ISO 11649 creditor reference (e.g. `"RF18 5390 0754 7034"`). Spaces are
+    /// removed.
+    pub fn reference<S: AsRef<str>>(&mut self, reference: S) -> &mut Self {
+        self.reference = Some(normalize(reference.as_ref()));
+        self
+    }
+
+    /// Sets the unstructured remittance information, up to 140 characters
+    pub fn text<S: Into<String>>(&mut self, text: S) -> &mut Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// Sets the beneficiary to originator information, up to 70 characters
+    pub fn information<S: Into<String>>(&mut self, information: S) -> &mut Self {
+        self.information = Some(information.into());
+        self
+    }
+
+    /// Checks every field
+    fn check(&self) -> Result<(), EpcError> {
+        let optional = |text: &Option<String>, max: usize| {
+            text.as_ref().map_or(true, |text| is_text(text, max))
+        };
+
+        if self.name.trim().is_empty() || !is_text(&self.name, 70) {
+            return Err(EpcError::InvalidName);
+        }
+        check_iban(&self.iban)?;
+        if let Some(bic) = &self.bic {
+            check_bic(bic)?;
+        }
+        if !self.amount.as_deref().map_or(true, is_amount) {
+            return Err(EpcError::InvalidAmount);
+        }
+
+        let purpose = self.purpose.as_ref().map(String::as_bytes);
+        if !purpose.map_or(true, |p| {
+            p.len() == 4 && p.iter().all(u8::is_ascii_uppercase)
+        }) {
+            return Err(EpcError::InvalidPurpose);
+        }
+        if !self
+            .reference
+            .as_deref()
+            .map_or(true, is_creditor_reference)
+        {
+            return Err(EpcError::InvalidReference);
+        }
+        if !optional(&self.text, 140) {
+            return Err(EpcError::InvalidText);
+        }
+        if self.reference.is_some() && self.text.is_some() {
+            return Err(EpcError::BothRemittances);
+        }
+        if !optional(&self.information, 70) {
+            return Err(EpcError::InvalidInformation);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the payload, one field per line, trailing empty fields being left out
+    ///
+    /// # Errors
+    /// - `EpcError::TooLarge` if the payload is longer than 331 bytes
+    /// - Other `EpcError` variants if a field is invalid
+    pub fn data(&self) -> Result<String, EpcError> {
+        self.check()?;
+
+        let amount = self.amount.as_ref().map(|amount| ["EUR", amount].concat());
+        fn field(field: &Option<String>) -> &str {
+            field.as_deref().unwrap_or("").trim()
+        }
+
+        let fields: [&str; 12] = [
+            "BCD",
+            "002",
+            "1",
+            "SCT",
+            field(&self.bic),
+            self.name.trim(),
+            &self.iban,
+            field(&amount),
+            field(&self.purpose),
+            field(&self.reference),
+            field(&self.text),
+            field(&self.information),
+        ];
+
+        let used = fields
+            .iter()
+            .rposition(|field| !field.is_empty())
+            .unwrap_or(0);
+        let data = fields[..=used].join("\n");
+
+        if data.len() > EPC_MAX_BYTES {
+            return Err(EpcError::TooLarge(data.len()));
+        }
+        Ok(data)
+    }
+
+    /// Returns a [`QRBuilder`] for the payload, with the `ECL::M` EPC069-12 requires
+    ///
+    /// # Errors
+    /// Same errors as [`Epc::data`]
+    pub fn qr_builder(&self) -> Result<QRBuilder, EpcError> {
+        let mut builder = QRBuilder::new(Vec::from(self.data()?));
+        builder.ecl(ECL::M);
+        Ok(builder)
+    }
+}
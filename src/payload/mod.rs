@@ -0,0 +1,196 @@
+//! Formats common QR Code payloads: WiFi credentials, contacts, URIs, one-time passwords and
+//! payments, with the escaping their format requires.
+//!
+//! Each payload implements `Display`, converts into the input of [`crate::QRBuilder::new`] and
+//! has a `qr_builder` shortcut. Where scanners allow it, payloads are written in uppercase and
+//! without visual separators, so they fit in the alphanumeric mode (e.g. `TEL:+15551234567`).
+//!
+//! Payment, one-time password and location payloads are validated instead: their `data` and
+//! `qr_builder` return an error for invalid fields.
+//!
+//! # Example
+//! ```rust
+//! use fast_qr::payload::{Wifi, WifiSecurity};
+//!
+//! let qrcode = Wifi::new("Home; 5GHz")
+//!     .security(WifiSecurity::Wpa)
+//!     .password("correct:horse")
+//!     .qr_builder()
+//!     .build();
+//! ```
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use alloc::string::{String, ToString};
+use core::fmt::{Display, Formatter, Write};
+
+mod bitcoin;
+mod contact;
+mod emv;
+mod epc;
+mod otp;
+mod swiss;
+mod uri;
+mod wifi;
+
+pub use bitcoin::{Bitcoin, BitcoinError};
+pub use contact::{Address, MeCard, VCard, VCardVersion};
+pub use emv::{EmvError, EmvQr, EmvTemplate, EMV_MAX_CHARS};
+pub use epc::{Epc, EpcError, EPC_MAX_BYTES};
+pub use otp::{Otp, OtpAlgorithm, OtpError};
+pub use swiss::{
+    SwissAddress, SwissCurrency, SwissQrBill, SwissQrBillError, SWISS_QR_BILL_MAX_CHARS,
+};
+pub use uri::{Geo, GeoError, Mailto, Sms, Tel};
+pub use wifi::{Wifi, WifiSecurity};
+
+/// Implements the conversions shared by every payload, from its `Display`
+macro_rules! impl_payload {
+    ($payload:ty) => {
+        impl From<&$payload> for alloc::vec::Vec<u8> {
+            fn from(payload: &$payload) -> Self {
+                use alloc::string::ToString;
+                payload.to_string().into_bytes()
+            }
+        }
+
+        impl $payload {
+            /// Returns a [`crate::QRBuilder`] for the payload
+            #[must_use]
+            pub fn qr_builder(&self) -> crate::QRBuilder {
+                crate::QRBuilder::new(self)
+            }
+        }
+    };
+}
+pub(crate) use impl_payload;
+
+/// Writes `text`, with a backslash in front of each character of `special`
+fn escape(f: &mut Formatter<'_>, text: &str, special: &[char]) -> core::fmt::Result {
+    for c in text.chars() {
+        if special.contains(&c) {
+            f.write_char('\\')?;
+        }
+        f.write_char(c)?;
+    }
+
+    Ok(())
+}
+
+/// Writes `text` percent-encoded, referring to 2.1 of RFC 3986. Unreserved characters and
+/// the ones of `keep` are written as is, hexadecimal digits are uppercase.
+pub(crate) fn percent_encode(f: &mut Formatter<'_>, text: &str, keep: &[u8]) -> core::fmt::Result {
+    for &byte in text.as_bytes() {
+        let unreserved = byte.is_ascii_alphanumeric() || b"-._~".contains(&byte);
+        if unreserved || keep.contains(&byte) {
+            f.write_char(byte as char)?;
+        } else {
+            write!(f, "%{byte:02X}")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns `text` percent-encoded, see [`percent_encode`]
+fn percent_encoded(text: &str, keep: &[u8]) -> String {
+    struct Encoded<'a>(&'a str, &'a [u8]);
+
+    impl Display for Encoded<'_> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+            percent_encode(f, self.0, self.1)
+        }
+    }
+
+    Encoded(text, keep).to_string()
+}
+
+/// Writes a phone number as the number of a `tel:` URI, referring to RFC 3966: visual
+/// separators (` `, `-`, `.`, `(`, `)`) are dropped and other characters percent-encoded
+fn phone_number(f: &mut Formatter<'_>, number: &str) -> core::fmt::Result {
+    let number = number.trim();
+    let (plus, digits) = match number.strip_prefix('+') {
+        Some(digits) => ("+", digits),
+        None => ("", number),
+    };
+    f.write_str(plus)?;
+
+    for c in digits.chars().filter(|c| !" -.()".contains(*c)) {
+        let mut buffer = [0; 4];
+        percent_encode(f, c.encode_utf8(&mut buffer), b"*")?;
+    }
+
+    Ok(())
+}
+
+/// Checks ISO 7064 mod 97-10 check digits, shared by IBANs and ISO 11649 references: the
+/// first 4 characters are moved to the end, letters count as 10 to 35, the result being 1
+fn mod97(code: &str) -> bool {
+    let (head, tail) = code.split_at(4);
+
+    let mut remainder = 0;
+    for c in tail.chars().chain(head.chars()) {
+        let value = match c.to_digit(36) {
+            Some(value) => value,
+            None => return false,
+        };
+        remainder = if value < 10 {
+            (remainder * 10 + value) % 97
+        } else {
+            (remainder * 100 + value) % 97
+        };
+    }
+
+    remainder == 1
+}
+
+/// Returns `code` without spaces, in uppercase
+fn normalize(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Returns true for a single line of text, up to `max` characters
+fn is_text(text: &str, max: usize) -> bool {
+    text.chars().count() <= max && !text.chars().any(char::is_control)
+}
+
+/// Returns true for an IBAN: country code, check digits and up to 30 alphanumeric characters
+fn is_iban(iban: &str) -> bool {
+    let bytes = iban.as_bytes();
+    (15..=34).contains(&bytes.len())
+        && bytes[..2].iter().all(u8::is_ascii_uppercase)
+        && bytes[2..4].iter().all(u8::is_ascii_digit)
+        && bytes.iter().all(u8::is_ascii_alphanumeric)
+        && mod97(iban)
+}
+
+/// Returns true for an ISO 11649 creditor reference: `RF`, check digits and up to 21
+/// alphanumeric characters
+fn is_creditor_reference(reference: &str) -> bool {
+    let bytes = reference.as_bytes();
+    (5..=25).contains(&bytes.len())
+        && reference.starts_with("RF")
+        && bytes[2..4].iter().all(u8::is_ascii_digit)
+        && bytes.iter().all(u8::is_ascii_alphanumeric)
+        && mod97(reference)
+}
+
+/// Returns true for a nonzero amount: up to 9 digits, then optionally a `.` and 1 or 2
+/// decimals
+fn is_amount(amount: &str) -> bool {
+    let (units, cents) = match amount.split_once('.') {
+        Some((units, cents)) => (units, cents),
+        None => (amount, ""),
+    };
+
+    let digits = |part: &str| part.bytes().all(|c| c.is_ascii_digit());
+    (1..=9).contains(&units.len())
+        && digits(units)
+        && (cents.len() <= 2 && digits(cents))
+        && !amount.ends_with('.')
+        && amount.bytes().any(|c| (b'1'..=b'9').contains(&c))
+}
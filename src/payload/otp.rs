@@ -0,0 +1,233 @@
+//! One-time password enrollment, as `otpauth://` URIs read by authenticator apps, referring to
+//! the Key URI Format of Google Authenticator
+//!
+//! Parameters at their default value are left out, and the secret is written in uppercase
+//! without padding, shrinking the payload. The scheme, type and parameter names stay in
+//! lowercase, as authenticator apps expect them.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use super::percent_encoded;
+use crate::QRBuilder;
+
+/// Characters of a label or an issuer written as is, besides the unreserved ones
+const LABEL: &[u8] = b"@";
+
+/// Contains the errors of an invalid one-time password payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OtpError {
+    /// Secret empty or not in base32 (RFC 4648, `A` to `Z` and `2` to `7`)
+    InvalidSecret,
+    /// Account name empty, or account name or issuer containing a `:`
+    InvalidLabel,
+    /// Number of digits not from 6 to 8
+    InvalidDigits,
+    /// Period of 0 seconds
+    InvalidPeriod,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OtpError {}
+
+impl Display for OtpError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            OtpError::InvalidSecret => f.write_str("Secret must be encoded in base32"),
+            OtpError::InvalidLabel => {
+                f.write_str("Account name must not be empty, neither it nor the issuer contain ':'")
+            }
+            OtpError::InvalidDigits => f.write_str("Number of digits must be from 6 to 8"),
+            OtpError::InvalidPeriod => f.write_str("Period must be at least 1 second"),
+        }
+    }
+}
+
+/// Hash function of a one-time password
+#[derive(Clone, Copy, Debug)]
+pub enum OtpAlgorithm {
+    /// SHA-1, the default, written `SHA1`
+    Sha1,
+    /// SHA-256, written `SHA256`
+    Sha256,
+    /// SHA-512, written `SHA512`
+    Sha512,
+}
+
+impl Default for OtpAlgorithm {
+    fn default() -> Self {
+        OtpAlgorithm::Sha1
+    }
+}
+
+/// Kind of one-time password, with its moving factor
+#[derive(Clone, Copy, Debug)]
+enum Kind {
+    /// Time-based, RFC 6238, with its period in seconds
+    Totp(u32),
+    /// Counter-based, RFC 4226, with its initial counter
+    Hotp(u64),
+}
+
+/// Returns true for a base32 secret without padding, whose length ends on a whole byte
+fn is_base32(secret: &str) -> bool {
+    let alphabet = |c: &u8| c.is_ascii_uppercase() || (b'2'..=b'7').contains(c);
+    !secret.is_empty()
+        && secret.bytes().all(|c| alphabet(&c))
+        && [0, 2, 4, 5, 7].contains(&(secret.len() % 8))
+}
+
+/// Builder for one-time password enrollment: `otpauth://totp/<issuer>:<account>?secret=...`
+///
+/// # Example
+/// ```rust
+/// use fast_qr::payload::Otp;
+///
+/// let data = Otp::totp("jbsw y3dp ehpk 3pxp", "jane@example.com")
+///     .issuer("Acme Co")
+///     .data()
+///     .unwrap();
+/// assert_eq!(
+///     data,
+///     "otpauth://totp/Acme%20Co:jane@example.com?secret=JBSWY3DPEHPK3PXP&issuer=Acme%20Co"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Otp {
+    kind: Kind,
+    secret: String,
+    account: String,
+    issuer: Option<String>,
+    algorithm: OtpAlgorithm,
+    digits: u8,
+}
+
+impl Otp {
+    /// Creates the payload of `secret`, without spaces nor padding
+    fn new(kind: Kind, secret: &str, account: String) -> Self {
+        let secret = secret
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_uppercase())
+            .collect::<String>();
+
+        Otp {
+            kind,
+            secret: String::from(secret.trim_end_matches('=')),
+            account,
+            issuer: None,
+            algorithm: OtpAlgorithm::default(),
+            digits: 6,
+        }
+    }
+
+    /// Creates a time-based one-time password for `account`, with a period of 30 seconds.
+    /// `secret` is encoded in base32, spaces and padding being removed.
+    #[must_use]
+    pub fn totp<S: AsRef<str>, A: Into<String>>(secret: S, account: A) -> Self {
+        Otp::new(Kind::Totp(30), secret.as_ref(), account.into())
+    }
+
+    /// Creates a counter-based one-time password for `account`, starting at `counter`.
+    /// `secret` is encoded in base32, spaces and padding being removed.
+    #[must_use]
+    pub fn hotp<S: AsRef<str>, A: Into<String>>(secret: S, account: A, counter: u64) -> Self {
+        Otp::new(Kind::Hotp(counter), secret.as_ref(), account.into())
+    }
+
+    /// Sets the issuer, the provider of the account, written in the label and as parameter
+    pub fn issuer<S: Into<String>>(&mut self, issuer: S) -> &mut Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Sets the hash function, `OtpAlgorithm::Sha1` by default
+    pub fn algorithm(&mut self, algorithm: OtpAlgorithm) -> &mut Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets the number of digits, from 6 (the default) to 8
+    pub fn digits(&mut self, digits: u8) -> &mut Self {
+        self.digits = digits;
+        self
+    }
+
+    /// Sets the period in seconds, 30 by default, ignored by counter-based passwords
+    pub fn period(&mut self, period: u32) -> &mut Self {
+        if let Kind::Totp(_) = self.kind {
+            self.kind = Kind::Totp(period);
+        }
+        self
+    }
+
+    /// Checks every field
+    fn check(&self) -> Result<(), OtpError> {
+        if !is_base32(&self.secret) {
+            return Err(OtpError::InvalidSecret);
+        }
+
+        let issuer = self.issuer.as_deref().unwrap_or("");
+        if self.account.trim().is_empty() || self.account.contains(':') || issuer.contains(':') {
+            return Err(OtpError::InvalidLabel);
+        }
+        if !(6..=8).contains(&self.digits) {
+            return Err(OtpError::InvalidDigits);
+        }
+        if let Kind::Totp(0) = self.kind {
+            return Err(OtpError::InvalidPeriod);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the `otpauth://` URI, parameters at their default value being left out
+    ///
+    /// # Errors
+    /// - `OtpError` if a field is invalid
+    pub fn data(&self) -> Result<String, OtpError> {
+        self.check()?;
+
+        let (kind, factor) = match self.kind {
+            Kind::Totp(30) => ("totp", None),
+            Kind::Totp(period) => ("totp", Some(format!("period={period}"))),
+            Kind::Hotp(counter) => ("hotp", Some(format!("counter={counter}"))),
+        };
+
+        let mut data = format!("otpauth://{kind}/");
+        if let Some(issuer) = &self.issuer {
+            data.push_str(&percent_encoded(issuer, LABEL));
+            data.push(':');
+        }
+        data.push_str(&percent_encoded(&self.account, LABEL));
+
+        let mut parameters = Vec::with_capacity(5);
+        parameters.push(format!("secret={}", self.secret));
+        if let Some(issuer) = &self.issuer {
+            parameters.push(format!("issuer={}", percent_encoded(issuer, LABEL)));
+        }
+        match self.algorithm {
+            OtpAlgorithm::Sha1 => {}
+            OtpAlgorithm::Sha256 => parameters.push(String::from("algorithm=SHA256")),
+            OtpAlgorithm::Sha512 => parameters.push(String::from("algorithm=SHA512")),
+        }
+        if self.digits != 6 {
+            parameters.push(format!("digits={}", self.digits));
+        }
+        parameters.extend(factor);
+
+        data.push('?');
+        data.push_str(&parameters.join("&"));
+        Ok(data)
+    }
+
+    /// Returns a [`QRBuilder`] for the payload
+    ///
+    /// # Errors
+    /// Same errors as [`Otp::data`]
+    pub fn qr_builder(&self) -> Result<QRBuilder, OtpError> {
+        Ok(QRBuilder::new(Vec::from(self.data()?)))
+    }
+}
@@ -0,0 +1,348 @@
+//! Swiss QR-bill payments, as SPC payloads, referring to the Swiss Implementation Guidelines
+//! for the QR-bill (version 2.3)
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Formatter;
+
+use super::{is_amount, is_creditor_reference, is_iban, is_text, normalize};
+use crate::{QRBuilder, ECL};
+
+/// Maximum number of characters of an SPC payload
+pub const SWISS_QR_BILL_MAX_CHARS: usize = 997;
+
+/// Contains the errors of an invalid QR-bill, the first invalid field being reported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwissQrBillError {
+    /// Not a Swiss or Liechtenstein IBAN, or with invalid check digits (mod 97)
+    InvalidIban,
+    /// Creditor address with a missing, too long or invalid field
+    InvalidCreditor,
+    /// Debtor address with a missing, too long or invalid field
+    InvalidDebtor,
+    /// Amount not with at most 2 decimals, from 0.01 to 999999999.99
+    InvalidAmount,
+    /// QR reference not made of 27 digits with a valid check digit (mod 10 recursive), or
+    /// creditor reference not following ISO 11649
+    InvalidReference,
+    /// QR-IBAN without QR reference, or QR reference without QR-IBAN
+    ReferenceMismatch,
+    /// Unstructured message and billing information longer than 140 characters together
+    InvalidMessage,
+    /// Payload longer than 997 characters
+    TooLarge(usize),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SwissQrBillError {}
+
+impl core::fmt::Display for SwissQrBillError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SwissQrBillError::InvalidIban => f.write_str("IBAN must be a valid CH or LI IBAN"),
+            SwissQrBillError::InvalidCreditor => f.write_str("Invalid creditor address"),
+            SwissQrBillError::InvalidDebtor => f.write_str("Invalid debtor address"),
+            SwissQrBillError::InvalidAmount => f.write_str("Invalid amount"),
+            SwissQrBillError::InvalidReference => f.write_str("Invalid QR or creditor reference"),
+            SwissQrBillError::ReferenceMismatch => {
+                f.write_str("QR references must be used with QR-IBANs, and only with them")
+            }
+            SwissQrBillError::InvalidMessage => {
+                f.write_str("Message and billing information must be at most 140 characters")
+            }
+            SwissQrBillError::TooLarge(len) => {
+                write!(
+                    f,
+                    "SPC payload of {len} characters, at most {SWISS_QR_BILL_MAX_CHARS} are allowed"
+                )
+            }
+        }
+    }
+}
+
+/// Returns true for a QR-IBAN, whose institution identification ranges from 30000 to 31999
+fn is_qr_iban(iban: &str) -> bool {
+    matches!(iban[4..9].parse::<u32>(), Ok(30000..=31999))
+}
+
+/// Returns true for a QR reference: 26 digits and a mod 10 recursive check digit
+fn is_qr_reference(reference: &str) -> bool {
+    const TABLE: [u32; 10] = [0, 9, 4, 6, 8, 2, 7, 1, 3, 5];
+
+    if reference.len() != 27 || !reference.bytes().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+
+    let carry = reference[..26].bytes().fold(0, |carry, c| {
+        TABLE[((carry + u32::from(c - b'0')) % 10) as usize]
+    });
+    (10 - carry) % 10 == u32::from(reference.as_bytes()[26] - b'0')
+}
+
+/// Structured address of a QR-bill, combined addresses are not allowed anymore
+#[derive(Clone, Debug, Default)]
+pub struct SwissAddress {
+    /// Name or company, up to 70 characters
+    pub name: String,
+    /// Street, up to 70 characters, optional
+    pub street: String,
+    /// Building number, up to 16 characters, optional
+    pub building_number: String,
+    /// Postal code, up to 16 characters, without country prefix
+    pub postal_code: String,
+    /// Town, up to 35 characters
+    pub town: String,
+    /// Two-letter country code, ISO 3166-1 (e.g. `CH`)
+    pub country: String,
+}
+
+impl SwissAddress {
+    /// Returns true if every mandatory field is set and no field is too long
+    fn is_valid(&self) -> bool {
+        let country = self.country.as_bytes();
+        !self.name.trim().is_empty()
+            && is_text(&self.name, 70)
+            && is_text(&self.street, 70)
+            && is_text(&self.building_number, 16)
+            && !self.postal_code.trim().is_empty()
+            && is_text(&self.postal_code, 16)
+            && !self.town.trim().is_empty()
+            && is_text(&self.town, 35)
+            && country.len() == 2
+            && country.iter().all(u8::is_ascii_uppercase)
+    }
+
+    /// Returns the 7 lines of the address, `S` standing for structured
+    fn fields(&self) -> [&str; 7] {
+        [
+            "S",
+            self.name.trim(),
+            self.street.trim(),
+            self.building_number.trim(),
+            self.postal_code.trim(),
+            self.town.trim(),
+            &self.country,
+        ]
+    }
+}
+
+/// Currency of a [`SwissQrBill`]
+#[derive(Clone, Copy, Debug)]
+pub enum SwissCurrency {
+    /// Swiss francs, written `CHF`
+    Chf,
+    /// Euros, written `EUR`
+    Eur,
+}
+
+impl Default for SwissCurrency {
+    fn default() -> Self {
+        SwissCurrency::Chf
+    }
+}
+
+/// Reference of a [`SwissQrBill`]
+#[derive(Clone, Debug)]
+enum Reference {
+    /// QR reference, only with a QR-IBAN
+    Qr(String),
+    /// ISO 11649 creditor reference
+    Creditor(String),
+}
+
+/// Builder for Swiss QR-bills: a payment read by Swiss banking apps, encoded in UTF-8 with
+/// version `0200` of the SPC format
+///
+/// The QR Code is printed 46x46 mm with a 7x7 mm Swiss cross in its center, see
+/// `SvgBuilder::swiss_cross` with the `svg` feature.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::payload::{SwissAddress, SwissQrBill};
+///
+/// let creditor = SwissAddress {
+///     name: String::from("Robert Schneider AG"),
+///     street: String::from("Rue du Lac"),
+///     building_number: String::from("1268"),
+///     postal_code: String::from("2501"),
+///     town: String::from("Biel"),
+///     country: String::from("CH"),
+/// };
+///
+/// let qrcode = SwissQrBill::new("CH44 3199 9123 0008 8901 2", creditor)
+///     .amount("1949.75")
+///     .qr_reference("21 00000 00003 13947 14300 09017")
+///     .qr_builder()
+///     .unwrap()
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SwissQrBill {
+    iban: String,
+    creditor: SwissAddress,
+    amount: Option<String>,
+    currency: SwissCurrency,
+    debtor: Option<SwissAddress>,
+    reference: Option<Reference>,
+    message: Option<String>,
+    billing_information: Option<String>,
+}
+
+impl SwissQrBill {
+    /// Creates a bill in Swiss francs to `creditor`, on `iban`. Spaces of the IBAN are removed.
+    #[must_use]
+    pub fn new<I: AsRef<str>>(iban: I, creditor: SwissAddress) -> Self {
+        SwissQrBill {
+            iban: normalize(iban.as_ref()),
+            creditor,
+            ..SwissQrBill::default()
+        }
+    }
+
+    /// Sets the amount, with `.` as decimal separator (e.g. `"12.5"`). Without amount, the
+    /// debtor fills it in.
+    pub fn amount<S: Into<String>>(&mut self, amount: S) -> &mut Self {
+        self.amount = Some(amount.into());
+        self
+    }
+
+    /// Sets the currency
+    pub fn currency(&mut self, currency: SwissCurrency) -> &mut Self {
+        self.currency = currency;
+        self
+    }
+
+    /// Sets the debtor. Without debtor, the debtor fills it in.
+    pub fn debtor(&mut self, debtor: SwissAddress) -> &mut Self {
+        self.debtor = Some(debtor);
+        self
+    }
+
+    /// Sets the QR reference, required by QR-IBANs (e.g. `"21 00000 00003 13947 14300 09017"`).
+    /// Spaces are removed.
+    pub fn qr_reference<S: AsRef<str>>(&mut self, reference: S) -> &mut Self {
+        self.reference = Some(Reference::Qr(normalize(reference.as_ref())));
+        self
+    }
+
+    /// Sets the ISO 11649 creditor reference (e.g. `"RF18 5390 0754 7034"`), not allowed with
+    /// QR-IBANs. Spaces are removed.
+    pub fn creditor_reference<S: AsRef<str>>(&mut self, reference: S) -> &mut Self {
+        self.reference = Some(Reference::Creditor(normalize(reference.as_ref())));
+        self
+    }
+
+    /// Sets the unstructured message
+    pub fn message<S: Into<String>>(&mut self, message: S) -> &mut Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Sets the billing information, a structured message for the debtor's software (e.g.
+    /// `"//S1/10/10201409/11/190512"`)
+    pub fn billing_information<S: Into<String>>(&mut self, information: S) -> &mut Self {
+        self.billing_information = Some(information.into());
+        self
+    }
+
+    /// Checks every field
+    fn check(&self) -> Result<(), SwissQrBillError> {
+        let swiss = self.iban.starts_with("CH") || self.iban.starts_with("LI");
+        if !(swiss && self.iban.len() == 21 && is_iban(&self.iban)) {
+            return Err(SwissQrBillError::InvalidIban);
+        }
+        if !self.creditor.is_valid() {
+            return Err(SwissQrBillError::InvalidCreditor);
+        }
+        if !self.amount.as_deref().map_or(true, is_amount) {
+            return Err(SwissQrBillError::InvalidAmount);
+        }
+        if !self.debtor.as_ref().map_or(true, SwissAddress::is_valid) {
+            return Err(SwissQrBillError::InvalidDebtor);
+        }
+
+        let qr_reference = match &self.reference {
+            Some(Reference::Qr(reference)) if !is_qr_reference(reference) => {
+                return Err(SwissQrBillError::InvalidReference)
+            }
+            Some(Reference::Creditor(reference)) if !is_creditor_reference(reference) => {
+                return Err(SwissQrBillError::InvalidReference)
+            }
+            Some(Reference::Qr(_)) => true,
+            _ => false,
+        };
+        if qr_reference != is_qr_iban(&self.iban) {
+            return Err(SwissQrBillError::ReferenceMismatch);
+        }
+
+        let message = self.message.as_deref().unwrap_or("");
+        let information = self.billing_information.as_deref().unwrap_or("");
+        let length = message.chars().count() + information.chars().count();
+        if length > 140 || !is_text(message, 140) || !is_text(information, 140) {
+            return Err(SwissQrBillError::InvalidMessage);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the amount with exactly 2 decimals, as the SPC format requires
+    fn formatted_amount(&self) -> Option<String> {
+        let amount = self.amount.as_deref()?;
+        let (units, cents) = amount.split_once('.').unwrap_or((amount, ""));
+
+        let units = units.trim_start_matches('0');
+        let units = if units.is_empty() { "0" } else { units };
+        Some([units, ".", cents, &"00"[cents.len()..]].concat())
+    }
+
+    /// Returns the payload, one element per line, closed by the `EPD` trailer
+    ///
+    /// # Errors
+    /// - `SwissQrBillError::TooLarge` if the payload is longer than 997 characters
+    /// - Other `SwissQrBillError` variants if a field is invalid
+    pub fn data(&self) -> Result<String, SwissQrBillError> {
+        self.check()?;
+
+        let amount = self.formatted_amount();
+        let currency = match self.currency {
+            SwissCurrency::Chf => "CHF",
+            SwissCurrency::Eur => "EUR",
+        };
+        let debtor = self.debtor.as_ref().map_or([""; 7], SwissAddress::fields);
+        let (kind, reference) = match &self.reference {
+            Some(Reference::Qr(reference)) => ("QRR", &reference[..]),
+            Some(Reference::Creditor(reference)) => ("SCOR", &reference[..]),
+            None => ("NON", ""),
+        };
+
+        let mut fields = Vec::with_capacity(32);
+        fields.extend(["SPC", "0200", "1", &self.iban]);
+        fields.extend(self.creditor.fields());
+        // The ultimate creditor is reserved for future use, left empty
+        fields.extend([""; 7]);
+        fields.extend([amount.as_deref().unwrap_or(""), currency]);
+        fields.extend(debtor);
+        fields.extend([kind, reference]);
+        fields.extend([self.message.as_deref().unwrap_or("").trim(), "EPD"]);
+        if let Some(information) = &self.billing_information {
+            fields.push(information.trim());
+        }
+
+        let data = fields.join("\n");
+        let len = data.chars().count();
+        if len > SWISS_QR_BILL_MAX_CHARS {
+            return Err(SwissQrBillError::TooLarge(len));
+        }
+        Ok(data)
+    }
+
+    /// Returns a [`QRBuilder`] for the payload, with the `ECL::M` the QR-bill requires
+    ///
+    /// # Errors
+    /// Same errors as [`SwissQrBill::data`]
+    pub fn qr_builder(&self) -> Result<QRBuilder, SwissQrBillError> {
+        let mut builder = QRBuilder::new(Vec::from(self.data()?));
+        builder.ecl(ECL::M);
+        Ok(builder)
+    }
+}
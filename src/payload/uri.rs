@@ -0,0 +1,271 @@
+//! URI payloads: `geo:`, `SMSTO:`, `mailto:` and `tel:`
+//!
+//! Schemes are case-insensitive and written in uppercase, so that alphanumeric content stays
+//! in the alphanumeric mode.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+use super::{impl_payload, percent_encode, phone_number};
+use crate::QRBuilder;
+
+/// Characters of an email address written as is in a `mailto:` URI, referring to RFC 6068
+const ADDRESS: &[u8] = b"@!$'()*+,;=";
+
+/// Contains the errors of an invalid location
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeoError {
+    /// Latitude not a number from -90 to 90 degrees
+    InvalidLatitude,
+    /// Longitude not a number from -180 to 180 degrees
+    InvalidLongitude,
+    /// Altitude not a finite number
+    InvalidAltitude,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GeoError {}
+
+impl Display for GeoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            GeoError::InvalidLatitude => f.write_str("Latitude must be from -90 to 90 degrees"),
+            GeoError::InvalidLongitude => f.write_str("Longitude must be from -180 to 180 degrees"),
+            GeoError::InvalidAltitude => f.write_str("Altitude must be a finite number"),
+        }
+    }
+}
+
+/// Geographic location, referring to RFC 5870: `GEO:<latitude>,<longitude>`
+///
+/// # Example
+/// ```rust
+/// use fast_qr::payload::Geo;
+///
+/// let data = Geo::new(48.8584, 2.2945).data().unwrap();
+/// assert_eq!(data, "GEO:48.8584,2.2945");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Geo {
+    latitude: f64,
+    longitude: f64,
+    altitude: Option<f64>,
+}
+
+impl Geo {
+    /// Creates a location, `latitude` from -90 to 90 and `longitude` from -180 to 180 degrees
+    #[must_use]
+    pub const fn new(latitude: f64, longitude: f64) -> Self {
+        Geo {
+            latitude,
+            longitude,
+            altitude: None,
+        }
+    }
+
+    /// Sets the altitude, in meters
+    pub fn altitude(&mut self, altitude: f64) -> &mut Self {
+        self.altitude = Some(altitude);
+        self
+    }
+
+    /// Returns the `GEO:` URI
+    ///
+    /// # Errors
+    /// - `GeoError` if a coordinate is out of range or not a finite number
+    pub fn data(&self) -> Result<String, GeoError> {
+        // Comparisons are false for NaN
+        if !(-90f64..=90f64).contains(&self.latitude) {
+            return Err(GeoError::InvalidLatitude);
+        }
+        if !(-180f64..=180f64).contains(&self.longitude) {
+            return Err(GeoError::InvalidLongitude);
+        }
+
+        match self.altitude {
+            Some(altitude) if !altitude.is_finite() => Err(GeoError::InvalidAltitude),
+            Some(altitude) => Ok(format!(
+                "GEO:{},{},{altitude}",
+                self.latitude, self.longitude
+            )),
+            None => Ok(format!("GEO:{},{}", self.latitude, self.longitude)),
+        }
+    }
+
+    /// Returns a [`QRBuilder`] for the payload
+    ///
+    /// # Errors
+    /// Same errors as [`Geo::data`]
+    pub fn qr_builder(&self) -> Result<QRBuilder, GeoError> {
+        Ok(QRBuilder::new(Vec::from(self.data()?)))
+    }
+}
+
+/// Text message: `SMSTO:<number>:<message>`
+///
+/// # Example
+/// ```rust
+/// use fast_qr::payload::Sms;
+///
+/// let sms = Sms::new("+1 555-123-4567").message("See you: 8pm").to_string();
+/// assert_eq!(sms, "SMSTO:+15551234567:See you: 8pm");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sms {
+    number: String,
+    message: Option<String>,
+}
+
+impl Sms {
+    /// Creates a message to `number`, visual separators being dropped
+    #[must_use]
+    pub fn new<S: Into<String>>(number: S) -> Self {
+        Sms {
+            number: number.into(),
+            message: None,
+        }
+    }
+
+    /// Sets the message, written as is: only the first `:` separates it from the number
+    pub fn message<S: Into<String>>(&mut self, message: S) -> &mut Self {
+        self.message = Some(message.into());
+        self
+    }
+}
+
+impl Display for Sms {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("SMSTO:")?;
+        phone_number(f, &self.number)?;
+        match &self.message {
+            Some(message) => write!(f, ":{message}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl_payload!(Sms);
+
+/// Email, referring to RFC 6068: `MAILTO:<address>?subject=...&body=...`
+///
+/// # Example
+/// ```rust
+/// use fast_qr::payload::Mailto;
+///
+/// let mailto = Mailto::new("jane@example.com").subject("Hello & welcome").to_string();
+/// assert_eq!(mailto, "MAILTO:jane@example.com?subject=Hello%20%26%20welcome");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Mailto {
+    address: String,
+    cc: Vec<String>,
+    subject: Option<String>,
+    body: Option<String>,
+}
+
+impl Mailto {
+    /// Creates an email to `address`
+    #[must_use]
+    pub fn new<S: Into<String>>(address: S) -> Self {
+        Mailto {
+            address: address.into(),
+            cc: Vec::new(),
+            subject: None,
+            body: None,
+        }
+    }
+
+    /// Adds a carbon copy recipient
+    pub fn cc<S: Into<String>>(&mut self, address: S) -> &mut Self {
+        self.cc.push(address.into());
+        self
+    }
+
+    /// Sets the subject
+    pub fn subject<S: Into<String>>(&mut self, subject: S) -> &mut Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Sets the body, newlines being written as CRLF
+    pub fn body<S: Into<String>>(&mut self, body: S) -> &mut Self {
+        self.body = Some(body.into());
+        self
+    }
+}
+
+impl Display for Mailto {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("MAILTO:")?;
+        percent_encode(f, &self.address, ADDRESS)?;
+
+        let mut separator = '?';
+        let mut field = |f: &mut Formatter<'_>, name: &str| -> core::fmt::Result {
+            write!(f, "{separator}{name}=")?;
+            separator = '&';
+            Ok(())
+        };
+
+        for (i, cc) in self.cc.iter().enumerate() {
+            if i == 0 {
+                field(f, "cc")?;
+            } else {
+                f.write_str(",")?;
+            }
+            percent_encode(f, cc, ADDRESS)?;
+        }
+        if let Some(subject) = &self.subject {
+            field(f, "subject")?;
+            percent_encode(f, subject, &[])?;
+        }
+        if let Some(body) = &self.body {
+            field(f, "body")?;
+            for (i, line) in body.lines().enumerate() {
+                if i > 0 {
+                    f.write_str("%0D%0A")?;
+                }
+                percent_encode(f, line, &[])?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl_payload!(Mailto);
+
+/// Phone number, referring to RFC 3966: `TEL:<number>`
+///
+/// Visual separators are dropped, so that international numbers fit in the alphanumeric mode.
+///
+/// # Example
+/// ```rust
+/// use fast_qr::payload::Tel;
+///
+/// assert_eq!(Tel::new("+1 (555) 123-4567").to_string(), "TEL:+15551234567");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tel {
+    number: String,
+}
+
+impl Tel {
+    /// Creates a phone number payload
+    #[must_use]
+    pub fn new<S: Into<String>>(number: S) -> Self {
+        Tel {
+            number: number.into(),
+        }
+    }
+}
+
+impl Display for Tel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("TEL:")?;
+        phone_number(f, &self.number)
+    }
+}
+
+impl_payload!(Tel);
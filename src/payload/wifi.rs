@@ -0,0 +1,104 @@
+//! WiFi network credentials, as read by the camera apps of Android and iOS
+
+use alloc::string::String;
+use core::fmt::{Display, Formatter};
+
+use super::{escape, impl_payload};
+
+/// Characters escaped with a backslash in the fields of a WiFi payload
+const SPECIAL: [char; 5] = ['\\', ';', ',', ':', '"'];
+
+/// Authentication of a WiFi network
+#[derive(Clone, Copy, Debug)]
+pub enum WifiSecurity {
+    /// WPA, WPA2 or WPA3 personal, written `WPA`
+    Wpa,
+    /// WEP, written `WEP`
+    Wep,
+    /// Open network without password, written `nopass`
+    Open,
+}
+
+impl WifiSecurity {
+    /// Returns the value of the `T` field
+    const fn as_str(self) -> &'static str {
+        match self {
+            WifiSecurity::Wpa => "WPA",
+            WifiSecurity::Wep => "WEP",
+            WifiSecurity::Open => "nopass",
+        }
+    }
+}
+
+/// WiFi network payload: `WIFI:T:WPA;S:<ssid>;P:<password>;;`
+///
+/// # Example
+/// ```rust
+/// use fast_qr::payload::{Wifi, WifiSecurity};
+///
+/// let wifi = Wifi::new("Café").security(WifiSecurity::Wpa).password("p@ss;word").to_string();
+/// assert_eq!(wifi, r"WIFI:T:WPA;S:Café;P:p@ss\;word;;");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Wifi {
+    ssid: String,
+    security: WifiSecurity,
+    password: Option<String>,
+    hidden: bool,
+}
+
+impl Wifi {
+    /// Creates the payload of an open network named `ssid`
+    #[must_use]
+    pub fn new<S: Into<String>>(ssid: S) -> Self {
+        Wifi {
+            ssid: ssid.into(),
+            security: WifiSecurity::Open,
+            password: None,
+            hidden: false,
+        }
+    }
+
+    /// Sets the authentication of the network
+    pub fn security(&mut self, security: WifiSecurity) -> &mut Self {
+        self.security = security;
+        self
+    }
+
+    /// Sets the password, ignored by open networks
+    pub fn password<S: Into<String>>(&mut self, password: S) -> &mut Self {
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Marks the network as hidden, its SSID not being broadcast
+    pub fn hidden(&mut self, hidden: bool) -> &mut Self {
+        self.hidden = hidden;
+        self
+    }
+}
+
+impl Display for Wifi {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "WIFI:T:{};S:", self.security.as_str())?;
+        escape(f, &self.ssid, &SPECIAL)?;
+        f.write_str(";")?;
+
+        match (self.security, &self.password) {
+            (WifiSecurity::Open, _) | (_, None) => {}
+            (_, Some(password)) => {
+                f.write_str("P:")?;
+                escape(f, password, &SPECIAL)?;
+                f.write_str(";")?;
+            }
+        }
+
+        if self.hidden {
+            f.write_str("H:true;")?;
+        }
+
+        f.write_str(";")
+    }
+}
+
+impl_payload!(Wifi);
@@ -4,9 +4,11 @@
 
 use crate::compact::CompactQR;
 use crate::datamasking::Mask;
-use crate::encode::Mode;
+use crate::encode::Segment;
 
-use crate::module::ModuleType;
+use crate::default::Canvas;
+use crate::module::{Module, ModuleType};
+use crate::score::MaskEvaluation;
 use crate::{datamasking, default, encode, polynomials, score, QRCode};
 use crate::{Version, ECL};
 use core::iter::Rev;
@@ -29,34 +31,31 @@ impl Iterator for BiRange {
 
 #[cfg(test)]
 pub fn test_place_on_matrix_data(qr: &mut QRCode, structure_as_binarystring: &CompactQR) {
-    place_on_matrix_data(qr, structure_as_binarystring);
+    place_on_matrix_data(qr, structure_as_binarystring.get_data());
 }
 
-/// Places the data on the matrix
-pub fn place_on_matrix_data(qr: &mut QRCode, structure_as_binarystring: &CompactQR) {
-    let structure_bytes_tmp = structure_as_binarystring.get_data();
+/// Places the data, the bytes of the final structure, on the matrix
+pub fn place_on_matrix_data<C: Canvas>(qr: &mut C, structure_bytes_tmp: &[u8]) {
+    let n = qr.width();
 
     let mut rev = true;
     let mut idx = 0;
 
     // 0, 2, 4, 7, 9, .., N (skipping 6)
-    for x in (0..6).chain(7..qr.size).rev().step_by(2) {
+    for x in (0..6).chain(7..n).rev().step_by(2) {
         let y_range = if rev {
-            BiRange::Backwards((0..qr.size).rev())
+            BiRange::Backwards((0..n).rev())
         } else {
-            BiRange::Forward(0..qr.size)
+            BiRange::Forward(0..n)
         };
 
         for y in y_range {
-            if qr[y][x].module_type() == ModuleType::Data {
-                let c = structure_bytes_tmp[idx / 8] & (1 << (7 - idx % 8));
-                idx += 1;
-                qr[y][x].set(c != 0);
-            }
-            if qr[y][x - 1].module_type() == ModuleType::Data {
-                let c = structure_bytes_tmp[idx / 8] & (1 << (7 - idx % 8));
-                idx += 1;
-                qr[y][x - 1].set(c != 0);
+            for x in [x, x - 1] {
+                if qr.get(y, x).module_type() == ModuleType::Data {
+                    let c = structure_bytes_tmp[idx / 8] & (1 << (7 - idx % 8));
+                    idx += 1;
+                    qr.set(y, x, Module::data(c != 0));
+                }
             }
         }
 
@@ -65,50 +64,26 @@ pub fn place_on_matrix_data(qr: &mut QRCode, structure_as_binarystring: &Compact
 
     #[cfg(debug_assertions)]
     {
-        let version = Version::from_n(qr.size);
+        let version = Version::from_n(n);
         assert_eq!(idx - version.missing_bits(), version.max_bytes() * 8);
     }
 }
 
-const MASKS: [Mask; 8] = [
-    Mask::Checkerboard,
-    Mask::HorizontalLines,
-    Mask::VerticalLines,
-    Mask::DiagonalLines,
-    Mask::LargeCheckerboard,
-    Mask::Fields,
-    Mask::Diamonds,
-    Mask::Meadow,
-];
-
 /// Main function to place everything in the `QRCode`, returns a valid matrix
 pub fn place_on_matrix(
     structure_as_binarystring: &CompactQR,
     quality: ECL,
     version: Version,
     mask: &mut Option<Mask>,
+    evaluation: MaskEvaluation,
 ) -> QRCode {
-    let mut best_score = u32::MAX;
-    let mut best_mask = MASKS[0];
-
     let mut qr = default::create_matrix(version);
-    place_on_matrix_data(&mut qr, structure_as_binarystring);
-
-    let transpose = default::transpose(&qr);
-
-    for mask in MASKS {
-        let mut copy = qr.clone();
-        let copy_transpose = transpose.clone();
+    place_on_matrix_data(&mut qr, structure_as_binarystring.get_data());
 
-        datamasking::mask(&mut copy, mask);
-        let matrix_score = score::score(&copy, &copy_transpose);
-        if matrix_score < best_score {
-            best_score = matrix_score;
-            best_mask = mask;
-        }
-    }
-
-    best_mask = mask.unwrap_or(best_mask);
+    let best_mask = match *mask {
+        Some(mask) => mask,
+        None => score::report(&qr, quality, evaluation).winner,
+    };
     *mask = Some(best_mask);
 
     default::create_matrix_format_info(&mut qr, quality, best_mask);
@@ -120,22 +95,31 @@ pub fn place_on_matrix(
 
 /// Generate the whole matrix
 pub fn create_matrix(
-    input: &[u8],
+    segments: &[Segment],
     ecl: ECL,
-    mode: Mode,
     version: Version,
     mask: &mut Option<Mask>,
+    evaluation: MaskEvaluation,
 ) -> QRCode {
-    let data_codewords = encode::encode(input, ecl, mode, version);
+    let data_codewords = encode::encode(segments, ecl, version);
     let structure = polynomials::structure(data_codewords.get_data(), ecl, version);
 
     let max = version.max_bytes() * 8;
     let structure_binstring = CompactQR::from_array(&structure, max + version.missing_bits());
 
+    // Mixed segments don't have a single mode
+    let mut modes = segments.iter().filter_map(|segment| match segment {
+        Segment::Data(mode, _) => Some(*mode),
+        _ => None,
+    });
+    let mode = modes
+        .next()
+        .filter(|&first| modes.all(|mode| mode == first));
+
     QRCode {
-        mode: Some(mode),
+        mode,
         ecl: Some(ecl),
         version: Some(version),
-        ..place_on_matrix(&structure_binstring, ecl, version, mask)
+        ..place_on_matrix(&structure_binstring, ecl, version, mask, evaluation)
     }
 }
@@ -6,6 +6,8 @@
 use crate::hardcode;
 use crate::polynomials;
 use crate::{Version, ECL};
+use alloc::vec;
+use alloc::vec::Vec;
 
 /// Used in the ring, convert a^x using `LOG[x % 255]` to it's decimal Galois-Field value
 const LOG: [u8; 256] = [
@@ -101,6 +103,132 @@ pub fn division(from: &[u8], by: &[u8]) -> [u8; 255] {
     from_mut
 }
 
+/// Multiplies two decimal values in the Galois Field (256)
+const fn multiply(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    LOG[(ANTILOG[a as usize] as usize + ANTILOG[b as usize] as usize) % 255]
+}
+
+/// Divides two decimal values in the Galois Field (256), `b` must not be 0
+const fn divide(a: u8, b: u8) -> u8 {
+    if a == 0 {
+        return 0;
+    }
+    LOG[(ANTILOG[a as usize] as usize + 255 - ANTILOG[b as usize] as usize) % 255]
+}
+
+/// Evaluates a polynomial, lowest degree first, at `x`
+fn evaluate(poly: &[u8], x: u8) -> u8 {
+    poly.iter()
+        .rev()
+        .fold(0, |acc, &coef| multiply(acc, x) ^ coef)
+}
+
+/// Computes the `ecc` syndromes of a block, all zero when the block has no error
+fn syndromes(block: &[u8], ecc: usize) -> Vec<u8> {
+    (0..ecc)
+        .map(|i| {
+            let x = LOG[i % 255];
+            block.iter().fold(0, |acc, &coef| multiply(acc, x) ^ coef)
+        })
+        .collect()
+}
+
+/// Corrects a block, data codewords followed by its `ecc` error correction codewords, in
+/// place. Returns the number of corrected codewords, `None` if there are too many errors.
+///
+/// The error locator is found with Berlekamp-Massey, the errors with a Chien search and their
+/// values with Forney's algorithm.
+pub fn correct(block: &mut [u8], ecc: usize) -> Option<usize> {
+    let syndrome = syndromes(block, ecc);
+    if syndrome.iter().all(|&s| s == 0) {
+        return Some(0);
+    }
+
+    // Berlekamp-Massey, `locator` and `previous` being lowest degree first
+    let mut locator = vec![1u8];
+    let mut previous = vec![1u8];
+    let mut errors = 0;
+    let mut shift = 1;
+    let mut previous_discrepancy = 1;
+
+    for n in 0..ecc {
+        let discrepancy = (1..=errors).fold(syndrome[n], |acc, i| {
+            acc ^ multiply(*locator.get(i).unwrap_or(&0), syndrome[n - i])
+        });
+
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+
+        let factor = divide(discrepancy, previous_discrepancy);
+        let mut next = locator.clone();
+        next.resize(core::cmp::max(locator.len(), previous.len() + shift), 0);
+        for (i, &coef) in previous.iter().enumerate() {
+            next[i + shift] ^= multiply(factor, coef);
+        }
+
+        if 2 * errors <= n {
+            previous = core::mem::replace(&mut locator, next);
+            errors = n + 1 - errors;
+            previous_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            locator = next;
+            shift += 1;
+        }
+    }
+
+    if errors * 2 > ecc {
+        return None;
+    }
+
+    // Error evaluator, `syndromes * locator mod x^ecc`
+    let mut evaluator = vec![0u8; ecc];
+    for (i, &s) in syndrome.iter().enumerate() {
+        for (j, &l) in locator.iter().enumerate().take(ecc - i) {
+            evaluator[i + j] ^= multiply(s, l);
+        }
+    }
+
+    // Formal derivative of the locator, only odd powers remain in GF(2^8)
+    let derivative = locator
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &coef)| if i % 2 == 1 { coef } else { 0 })
+        .collect::<Vec<_>>();
+
+    let len = block.len();
+    let mut found = 0;
+    for power in 0..len {
+        let x_inv = LOG[(255 - power % 255) % 255];
+        if evaluate(&locator, x_inv) != 0 {
+            continue;
+        }
+
+        let denominator = evaluate(&derivative, x_inv);
+        if denominator == 0 {
+            return None;
+        }
+        let magnitude = multiply(
+            LOG[power % 255],
+            divide(evaluate(&evaluator, x_inv), denominator),
+        );
+        block[len - 1 - power] ^= magnitude;
+        found += 1;
+    }
+
+    if found != errors || syndromes(block, ecc).iter().any(|&s| s != 0) {
+        return None;
+    }
+
+    Some(found)
+}
+
 /// Uses the data and error(generator polynomial) to compute the divisions
 /// for each block.
 pub fn structure(data: &[u8], quality: ECL, version: Version) -> [u8; 5430] {
@@ -1,17 +1,26 @@
 //! Module `qr` is the entrypoint to start making `QRCodes`
 
-use crate::module::Module;
+use crate::module::{Module, ModuleType};
+use alloc::boxed::Box;
+#[cfg(not(feature = "wasm-bindgen"))]
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
 use core::fmt::{Debug, Formatter};
 use core::ops::{Index, IndexMut};
 
 use crate::datamasking::Mask;
-use crate::encode::Mode;
+use crate::encode::{Mode, Segment};
 #[cfg(not(feature = "wasm-bindgen"))]
 use crate::helpers;
-use crate::{encode, Version, ECL};
+use crate::micro::MicroVersion;
+use crate::packed::{self, Payload};
+use crate::rmqr::RMQRVersion;
+use crate::score::{MaskEvaluation, MaskReport};
+use crate::{encode, hardcode, Version, ECL};
 
-const QR_MAX_WIDTH: usize = 177;
-const QR_MAX_MODULES: usize = QR_MAX_WIDTH * QR_MAX_WIDTH;
+/// ECI assignment number for UTF-8
+const UTF8_ECI: u32 = 26;
 
 /// A `QRCode` can be created using [`QRBuilder`]. Simple API for simple usage.
 /// If you need to use `QRCode` directly, please file an [issue on
@@ -23,21 +32,32 @@ const QR_MAX_MODULES: usize = QR_MAX_WIDTH * QR_MAX_WIDTH;
 /// It contains the matrix of the `QRCode`, stored as a one-dimensional array.
 #[derive(Clone)]
 pub struct QRCode {
-    /// This array length is of size `177 x 177`. It is using a fixed size
-    /// array simply because of performance.
+    /// Modules of the `QRCode`, row by row, of length `size x height`. Boxed so that a V01
+    /// `QRCode` only holds its `21 x 21` modules, making clones and moves cheap.
     ///
     /// # Other data type possible:
+    /// - Fixed size array of `177 x 177`, about 31 KB copied on each clone even for a V01.
     /// - Templated Matrix was faster but crate size was huge.
-    /// - Vector using `with_capacity`, really bad.
-    pub data: [Module; QR_MAX_MODULES],
+    pub data: Box<[Module]>,
     /// Width & Height of QRCode. If manually set, should be `version * 4 + 17`, `version` going
     /// from 1 to 40 both included.
+    ///
+    /// Rectangular symbols (rMQR) use it as their width, `height` being their number of rows.
     pub size: usize,
+    /// Number of rows of the `QRCode`, always equal to `size` except for rectangular symbols.
+    pub height: usize,
 
     /// Version of the `QRCode`, impacts the size.
     ///
-    /// `None` will optimize Version according to ECL and Mode
+    /// `None` will optimize Version according to ECL and Mode. Once built, `None` means a
+    /// Micro QR or rMQR symbol.
     pub version: Option<Version>,
+    /// Version of a Micro QR symbol, built by [`crate::MicroQRBuilder`]. `None` for
+    /// regular `QRCode`s.
+    pub micro: Option<MicroVersion>,
+    /// Version of a rectangular Micro QR symbol, built by [`crate::RMQRBuilder`]. `None` for
+    /// square `QRCode`s.
+    pub rmqr: Option<RMQRVersion>,
     /// Defines how powerful `QRCode` redundancy should be or how much percent of a QRCode can be
     /// recovered.
     ///
@@ -53,12 +73,13 @@ pub struct QRCode {
     ///
     /// None will find the best suited mask.
     pub mask: Option<Mask>,
-    /// Mode defines which data is being parsed, between Numeric, AlphaNumeric & Byte.
+    /// Mode defines which data is being parsed, between Numeric, AlphaNumeric, Byte & Kanji.
     ///
-    /// `None` will optimize Mode according to user input.
+    /// `None` will optimize Mode according to user input. Once built, `None` means the input
+    /// was split into segments of different modes.
     ///
     /// ## Note
-    /// Kanji mode is not supported (yet).
+    /// Kanji mode expects Shift JIS double-byte input, UTF-8 input stays in Byte mode.
     pub mode: Option<Mode>,
 }
 
@@ -66,7 +87,10 @@ impl Debug for QRCode {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("QRCode")
             .field("size", &self.size)
+            .field("height", &self.height)
             .field("version", &self.version)
+            .field("micro", &self.micro)
+            .field("rmqr", &self.rmqr)
             .field("ecl", &self.ecl)
             .field("mask", &self.mask)
             .field("mode", &self.mode)
@@ -76,12 +100,22 @@ impl Debug for QRCode {
 
 impl QRCode {
     /// A default `QRCode` will have all it's fields as `None` and a default Matrix filled with `Module::LIGHT`.
+    ///
+    /// Not a `const fn`, since its modules are allocated on the heap.
     #[must_use]
-    pub const fn default(size: usize) -> Self {
+    pub fn default(size: usize) -> Self {
+        Self::rectangle(size, size)
+    }
+
+    /// A default `QRCode` of `height` rows of `width` modules, see [`QRCode::default`]
+    pub(crate) fn rectangle(width: usize, height: usize) -> Self {
         QRCode {
-            data: [Module::data(Module::LIGHT); QR_MAX_MODULES],
-            size,
+            data: vec![Module::data(Module::LIGHT); width * height].into_boxed_slice(),
+            size: width,
+            height,
             version: None,
+            micro: None,
+            rmqr: None,
             ecl: None,
             mask: None,
             mode: None,
@@ -109,51 +143,152 @@ pub enum QRCodeError {
     EncodedData,
     /// Specified version too small to contain data
     SpecifiedVersion,
+    /// A [`Segment`], or the input in a forced [`QRBuilder::mode`], contains a character its
+    /// mode can't encode. `index` counts the data of all segments, as if they were a single input.
+    InvalidCharacter {
+        /// Position of the character
+        index: usize,
+        /// Value of the character
+        byte: u8,
+    },
+    /// ECI assignment number above 999999
+    InvalidEci(u32),
+    /// Structured Append header with a `total` outside of 1 to 16, or an `index` not below `total`
+    InvalidStructuredAppend,
+    /// FNC1 second position application indicator neither a two-digit number nor a letter + 100
+    InvalidApplicationIndicator(u8),
+    /// ECL not available for the Micro QR version(s), `ECL::H` never is. rMQR only supports
+    /// `ECL::M` and `ECL::H`
+    UnsupportedEcl,
+    /// Mask not available in Micro QR
+    UnsupportedMask,
+    /// Min version of [`QRBuilder::version_range`] above its max version
+    InvalidVersionRange,
+    /// Data too large for the max version of [`QRBuilder::version_range`]
+    MaxVersionExceeded {
+        /// Number of bytes over the capacity of the max version
+        excess: usize,
+    },
+    /// Buffer given to [`QRBuilder::build_into`] too small to hold the matrix
+    BufferTooSmall {
+        /// Number of bytes of the matrix
+        needed: usize,
+    },
 }
 
-// We don't want to use `std::error::Error` on wasm32
+// `std::error::Error` is not available in `no_std`
+#[cfg(feature = "std")]
 impl std::error::Error for QRCodeError {}
 
-impl std::fmt::Display for QRCodeError {
+impl core::fmt::Display for QRCodeError {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             QRCodeError::EncodedData => f.write_str("Data too big to be encoded"),
             QRCodeError::SpecifiedVersion => {
                 f.write_str("Specified version too low to contain data")
             }
+            QRCodeError::InvalidCharacter { index, byte } => {
+                write!(f, "Invalid character {byte:#04x} at index {index}")
+            }
+            QRCodeError::InvalidEci(assignment) => {
+                write!(f, "Invalid ECI assignment number {assignment}")
+            }
+            QRCodeError::InvalidStructuredAppend => {
+                f.write_str("Invalid structured append index or total")
+            }
+            QRCodeError::InvalidApplicationIndicator(indicator) => {
+                write!(f, "Invalid FNC1 application indicator {indicator}")
+            }
+            QRCodeError::UnsupportedEcl => {
+                f.write_str("ECL not available for Micro QR or rMQR version")
+            }
+            QRCodeError::UnsupportedMask => f.write_str("Mask not available for Micro QR"),
+            QRCodeError::InvalidVersionRange => {
+                f.write_str("Min version of the range above its max version")
+            }
+            QRCodeError::MaxVersionExceeded { excess } => {
+                write!(f, "Data exceeds max version by {excess} bytes")
+            }
+            QRCodeError::BufferTooSmall { needed } => {
+                write!(f, "Buffer too small, {needed} bytes needed")
+            }
         }
     }
 }
 
 impl Debug for QRCodeError {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        match self {
-            QRCodeError::EncodedData => f.write_str("Data too big to be encoded"),
-            QRCodeError::SpecifiedVersion => {
-                f.write_str("Specified version too low to contain data")
-            }
-        }
+        core::fmt::Display::fmt(self, f)
     }
 }
 
 impl QRCode {
-    /// Creates a new `QRCode` from a ECL / version
+    /// Creates a new `QRCode` from a ECL / version, scoring masks with `MaskEvaluation::Fast`
     ///
     /// # Errors
     /// - `QRCodeError::EncodedData` if `input` is too large to be encoded
     /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small to contain data
+    #[cfg(any(test, target_arch = "wasm32"))]
     pub(crate) fn new(
         input: &[u8],
         ecl: Option<ECL>,
         v: Option<Version>,
-        mut mask: Option<Mask>,
+        mask: Option<Mask>,
     ) -> Result<Self, QRCodeError> {
-        use crate::placement::create_matrix;
+        Self::with_prefix(input, &[], ecl, v, mask, MaskEvaluation::Fast)
+    }
+
+    /// Creates a new `QRCode` from a ECL / version, with `prefix` segments (i.e. ECI headers)
+    /// in front of the data
+    ///
+    /// # Errors
+    /// - `QRCodeError::EncodedData` if `input` is too large to be encoded
+    /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small to contain data
+    pub(crate) fn with_prefix(
+        input: &[u8],
+        prefix: &[Segment],
+        ecl: Option<ECL>,
+        v: Option<Version>,
+        mask: Option<Mask>,
+        evaluation: MaskEvaluation,
+    ) -> Result<Self, QRCodeError> {
+        let level = ecl.unwrap_or(ECL::Q);
+
+        let (version, segments) = match encode::best_segments(input, level, prefix) {
+            Some(best) => best,
+            None => return Err(QRCodeError::EncodedData),
+        };
+        let (version, segments) = match v {
+            Some(user_version) if user_version as usize >= version as usize => {
+                let segments = encode::prefixed_segments(input, user_version, prefix);
+                (user_version, segments)
+            }
+            None => (version, segments),
+            Some(_) => return Err(QRCodeError::SpecifiedVersion),
+        };
+
+        Self::from_segments(&segments, level, version, mask, evaluation)
+    }
+
+    /// Creates a new `QRCode` from explicit segments, encoded as is
+    ///
+    /// # Errors
+    /// - `QRCodeError::InvalidCharacter` if a segment contains a character its mode can't encode
+    /// - `QRCodeError::InvalidEci` if an ECI assignment number is above 999999
+    /// - `QRCodeError::EncodedData` if `segments` are too large to be encoded
+    /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small to contain data
+    pub(crate) fn with_segments(
+        segments: &[Segment],
+        ecl: Option<ECL>,
+        v: Option<Version>,
+        mask: Option<Mask>,
+        evaluation: MaskEvaluation,
+    ) -> Result<Self, QRCodeError> {
+        encode::check_segments(segments)?;
 
-        let mode = encode::best_encoding(input);
         let level = ecl.unwrap_or(ECL::Q);
 
-        let version = match Version::get(mode, level, input.len()) {
+        let version = match encode::segments_version(segments, level) {
             Some(version) => version,
             None => return Err(QRCodeError::EncodedData),
         };
@@ -163,8 +298,71 @@ impl QRCode {
             Some(_) => return Err(QRCodeError::SpecifiedVersion),
         };
 
-        let out = create_matrix(input, level, mode, version, &mut mask);
-        Ok(out)
+        Self::from_segments(segments, level, version, mask, evaluation)
+    }
+
+    /// Places `segments` on a matrix of said `version`
+    fn from_segments(
+        segments: &[Segment],
+        level: ECL,
+        version: Version,
+        mut mask: Option<Mask>,
+        evaluation: MaskEvaluation,
+    ) -> Result<Self, QRCodeError> {
+        use crate::placement::create_matrix;
+
+        // Longer character count indicators can outgrow a bigger version
+        if encode::segments_bits(segments, version) > hardcode::data_bits(version, level) {
+            return Err(QRCodeError::EncodedData);
+        }
+
+        Ok(create_matrix(
+            segments, level, version, &mut mask, evaluation,
+        ))
+    }
+
+    /// Returns the penalty of each of the 8 masks, as scored by `evaluation` when building the
+    /// `QRCode`, and the mask with the lowest one. `None` for Micro QR and rMQR symbols.
+    ///
+    /// The winner is the mask of the `QRCode` built with the same [`QRBuilder::mask_evaluation`],
+    /// unless it was forced with [`QRBuilder::mask`]. With [`MaskEvaluation::Fast`], the column
+    /// components of each penalty are approximations, see [`MaskReport`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::{MaskEvaluation, QRBuilder};
+    ///
+    /// let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    /// let report = qrcode.mask_report(MaskEvaluation::Fast).unwrap();
+    ///
+    /// let best = report.penalties.iter().map(|penalty| penalty.total()).min();
+    /// assert_eq!(best, Some(report.penalties[report.winner as usize].total()));
+    /// assert_eq!(report.winner as usize, qrcode.mask.unwrap() as usize);
+    /// ```
+    #[must_use]
+    pub fn mask_report(&self, evaluation: MaskEvaluation) -> Option<MaskReport> {
+        let (version, ecl, mask) = match (self.version, self.ecl, self.mask) {
+            (Some(version), Some(ecl), Some(mask))
+                if self.micro.is_none() && self.rmqr.is_none() =>
+            {
+                (version, ecl, mask)
+            }
+            _ => return None,
+        };
+
+        // Data placed as before masking, without format information
+        let mut qr = crate::default::create_matrix(version);
+        for (module, own) in qr.data[..self.size * self.size]
+            .iter_mut()
+            .zip(&self.data[..self.size * self.size])
+        {
+            if module.module_type() == ModuleType::Data {
+                module.set(own.value());
+            }
+        }
+        crate::datamasking::mask(&mut qr, mask);
+
+        Some(crate::score::report(&qr, ecl, evaluation))
     }
 
     /// Prints the `QRCode` to the terminal
@@ -175,12 +373,18 @@ impl QRCode {
     }
 
     /// Prints the `QRCode` to the terminal
-    #[cfg(not(feature = "wasm-bindgen"))]
+    #[cfg(all(feature = "std", not(feature = "wasm-bindgen")))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     pub fn print(&self) {
         println!("{}", helpers::print_matrix_with_margin(self));
     }
 }
 
+/// Checks if `input` is UTF-8 with at least one non-ASCII character
+fn is_utf8(input: &[u8]) -> bool {
+    !input.is_ascii() && core::str::from_utf8(input).is_ok()
+}
+
 /// Builder struct, makes it easier to create a [`QRCode`].
 ///
 /// # Example
@@ -196,12 +400,19 @@ impl QRCode {
 ///     // .mask(Mask::Checkerboard)
 ///     .build();
 /// ```
+#[derive(Clone)]
 pub struct QRBuilder {
     input: Vec<u8>,
+    segments: Option<Vec<Segment>>,
+    utf8_eci: bool,
+    fnc1: Option<Segment>,
     ecl: Option<ECL>,
-    // mode: Option<Mode>,
+    maximize_ecl: bool,
+    mode: Option<Mode>,
     version: Option<Version>,
+    version_range: Option<(Version, Version)>,
     mask: Option<Mask>,
+    mask_evaluation: MaskEvaluation,
 }
 
 impl QRBuilder {
@@ -210,17 +421,65 @@ impl QRBuilder {
     pub fn new<I: Into<Vec<u8>>>(input: I) -> QRBuilder {
         QRBuilder {
             input: input.into(),
+            segments: None,
+            utf8_eci: false,
+            fnc1: None,
             mask: None,
-            // mode: None,
+            mask_evaluation: MaskEvaluation::Fast,
+            mode: None,
             version: None,
+            version_range: None,
             ecl: None,
+            maximize_ecl: false,
+        }
+    }
+
+    /// Creates an instance of `QRBuilder` from explicit segments, which are encoded as is
+    /// instead of being optimized
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::{QRBuilder, Segment};
+    ///
+    /// let qr = QRBuilder::segments(vec![
+    ///     Segment::alphanumeric("INVOICE "),
+    ///     Segment::numeric("000123"),
+    /// ])
+    /// .build();
+    /// ```
+    #[must_use]
+    pub fn segments(segments: Vec<Segment>) -> QRBuilder {
+        QRBuilder {
+            segments: Some(segments),
+            ..QRBuilder::new(Vec::new())
         }
     }
 
-    // pub fn mode(&mut self, mode: Mode) -> &mut Self {
-    //     self.mode = Some(mode);
-    //     self
-    // }
+    /// Forces the input into a single segment of `mode`, instead of splitting it into the
+    /// segments giving the shortest bitstream. The input is checked against `mode` when built.
+    ///
+    /// With an FNC1 header, GS bytes (0x1D) can be encoded in `Mode::Alphanumeric`.
+    /// Ignored by builders created with [`QRBuilder::segments`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::qr::QRCodeError;
+    /// use fast_qr::{Mode, QRBuilder};
+    ///
+    /// // Digits kept in Byte mode for a legacy scanner
+    /// let qr = QRBuilder::new("0123456789").mode(Mode::Byte).build().unwrap();
+    /// assert_eq!(qr.mode, Some(Mode::Byte));
+    ///
+    /// let qr = QRBuilder::new("HELLo").mode(Mode::Alphanumeric).build();
+    /// assert!(matches!(
+    ///     qr,
+    ///     Err(QRCodeError::InvalidCharacter { index: 4, byte: b'o' })
+    /// ));
+    /// ```
+    pub fn mode(&mut self, mode: Mode) -> &mut Self {
+        self.mode = Some(mode);
+        self
+    }
 
     /// Forces the Encoding Level
     pub fn ecl(&mut self, ecl: ECL) -> &mut Self {
@@ -228,24 +487,525 @@ impl QRBuilder {
         self
     }
 
+    /// Picks the highest Encoding Level holding the data (default: false), trying H, then Q,
+    /// M and L. The version stays the forced one, or the smallest one holding the data at the
+    /// lowest level tried: a level forced with [`QRBuilder::ecl`] becomes that lowest level.
+    ///
+    /// The chosen level is reported on [`QRCode::ecl`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::{QRBuilder, Version, ECL};
+    ///
+    /// let qr = QRBuilder::new("HELLO WORLD")
+    ///     .version(Version::V02)
+    ///     .maximize_ecl(true)
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(matches!(qr.ecl, Some(ECL::H)));
+    /// ```
+    pub fn maximize_ecl(&mut self, maximize_ecl: bool) -> &mut Self {
+        self.maximize_ecl = maximize_ecl;
+        self
+    }
+
     /// Forces the version
     pub fn version(&mut self, version: Version) -> &mut Self {
         self.version = Some(version);
         self
     }
 
+    /// Bounds the version picked when none is forced with [`QRBuilder::version`]: the smallest
+    /// version holding the data, but at least `min`. Data needing more than `max` fails with
+    /// `QRCodeError::MaxVersionExceeded`, and `min` above `max` with
+    /// `QRCodeError::InvalidVersionRange`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::qr::QRCodeError;
+    /// use fast_qr::{QRBuilder, Version};
+    ///
+    /// let qr = QRBuilder::new("Hello")
+    ///     .version_range(Version::V05, Version::V10)
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(matches!(qr.version, Some(Version::V05)));
+    ///
+    /// let qr = QRBuilder::new("0123456789abcdef".repeat(4))
+    ///     .version_range(Version::V01, Version::V03)
+    ///     .build();
+    /// assert!(matches!(qr, Err(QRCodeError::MaxVersionExceeded { excess: 20 })));
+    /// ```
+    pub fn version_range(&mut self, min: Version, max: Version) -> &mut Self {
+        self.version_range = Some((min, max));
+        self
+    }
+
+    /// Prefixes non-ASCII UTF-8 input with an ECI 26 header (default: false), so scanners
+    /// don't read it as ISO-8859-1. ASCII input, other encodings and segments given to
+    /// [`QRBuilder::segments`] are left untouched.
+    ///
+    /// The header takes 12 bits, which can require a bigger version.
+    pub fn utf8_eci(&mut self, utf8_eci: bool) -> &mut Self {
+        self.utf8_eci = utf8_eci;
+        self
+    }
+
+    /// Marks the input as GS1 data with an FNC1 first position header, GS bytes (0x1D)
+    /// separating its elements. See [`crate::gs1::Gs1Builder`] to create such input.
+    ///
+    /// Segments given to [`QRBuilder::segments`] are left untouched.
+    pub fn fnc1_first(&mut self) -> &mut Self {
+        self.fnc1 = Some(Segment::fnc1_first());
+        self
+    }
+
+    /// Marks the input as following an industry specification, with an FNC1 second
+    /// position header holding its `application_indicator`, GS bytes (0x1D) separating
+    /// its elements. See [`Segment::Fnc1Second`] for valid indicators.
+    ///
+    /// Segments given to [`QRBuilder::segments`] are left untouched.
+    pub fn fnc1_second(&mut self, application_indicator: u8) -> &mut Self {
+        self.fnc1 = Some(Segment::fnc1_second(application_indicator));
+        self
+    }
+
     /// Forces the mask, should very rarely be used
     pub fn mask(&mut self, mask: Mask) -> &mut Self {
         self.mask = Some(mask);
         self
     }
 
+    /// Changes how masks are scored to pick the best one (default: `MaskEvaluation::Fast`).
+    /// `MaskEvaluation::Iso` follows the spec, giving the same symbol as reference encoders
+    /// for the same segments, version and `ECL`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::{MaskEvaluation, QRBuilder};
+    ///
+    /// let qr = QRBuilder::new("https://example.com/")
+    ///     .mask_evaluation(MaskEvaluation::Iso)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let report = qr.mask_report(MaskEvaluation::Iso).unwrap();
+    /// assert_eq!(report.winner as usize, qr.mask.unwrap() as usize);
+    /// ```
+    pub fn mask_evaluation(&mut self, mask_evaluation: MaskEvaluation) -> &mut Self {
+        self.mask_evaluation = mask_evaluation;
+        self
+    }
+
     /// Computes a [`QRCode`] with given parameters
     ///
+    /// The input is split into the segments giving the shortest bitstream, mixing modes when
+    /// it helps. [`QRBuilder::build_into`] doesn't, encoding it as a single segment: both give
+    /// the same symbol when the shortest split is a single segment, as for digits only or
+    /// text in a single mode without long runs of a more compact one, or with a forced
+    /// [`QRBuilder::mode`] or [`QRBuilder::segments`]. Otherwise, `build` can give a smaller
+    /// version or another matrix.
+    ///
     /// # Errors
     /// - `QRCodeError::EncodedData` if `input` is too large to be encoded. See [an online table](https://fast-qr.com/blog/tables/ecl) for more info.
     /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small to contain data
+    /// - `QRCodeError::InvalidCharacter` if a [`Segment`] or the input in a forced
+    ///   [`QRBuilder::mode`] contains a character its mode can't encode
+    /// - `QRCodeError::InvalidEci` if an ECI assignment number is above 999999
+    /// - `QRCodeError::InvalidApplicationIndicator` if the FNC1 application indicator is invalid
+    /// - `QRCodeError::InvalidVersionRange` if the min version of the range is above its max
+    /// - `QRCodeError::MaxVersionExceeded` if `input` needs a version above the range
     pub fn build(&self) -> Result<QRCode, QRCodeError> {
-        QRCode::new(&self.input, self.ecl, self.version, self.mask)
+        self.check_version_range()?;
+        if self.maximize_ecl {
+            return self.build_maximized_ecl();
+        }
+
+        self.build_with(self.ecl, self.version)
+    }
+
+    /// Checks that the min version of the range is not above its max version
+    fn check_version_range(&self) -> Result<(), QRCodeError> {
+        match self.version_range {
+            Some((min, max)) if min as usize > max as usize => {
+                Err(QRCodeError::InvalidVersionRange)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Computes a [`QRCode`] with the given `ecl` & `version` instead of the forced ones.
+    /// Without `version`, it is picked within the version range.
+    fn build_with(
+        &self,
+        ecl: Option<ECL>,
+        version: Option<Version>,
+    ) -> Result<QRCode, QRCodeError> {
+        let version = match (version, self.version_range) {
+            (None, Some((min, max))) => Some(self.version_in_range(ecl, min, max)?),
+            (version, _) => version,
+        };
+
+        if let Some(segments) = self.fixed_segments()? {
+            return QRCode::with_segments(&segments, ecl, version, self.mask, self.mask_evaluation);
+        }
+
+        let prefix = self.prefix()?;
+        QRCode::with_prefix(
+            &self.input,
+            &prefix,
+            ecl,
+            version,
+            self.mask,
+            self.mask_evaluation,
+        )
+    }
+
+    /// Returns the smallest version holding the data at `ecl`, but at least `min`
+    ///
+    /// # Errors
+    /// - `QRCodeError::MaxVersionExceeded` if the data needs a version above `max`
+    fn version_in_range(
+        &self,
+        ecl: Option<ECL>,
+        min: Version,
+        max: Version,
+    ) -> Result<Version, QRCodeError> {
+        let level = ecl.unwrap_or(ECL::Q);
+
+        let (needed, bits) = match self.fixed_segments()? {
+            Some(segments) => {
+                encode::check_segments(&segments)?;
+                let needed = encode::segments_version(&segments, level);
+                (needed, encode::segments_bits(&segments, max))
+            }
+            None => {
+                let prefix = self.prefix()?;
+                let needed = encode::best_segments(&self.input, level, &prefix);
+                let segments = encode::prefixed_segments(&self.input, max, &prefix);
+                (
+                    needed.map(|(version, _)| version),
+                    encode::segments_bits(&segments, max),
+                )
+            }
+        };
+
+        match needed {
+            Some(needed) if needed as usize <= max as usize => {
+                Ok(Version::from_n(core::cmp::max(needed.size(), min.size())))
+            }
+            _ => {
+                let excess = bits.saturating_sub(hardcode::data_bits(max, level));
+                Err(QRCodeError::MaxVersionExceeded {
+                    excess: (excess + 7) / 8,
+                })
+            }
+        }
+    }
+
+    /// Computes a [`QRCode`] with the highest `ECL` holding the data, see
+    /// [`QRBuilder::maximize_ecl`]
+    fn build_maximized_ecl(&self) -> Result<QRCode, QRCodeError> {
+        let lowest = self.ecl.unwrap_or(ECL::L);
+        let version = match self.version {
+            Some(version) => version,
+            None => self.needed_version(lowest)?,
+        };
+
+        for ecl in [ECL::H, ECL::Q, ECL::M] {
+            if (ecl as usize) <= lowest as usize {
+                break;
+            }
+
+            match self.build_with(Some(ecl), Some(version)) {
+                Err(QRCodeError::EncodedData | QRCodeError::SpecifiedVersion) => {}
+                result => return result,
+            }
+        }
+
+        self.build_with(Some(lowest), Some(version))
+    }
+
+    /// Returns the version `build_with` picks at `ecl` without forced version,
+    /// without computing the symbol
+    ///
+    /// # Errors
+    /// - `QRCodeError::EncodedData` if the data is too large to be encoded at `ecl`
+    /// - Same errors as `version_in_range`
+    fn needed_version(&self, ecl: ECL) -> Result<Version, QRCodeError> {
+        if let Some((min, max)) = self.version_range {
+            return self.version_in_range(Some(ecl), min, max);
+        }
+
+        let needed = match self.fixed_segments()? {
+            Some(segments) => {
+                encode::check_segments(&segments)?;
+                encode::segments_version(&segments, ecl)
+            }
+            None => {
+                let prefix = self.prefix()?;
+                encode::best_segments(&self.input, ecl, &prefix).map(|(version, _)| version)
+            }
+        };
+        needed.ok_or(QRCodeError::EncodedData)
+    }
+
+    /// Returns the segments encoded as is: the ones given to [`QRBuilder::segments`], or the
+    /// headers followed by `input` in the forced mode
+    ///
+    /// # Errors
+    /// - `QRCodeError::InvalidCharacter` if the forced mode can't encode `input`
+    fn fixed_segments(&self) -> Result<Option<Vec<Segment>>, QRCodeError> {
+        if let Some(segments) = &self.segments {
+            return Ok(Some(segments.clone()));
+        }
+
+        match self.mode {
+            Some(mode) => {
+                let mut segments = self.prefix()?;
+                let forced = encode::forced_segment(&self.input, mode, self.fnc1.is_some())?;
+                segments.push(forced);
+                Ok(Some(segments))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the headers placed in front of `input`: ECI, then FNC1, referring to 7.4.8 of the spec
+    fn prefix(&self) -> Result<Vec<Segment>, QRCodeError> {
+        let mut prefix = Vec::new();
+        if self.utf8_eci && is_utf8(&self.input) {
+            prefix.push(Segment::eci(UTF8_ECI));
+        }
+        if let Some(fnc1) = &self.fnc1 {
+            prefix.push(fnc1.clone());
+        }
+
+        encode::check_segments(&prefix)?;
+        Ok(prefix)
+    }
+
+    /// Computes the [`QRCode`] into `buffer` without allocating, as a packed bit matrix: rows
+    /// of `(size + 7) / 8` bytes, the leftmost module in the most significant bit, dark modules
+    /// being `1`. Returns the `Version`, giving the `size` of the matrix. Bytes past the
+    /// matrix are left untouched.
+    ///
+    /// The largest matrix, a V40, takes 4071 bytes. Generating it uses about 14 KB of stack.
+    ///
+    /// Unlike [`QRBuilder::build`], the input is encoded as a single segment, in the forced
+    /// [`QRBuilder::mode`] or the most compact mode holding every character, so mixed input can
+    /// need a bigger version. Splitting it as `build` does needs memory growing with the input.
+    /// Segments given to [`QRBuilder::segments`] are encoded as is.
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let mut buffer = [0; 4071];
+    /// let version = QRBuilder::new("HELLO WORLD").build_into(&mut buffer).unwrap();
+    ///
+    /// let size = version.size();
+    /// let stride = (size + 7) / 8;
+    /// let is_dark = |y: usize, x: usize| buffer[y * stride + x / 8] & (0x80 >> (x % 8)) != 0;
+    /// // Top left corner of a finder pattern
+    /// assert!(is_dark(0, 0));
+    /// ```
+    ///
+    /// # Errors
+    /// - `QRCodeError::BufferTooSmall` if `buffer` can't hold the matrix
+    /// - Same errors as [`QRBuilder::build`]
+    pub fn build_into(&self, buffer: &mut [u8]) -> Result<Version, QRCodeError> {
+        self.check_version_range()?;
+        let eci = Segment::eci(UTF8_ECI);
+        let payload = match &self.segments {
+            Some(segments) => Payload {
+                headers: [None, None],
+                segments,
+                input: None,
+                fnc1: false,
+            },
+            None => {
+                let fnc1 = self.fnc1.is_some();
+                let mode = match self.mode {
+                    Some(mode) => mode,
+                    None => packed::single_mode(&self.input, fnc1),
+                };
+
+                Payload {
+                    headers: [
+                        (self.utf8_eci && is_utf8(&self.input)).then(|| &eci),
+                        self.fnc1.as_ref(),
+                    ],
+                    segments: &[],
+                    input: Some((mode, &self.input)),
+                    fnc1,
+                }
+            }
+        };
+        payload.check()?;
+
+        let (ecl, version) = if self.maximize_ecl {
+            self.maximized_ecl_into(&payload)?
+        } else {
+            let ecl = self.ecl.unwrap_or(ECL::Q);
+            (ecl, self.version_into(&payload, ecl, self.version)?)
+        };
+
+        let size = version.size();
+        let needed = packed::stride(size) * size;
+        if buffer.len() < needed {
+            return Err(QRCodeError::BufferTooSmall { needed });
+        }
+
+        packed::generate(
+            &mut buffer[..needed],
+            &payload,
+            ecl,
+            version,
+            self.mask,
+            self.mask_evaluation,
+        );
+        Ok(version)
+    }
+
+    /// Returns the version of `payload` at `ecl`: the given one, else the smallest one holding
+    /// it within the version range
+    ///
+    /// # Errors
+    /// - `QRCodeError::EncodedData` if `payload` is too large to be encoded
+    /// - `QRCodeError::SpecifiedVersion` if `version` is too small to contain data
+    /// - `QRCodeError::MaxVersionExceeded` if `payload` needs a version above the range
+    fn version_into(
+        &self,
+        payload: &Payload,
+        ecl: ECL,
+        version: Option<Version>,
+    ) -> Result<Version, QRCodeError> {
+        let needed = payload.version(ecl);
+
+        let version = match (version, self.version_range) {
+            (None, Some((min, max))) => match needed {
+                Some(needed) if needed as usize <= max as usize => {
+                    Some(Version::from_n(core::cmp::max(needed.size(), min.size())))
+                }
+                _ => {
+                    let excess = payload
+                        .bits(max)
+                        .saturating_sub(hardcode::data_bits(max, ecl));
+                    return Err(QRCodeError::MaxVersionExceeded {
+                        excess: (excess + 7) / 8,
+                    });
+                }
+            },
+            (version, _) => version,
+        };
+
+        let version = match (version, needed) {
+            (_, None) => return Err(QRCodeError::EncodedData),
+            (Some(version), Some(needed)) if version as usize >= needed as usize => version,
+            (None, Some(needed)) => needed,
+            (Some(_), Some(_)) => return Err(QRCodeError::SpecifiedVersion),
+        };
+
+        // Longer character count indicators can outgrow a bigger version
+        if payload.bits(version) > hardcode::data_bits(version, ecl) {
+            return Err(QRCodeError::EncodedData);
+        }
+
+        Ok(version)
+    }
+
+    /// Returns the highest `ECL` holding `payload` and its version, as
+    /// [`QRBuilder::maximize_ecl`] does for [`QRBuilder::build`]
+    fn maximized_ecl_into(&self, payload: &Payload) -> Result<(ECL, Version), QRCodeError> {
+        let lowest = self.ecl.unwrap_or(ECL::L);
+        let version = match self.version {
+            Some(version) => version,
+            None => self.version_into(payload, lowest, None)?,
+        };
+
+        for ecl in [ECL::H, ECL::Q, ECL::M] {
+            if (ecl as usize) <= lowest as usize {
+                break;
+            }
+
+            match self.version_into(payload, ecl, Some(version)) {
+                Err(QRCodeError::EncodedData | QRCodeError::SpecifiedVersion) => {}
+                result => return result.map(|version| (ecl, version)),
+            }
+        }
+
+        self.version_into(payload, lowest, Some(version))
+            .map(|version| (lowest, version))
+    }
+
+    /// Computes [`QRCode`]s linked with Structured Append, for data too large for a single one.
+    ///
+    /// The data is split across up to 16 symbols, kept at similar versions. Each symbol starts
+    /// with a header holding its position, the number of symbols and the parity of the whole
+    /// data. Data fitting in a single [`QRCode`] is returned alone, without header, as are
+    /// builders created with [`QRBuilder::segments`] or with a forced [`QRBuilder::mode`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use fast_qr::QRBuilder;
+    ///
+    /// let config = "0123456789abcdef".repeat(300);
+    /// let qrcodes = QRBuilder::new(config).build_structured_append().unwrap();
+    /// assert_eq!(qrcodes.len(), 3);
+    /// ```
+    ///
+    /// # Errors
+    /// - `QRCodeError::EncodedData` if `input` doesn't fit in 16 symbols, at most at the
+    ///   forced version or the max of [`QRBuilder::version_range`]
+    /// - Same errors as [`QRBuilder::build`]
+    pub fn build_structured_append(&self) -> Result<Vec<QRCode>, QRCodeError> {
+        use crate::structured_append;
+
+        match self.build() {
+            Err(
+                QRCodeError::EncodedData
+                | QRCodeError::SpecifiedVersion
+                | QRCodeError::MaxVersionExceeded { .. },
+            ) if self.segments.is_none() && self.mode.is_none() => {}
+            result => return result.map(|qr| vec![qr]),
+        }
+
+        let prefix = self.prefix()?;
+        let level = self.ecl.unwrap_or(ECL::Q);
+        let range_max = self.version_range.map(|(_, max)| max);
+        let max = self.version.or(range_max).unwrap_or(Version::V40);
+        let chunks = structured_append::split(&self.input, level, max, &prefix)
+            .ok_or(QRCodeError::EncodedData)?;
+
+        let total = chunks.len() as u8;
+        let parity = Segment::parity(&self.input);
+
+        chunks
+            .iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                let mut header = vec![Segment::structured_append(index as u8, total, parity)];
+                header.extend_from_slice(&prefix);
+
+                // Symbols below the range are raised to its min version
+                let version = match (self.version, self.version_range) {
+                    (None, Some((min, _))) => {
+                        encode::best_segments(chunk, level, &header).map(|(needed, _)| {
+                            Version::from_n(core::cmp::max(needed.size(), min.size()))
+                        })
+                    }
+                    (version, _) => version,
+                };
+                QRCode::with_prefix(
+                    chunk,
+                    &header,
+                    self.ecl,
+                    version,
+                    self.mask,
+                    self.mask_evaluation,
+                )
+            })
+            .collect()
     }
 }
@@ -0,0 +1,536 @@
+//! Creates rectangular Micro QR Code symbols (rMQR, R7x43 to R17x139), referring to ISO/IEC 23941.
+//!
+//! rMQR symbols are at most 17 modules high, which makes them fit narrow printing areas. Only
+//! `ECL::M` and `ECL::H` are available, the mask is always `Mask::LargeCheckerboard` and data is
+//! encoded in a single segment.
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use crate::compact::CompactQR;
+use crate::datamasking::{self, Mask};
+use crate::encode::{self, Mode};
+use crate::module::{Module, ModuleType};
+use crate::placement::BiRange;
+use crate::qr::QRCodeError;
+use crate::{default, hardcode, polynomials, QRCode, ECL};
+use alloc::vec::Vec;
+
+/// Enum containing all possible rMQR versions, named after their height & width
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum RMQRVersion {
+    R7x43 = 0,
+    R7x59,
+    R7x77,
+    R7x99,
+    R7x139,
+    R9x43,
+    R9x59,
+    R9x77,
+    R9x99,
+    R9x139,
+    R11x27,
+    R11x43,
+    R11x59,
+    R11x77,
+    R11x99,
+    R11x139,
+    R13x27,
+    R13x43,
+    R13x59,
+    R13x77,
+    R13x99,
+    R13x139,
+    R15x43,
+    R15x59,
+    R15x77,
+    R15x99,
+    R15x139,
+    R17x43,
+    R17x59,
+    R17x77,
+    R17x99,
+    R17x139,
+}
+
+/// Width of each version, referring to Table 1 of the spec
+#[rustfmt::skip]
+const WIDTHS: [usize; 32] = [
+    43, 59, 77, 99, 139,
+    43, 59, 77, 99, 139,
+    27, 43, 59, 77, 99, 139,
+    27, 43, 59, 77, 99, 139,
+    43, 59, 77, 99, 139,
+    43, 59, 77, 99, 139,
+];
+
+/// Character count indicator bits of each version for Numeric, Alphanumeric, Byte & Kanji,
+/// referring to Table 3 of the spec
+#[rustfmt::skip]
+const CCI_BITS: [[usize; 4]; 32] = [
+    [4, 3, 3, 2], [5, 5, 4, 3], [6, 5, 5, 4], [7, 6, 5, 5], [7, 6, 6, 5],
+    [5, 5, 4, 3], [6, 5, 5, 4], [7, 6, 5, 5], [7, 6, 6, 5], [8, 7, 6, 6],
+    [4, 4, 3, 2], [6, 5, 5, 4], [7, 6, 5, 5], [7, 6, 6, 5], [8, 7, 6, 6], [8, 7, 7, 6],
+    [5, 5, 4, 3], [6, 6, 5, 5], [7, 6, 6, 5], [7, 7, 6, 5], [8, 7, 7, 6], [8, 8, 7, 7],
+    [7, 6, 6, 5], [7, 7, 6, 5], [8, 7, 7, 6], [8, 7, 7, 6], [9, 8, 7, 7],
+    [7, 6, 6, 5], [8, 7, 6, 6], [8, 7, 7, 6], [8, 8, 7, 6], [9, 8, 8, 7],
+];
+
+/// Error correction codewords per block, followed by the two groups of blocks as
+/// `(block count, data codewords per block)`
+type Blocks = (usize, [(usize, usize); 2]);
+
+/// Blocks of each version, for `ECL::M` then `ECL::H`, referring to Table 8 of the spec
+#[rustfmt::skip]
+const BLOCKS: [[Blocks; 2]; 32] = [
+    [(7, [(1, 6), (0, 0)]), (10, [(1, 3), (0, 0)])],
+    [(9, [(1, 12), (0, 0)]), (14, [(1, 7), (0, 0)])],
+    [(12, [(1, 20), (0, 0)]), (22, [(1, 10), (0, 0)])],
+    [(16, [(1, 28), (0, 0)]), (30, [(1, 14), (0, 0)])],
+    [(24, [(1, 44), (0, 0)]), (22, [(2, 12), (0, 0)])],
+    [(9, [(1, 12), (0, 0)]), (14, [(1, 7), (0, 0)])],
+    [(12, [(1, 21), (0, 0)]), (22, [(1, 11), (0, 0)])],
+    [(18, [(1, 31), (0, 0)]), (16, [(1, 8), (1, 9)])],
+    [(24, [(1, 42), (0, 0)]), (22, [(2, 11), (0, 0)])],
+    [(18, [(1, 31), (1, 32)]), (22, [(3, 11), (0, 0)])],
+    [(8, [(1, 7), (0, 0)]), (10, [(1, 5), (0, 0)])],
+    [(12, [(1, 19), (0, 0)]), (20, [(1, 11), (0, 0)])],
+    [(16, [(1, 31), (0, 0)]), (16, [(1, 7), (1, 8)])],
+    [(24, [(1, 43), (0, 0)]), (22, [(1, 11), (1, 12)])],
+    [(16, [(1, 28), (1, 29)]), (30, [(1, 14), (1, 15)])],
+    [(24, [(2, 42), (0, 0)]), (30, [(3, 14), (0, 0)])],
+    [(9, [(1, 12), (0, 0)]), (14, [(1, 7), (0, 0)])],
+    [(14, [(1, 27), (0, 0)]), (28, [(1, 13), (0, 0)])],
+    [(22, [(1, 38), (0, 0)]), (20, [(2, 10), (0, 0)])],
+    [(16, [(1, 26), (1, 27)]), (28, [(1, 14), (1, 15)])],
+    [(20, [(1, 36), (1, 37)]), (26, [(1, 11), (2, 12)])],
+    [(20, [(2, 35), (1, 36)]), (28, [(2, 13), (2, 14)])],
+    [(18, [(1, 33), (0, 0)]), (18, [(1, 7), (1, 8)])],
+    [(26, [(1, 48), (0, 0)]), (24, [(2, 13), (0, 0)])],
+    [(18, [(1, 33), (1, 34)]), (24, [(2, 10), (1, 11)])],
+    [(24, [(2, 44), (0, 0)]), (22, [(4, 12), (0, 0)])],
+    [(24, [(2, 42), (1, 43)]), (26, [(1, 13), (4, 14)])],
+    [(22, [(1, 39), (0, 0)]), (20, [(1, 10), (1, 11)])],
+    [(16, [(2, 28), (0, 0)]), (30, [(2, 14), (0, 0)])],
+    [(22, [(2, 39), (0, 0)]), (28, [(1, 12), (2, 13)])],
+    [(20, [(2, 33), (1, 34)]), (26, [(4, 14), (0, 0)])],
+    [(20, [(4, 38), (0, 0)]), (26, [(2, 12), (4, 13)])],
+];
+
+/// Generator of the (18, 6) BCH code protecting the format information
+const FORMAT_GENERATOR: u32 = 0b1_1111_0010_0101;
+/// Mask of the format information next to the finder pattern
+const FORMAT_MASK_FINDER: u32 = 0b01_1111_1010_1011_0010;
+/// Mask of the format information next to the finder sub pattern
+const FORMAT_MASK_SUB_FINDER: u32 = 0b10_0000_1010_0111_1011;
+
+impl RMQRVersion {
+    pub(crate) const ALL: [RMQRVersion; 32] = [
+        RMQRVersion::R7x43,
+        RMQRVersion::R7x59,
+        RMQRVersion::R7x77,
+        RMQRVersion::R7x99,
+        RMQRVersion::R7x139,
+        RMQRVersion::R9x43,
+        RMQRVersion::R9x59,
+        RMQRVersion::R9x77,
+        RMQRVersion::R9x99,
+        RMQRVersion::R9x139,
+        RMQRVersion::R11x27,
+        RMQRVersion::R11x43,
+        RMQRVersion::R11x59,
+        RMQRVersion::R11x77,
+        RMQRVersion::R11x99,
+        RMQRVersion::R11x139,
+        RMQRVersion::R13x27,
+        RMQRVersion::R13x43,
+        RMQRVersion::R13x59,
+        RMQRVersion::R13x77,
+        RMQRVersion::R13x99,
+        RMQRVersion::R13x139,
+        RMQRVersion::R15x43,
+        RMQRVersion::R15x59,
+        RMQRVersion::R15x77,
+        RMQRVersion::R15x99,
+        RMQRVersion::R15x139,
+        RMQRVersion::R17x43,
+        RMQRVersion::R17x59,
+        RMQRVersion::R17x77,
+        RMQRVersion::R17x99,
+        RMQRVersion::R17x139,
+    ];
+
+    /// Returns the width of the symbol, from 27 to 139
+    #[must_use]
+    pub const fn width(self) -> usize {
+        WIDTHS[self as usize]
+    }
+
+    /// Returns the height of the symbol, from 7 to 17
+    #[must_use]
+    pub const fn height(self) -> usize {
+        match self as usize {
+            0..=4 => 7,
+            5..=9 => 9,
+            10..=15 => 11,
+            16..=21 => 13,
+            22..=26 => 15,
+            _ => 17,
+        }
+    }
+
+    /// Returns the columns of the alignment patterns' centers, referring to Table D.1 of the spec
+    const fn alignment_columns(self) -> &'static [usize] {
+        match self.width() {
+            43 => &[21],
+            59 => &[19, 39],
+            77 => &[25, 51],
+            99 => &[23, 49, 75],
+            139 => &[27, 55, 83, 111],
+            _ => &[],
+        }
+    }
+
+    /// Returns the number of bits of the character count indicator
+    const fn cci_bits(self, mode: Mode) -> usize {
+        CCI_BITS[self as usize][mode as usize]
+    }
+
+    /// Returns the error correction codewords per block and the groups of blocks, `None` if
+    /// `ecl` is not available
+    const fn blocks(self, ecl: ECL) -> Option<Blocks> {
+        match ecl {
+            ECL::M => Some(BLOCKS[self as usize][0]),
+            ECL::H => Some(BLOCKS[self as usize][1]),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of data bits
+    const fn data_bits(self, ecl: ECL) -> Option<usize> {
+        match self.blocks(ecl) {
+            Some((_, [(count1, size1), (count2, size2)])) => {
+                Some((count1 * size1 + count2 * size2) * 8)
+            }
+            None => None,
+        }
+    }
+}
+
+/// Returns the format information next to the finder pattern and next to the finder sub pattern,
+/// referring to 7.9 of the spec
+fn format_information(version: RMQRVersion, ecl: ECL) -> (u32, u32) {
+    let data = (u32::from(matches!(ecl, ECL::H)) << 5 | version as u32) << 12;
+
+    let mut remainder = data;
+    for i in (12..18).rev() {
+        if remainder >> i & 1 != 0 {
+            remainder ^= FORMAT_GENERATOR << (i - 12);
+        }
+    }
+
+    let info = data | remainder;
+    (info ^ FORMAT_MASK_FINDER, info ^ FORMAT_MASK_SUB_FINDER)
+}
+
+/// Creates the empty matrix, with function patterns and format information placeholders
+fn create_matrix(version: RMQRVersion) -> QRCode {
+    let (width, height) = (version.width(), version.height());
+    let mut qr = QRCode::rectangle(width, height);
+
+    default::create_finder_pattern(&mut qr, 0, 0);
+    for i in 0..=7 {
+        if i < height {
+            qr[i][7] = Module::empty(Module::LIGHT);
+        }
+        if height > 7 {
+            qr[7][i] = Module::empty(Module::LIGHT);
+        }
+    }
+
+    // Finder sub pattern, in the bottom right corner
+    for i in 0..5 {
+        for j in 0..5 {
+            let ring = core::cmp::max((i as isize - 2).abs(), (j as isize - 2).abs());
+            qr[height - 5 + i][width - 5 + j] = Module::finder_pattern(ring != 1);
+        }
+    }
+
+    // Corner finder patterns, the bottom left one is hidden by the finder pattern in R7
+    qr[0][width - 2] = Module::finder_pattern(Module::DARK);
+    qr[0][width - 1] = Module::finder_pattern(Module::DARK);
+    qr[1][width - 2] = Module::finder_pattern(Module::LIGHT);
+    qr[1][width - 1] = Module::finder_pattern(Module::DARK);
+    if height > 7 {
+        for j in 0..3 {
+            qr[height - 1][j] = Module::finder_pattern(Module::DARK);
+        }
+    }
+    if height >= 11 {
+        qr[height - 2][0] = Module::finder_pattern(Module::DARK);
+        qr[height - 2][1] = Module::finder_pattern(Module::LIGHT);
+    }
+
+    // Alignment patterns are on both the top and bottom edges
+    for &center in version.alignment_columns() {
+        for i in 0..3 {
+            for j in 0..3 {
+                let value = i != 1 || j != 1;
+                qr[i][center - 1 + j] = Module::alignment(value);
+                qr[height - 1 - i][center - 1 + j] = Module::alignment(value);
+            }
+        }
+    }
+
+    // Timing patterns run along all edges and through the alignment patterns
+    for j in 0..width {
+        for i in [0, height - 1] {
+            if qr[i][j].module_type() == ModuleType::Data {
+                qr[i][j] = Module::timing(j % 2 == 0);
+            }
+        }
+    }
+    let columns = version.alignment_columns().iter();
+    for &j in [0, width - 1].iter().chain(columns) {
+        for i in 0..height {
+            if qr[i][j].module_type() == ModuleType::Data {
+                qr[i][j] = Module::timing(i % 2 == 0);
+            }
+        }
+    }
+
+    place_format_information(&mut qr, 0, 0);
+
+    qr
+}
+
+/// Places the format information on both sides of the matrix, next to the finder pattern and
+/// above the finder sub pattern
+fn place_format_information(qr: &mut QRCode, finder: u32, sub_finder: u32) {
+    let (width, height) = (qr.size, qr.height);
+
+    for i in 0..18 {
+        qr[1 + i % 5][8 + i / 5] = Module::format(finder >> i & 1 != 0);
+
+        let (y, x) = match i {
+            0..=14 => (height - 6 + i % 5, width - 8 + i / 5),
+            _ => (height - 6, width - 20 + i),
+        };
+        qr[y][x] = Module::format(sub_finder >> i & 1 != 0);
+    }
+}
+
+/// Places the data on the matrix, by pairs of columns from the bottom right corner. Remainder
+/// bits are left light.
+fn place_on_matrix_data(qr: &mut QRCode, structure: &CompactQR) {
+    let data = structure.get_data();
+
+    let mut rev = true;
+    let mut idx = 0;
+
+    // The last column holds the timing pattern
+    for x in (1..qr.size - 1).rev().step_by(2) {
+        let y_range = if rev {
+            BiRange::Backwards((0..qr.height).rev())
+        } else {
+            BiRange::Forward(0..qr.height)
+        };
+
+        for y in y_range {
+            for x in [x, x - 1] {
+                if qr[y][x].module_type() == ModuleType::Data && idx < structure.len() {
+                    let c = data[idx / 8] & (1 << (7 - idx % 8));
+                    idx += 1;
+                    qr[y][x].set(c != 0);
+                }
+            }
+        }
+
+        rev = !rev;
+    }
+
+    #[cfg(debug_assertions)]
+    assert_eq!(idx, structure.len());
+}
+
+/// Encodes `input` as a single segment, followed by the terminator and padding, referring to
+/// 7.4 of the spec
+fn encode(input: &[u8], mode: Mode, cci_bits: usize, data_bits: usize) -> CompactQR {
+    let mut compact = CompactQR::with_len(data_bits);
+
+    compact.push_bits(mode as usize + 1, 3);
+    compact.push_bits(encode::char_count(mode, input), cci_bits);
+    encode::push_payload(&mut compact, mode, input);
+
+    let terminator = core::cmp::min(3, data_bits - compact.len());
+    compact.push_bits(0, terminator);
+
+    let pad = core::cmp::min((8 - compact.len() % 8) % 8, data_bits - compact.len());
+    compact.push_bits(0, pad);
+
+    for &pad in [0b1110_1100, 0b0001_0001].iter().cycle() {
+        if compact.len() == data_bits {
+            break;
+        }
+        compact.push_u8(pad);
+    }
+
+    compact
+}
+
+/// Splits the data codewords into blocks, then interleaves their data and error correction
+/// codewords, referring to 7.6 of the spec
+fn interleave(data: &[u8], ecc: usize, groups: [(usize, usize); 2]) -> CompactQR {
+    let polynomial = hardcode::rmqr_polynomial(ecc);
+
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    for &(count, size) in groups.iter() {
+        for _ in 0..count {
+            blocks.push(&data[start..start + size]);
+            start += size;
+        }
+    }
+
+    let errors = blocks
+        .iter()
+        .map(|block| polynomials::division(block, polynomial))
+        .collect::<Vec<_>>();
+
+    let mut structure = CompactQR::with_len((data.len() + ecc * blocks.len()) * 8);
+    let longest = blocks.iter().map(|block| block.len()).max().unwrap_or(0);
+    for i in 0..longest {
+        for block in blocks.iter().filter(|block| i < block.len()) {
+            structure.push_u8(block[i]);
+        }
+    }
+    for i in 0..ecc {
+        for division in errors.iter() {
+            structure.push_u8(division[256 - polynomial.len() + i]);
+        }
+    }
+
+    structure
+}
+
+/// Generates the whole rMQR matrix for `input`
+fn create(input: &[u8], mode: Mode, version: RMQRVersion, ecl: ECL) -> QRCode {
+    let (ecc, groups) = version.blocks(ecl).unwrap();
+    let data_bits = version.data_bits(ecl).unwrap();
+
+    let compact = encode(input, mode, version.cci_bits(mode), data_bits);
+    let structure = interleave(&compact.get_data()[..data_bits / 8], ecc, groups);
+
+    let mut qr = create_matrix(version);
+    place_on_matrix_data(&mut qr, &structure);
+    datamasking::mask(&mut qr, Mask::LargeCheckerboard);
+    let (finder, sub_finder) = format_information(version, ecl);
+    place_format_information(&mut qr, finder, sub_finder);
+
+    QRCode {
+        rmqr: Some(version),
+        ecl: Some(ecl),
+        mask: Some(Mask::LargeCheckerboard),
+        mode: Some(mode),
+        ..qr
+    }
+}
+
+/// Builder struct, makes it easier to create a rectangular Micro QR [`QRCode`].
+///
+/// The version with the smallest area holding the data is picked, the lowest one on ties.
+/// `max_height` and `max_width` restrict the versions considered. rMQR only needs a 2 modules
+/// quiet zone, see [`crate::convert::Builder::margin`].
+///
+/// # Example
+/// ```rust
+/// use fast_qr::{RMQRBuilder, RMQRVersion};
+///
+/// let qr = RMQRBuilder::new("HELLO").build().unwrap();
+/// assert_eq!(qr.rmqr, Some(RMQRVersion::R11x27));
+/// assert_eq!((qr.size, qr.height), (27, 11));
+///
+/// let qr = RMQRBuilder::new("HELLO").max_height(7).build().unwrap();
+/// assert_eq!(qr.rmqr, Some(RMQRVersion::R7x43));
+/// ```
+pub struct RMQRBuilder {
+    input: Vec<u8>,
+    ecl: ECL,
+    version: Option<RMQRVersion>,
+    max_height: Option<usize>,
+    max_width: Option<usize>,
+}
+
+impl RMQRBuilder {
+    /// Creates an instance of `RMQRBuilder` with default parameters, `ECL::M` being the default
+    #[must_use]
+    pub fn new<I: Into<Vec<u8>>>(input: I) -> RMQRBuilder {
+        RMQRBuilder {
+            input: input.into(),
+            ecl: ECL::M,
+            version: None,
+            max_height: None,
+            max_width: None,
+        }
+    }
+
+    /// Forces the Encoding Level, only `ECL::M` and `ECL::H` are available in rMQR
+    pub fn ecl(&mut self, ecl: ECL) -> &mut Self {
+        self.ecl = ecl;
+        self
+    }
+
+    /// Forces the version
+    pub fn version(&mut self, version: RMQRVersion) -> &mut Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Only considers versions at most `max_height` modules high
+    pub fn max_height(&mut self, max_height: usize) -> &mut Self {
+        self.max_height = Some(max_height);
+        self
+    }
+
+    /// Only considers versions at most `max_width` modules wide
+    pub fn max_width(&mut self, max_width: usize) -> &mut Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Computes a rMQR [`QRCode`] with given parameters
+    ///
+    /// # Errors
+    /// - `QRCodeError::EncodedData` if `input` is too large for the versions considered
+    /// - `QRCodeError::SpecifiedVersion` if specified `version` is too small
+    /// - `QRCodeError::UnsupportedEcl` if `ecl` is neither `ECL::M` nor `ECL::H`
+    pub fn build(&self) -> Result<QRCode, QRCodeError> {
+        if !matches!(self.ecl, ECL::M | ECL::H) {
+            return Err(QRCodeError::UnsupportedEcl);
+        }
+
+        let mode = encode::best_encoding(&self.input);
+        let count = encode::char_count(mode, &self.input);
+        let payload_bits = encode::payload_bits(mode, &self.input);
+
+        let version = RMQRVersion::ALL
+            .iter()
+            .copied()
+            .filter(|&version| self.version.map_or(true, |forced| forced == version))
+            .filter(|version| self.max_height.map_or(true, |max| version.height() <= max))
+            .filter(|version| self.max_width.map_or(true, |max| version.width() <= max))
+            .filter(|&version| {
+                let cci_bits = version.cci_bits(mode);
+                let bits = 3 + cci_bits + payload_bits;
+                count < 1 << cci_bits && bits <= version.data_bits(self.ecl).unwrap()
+            })
+            .min_by_key(|version| version.width() * version.height());
+
+        match (version, self.version) {
+            (Some(version), _) => Ok(create(&self.input, mode, version, self.ecl)),
+            (None, Some(_)) => Err(QRCodeError::SpecifiedVersion),
+            (None, None) => Err(QRCodeError::EncodedData),
+        }
+    }
+}
@@ -3,10 +3,12 @@
 
 #![warn(missing_docs)]
 
+use crate::datamasking::{self, Mask};
 #[cfg(test)]
 use crate::default::transpose;
+use crate::default::{self, Canvas};
 use crate::module::{Module, ModuleType};
-use crate::QRCode;
+use crate::{QRCode, ECL};
 
 use super::hardcode;
 
@@ -43,35 +45,34 @@ pub fn test_matrix_score_squares(qr: &QRCode) -> u32 {
 /// We don't want to access the 4 squares each time, so we score the left most
 /// ones and only fetch the next right ones
 fn matrix_score_squares(qr: &QRCode) -> u32 {
-    let mut square_score = 0;
-
-    for i in 0..qr.size - 1 {
-        let mut count_data = 2;
-
-        let line1 = &qr[i];
-        let line2 = &qr[i + 1];
+    (0..qr.size - 1).map(|i| squares(&qr[i], &qr[i + 1])).sum()
+}
 
-        let mut buffer = 0u8;
-        buffer |= u8::from(line1[0].value()) << 2;
-        buffer |= u8::from(line2[0].value()) << 3;
+/// Computes the score of the 2x2 squares spanning two consecutive lines
+fn squares(line1: &[Module], line2: &[Module]) -> u32 {
+    let mut square_score = 0;
+    let mut count_data = 2;
 
-        for j in 0..qr.size - 1 {
-            buffer >>= 2;
-            buffer |= u8::from(line1[j + 1].value()) << 2;
-            buffer |= u8::from(line2[j + 1].value()) << 3;
+    let mut buffer = 0u8;
+    buffer |= u8::from(line1[0].value()) << 2;
+    buffer |= u8::from(line2[0].value()) << 3;
 
-            if line1[j + 1].module_type() != ModuleType::Data
-                || line2[j + 1].module_type() != ModuleType::Data
-            {
-                count_data = 0;
-            }
+    for j in 0..line1.len() - 1 {
+        buffer >>= 2;
+        buffer |= u8::from(line1[j + 1].value()) << 2;
+        buffer |= u8::from(line2[j + 1].value()) << 3;
 
-            if count_data >= 2 && (buffer == 0b1111 || buffer == 0b0000) {
-                square_score += 3;
-            }
+        if line1[j + 1].module_type() != ModuleType::Data
+            || line2[j + 1].module_type() != ModuleType::Data
+        {
+            count_data = 0;
+        }
 
-            count_data += 1;
+        if count_data >= 2 && (buffer == 0b1111 || buffer == 0b0000) {
+            square_score += 3;
         }
+
+        count_data += 1;
     }
 
     square_score
@@ -159,20 +160,322 @@ fn dark_module_score(qr: &QRCode) -> u32 {
         .filter(|m| m.value() == Module::DARK)
         .count();
 
-    let percent = (dark_modules * 100) / (n * n);
+    dark_score(dark_modules, n * n)
+}
+
+/// Scores the ratio of `dark` modules out of `total`
+fn dark_score(dark: usize, total: usize) -> u32 {
+    let percent = (dark * 100) / total;
     u32::from(hardcode::PERCENT_SCORE[percent])
 }
 
-/// Computes the score for the matrix
+const MASKS: [Mask; 8] = [
+    Mask::Checkerboard,
+    Mask::HorizontalLines,
+    Mask::VerticalLines,
+    Mask::DiagonalLines,
+    Mask::LargeCheckerboard,
+    Mask::Fields,
+    Mask::Diamonds,
+    Mask::Meadow,
+];
+
+/// Penalty of a [`Mask`], split into the four rules of the spec (Table 11)
+#[derive(Clone, Copy, Debug)]
+pub struct MaskPenalty {
+    /// Mask the penalty was computed with
+    pub mask: Mask,
+    /// N1: N - 2 points for each row or column run of N >= 5 modules of the same color
+    pub n1: u32,
+    /// N2: 3 points for each 2x2 block of the same color
+    pub n2: u32,
+    /// N3: 40 points for each `1011101` finder-like pattern, in rows and columns
+    pub n3: u32,
+    /// N4: 10 points for each 5% of dark modules away from 50%
+    pub n4: u32,
+}
+
+impl MaskPenalty {
+    /// Returns the sum of the four components, the lesser, the better
+    #[must_use]
+    pub const fn total(&self) -> u32 {
+        self.n1 + self.n2 + self.n3 + self.n4
+    }
+}
+
+/// Penalties of the 8 masks of a `QRCode`, see [`QRCode::mask_report`]
+///
+/// With [`MaskEvaluation::Fast`], the column components are approximations: the column runs
+/// (part of N1) and column patterns (part of N3) are scored once, on the unmasked symbol, and
+/// shared by the 8 penalties. Only the row components, N2 & N4 follow each mask. The totals
+/// are the ones `Fast` compares, not the penalties of 7.8.3 of the spec, given by
+/// [`MaskEvaluation::Iso`].
+#[derive(Clone, Copy, Debug)]
+pub struct MaskReport {
+    /// Penalty of each mask, indexed by the mask number
+    pub penalties: [MaskPenalty; 8],
+    /// Mask with the lowest total, the first one on ties
+    pub winner: Mask,
+}
+
+/// Computes the penalty of a masked `qr`
 /// - `matrix_pattern_and_line`:
 ///   - 40 points for each [TFTTTFT] pattern (T: true / F: false)
 ///   - N - 2 points for each line with N consecutive modules of the same color (N >= 5)
 /// - `matrix_score_squares`: 3 points for each 2x2 square (black or white)
 /// - `dark_module_score`: 10 points for each 5% of dark modules away from 50%
-pub fn score(qr: &QRCode, qr_transpose: &QRCode) -> u32 {
-    let dark_score = dark_module_score(qr);
-    let square_score = matrix_score_squares(qr);
+fn penalty(qr: &QRCode, qr_transpose: &QRCode, mask: Mask) -> MaskPenalty {
     let (line_score, col_score, patt_score) = matrix_pattern_and_line(qr, qr_transpose);
 
-    line_score + patt_score + col_score + dark_score + square_score
+    MaskPenalty {
+        mask,
+        n1: line_score + col_score,
+        n2: matrix_score_squares(qr),
+        n3: patt_score,
+        n4: dark_module_score(qr),
+    }
+}
+
+/// How masks are scored when building a `QRCode`, see [`crate::QRBuilder::mask_evaluation`]
+#[derive(Clone, Copy, Debug)]
+pub enum MaskEvaluation {
+    /// Scores the data modules of each candidate, before format information is placed, the
+    /// columns being scored once on the unmasked symbol. Faster, but can pick a different mask
+    /// than other encoders.
+    Fast,
+    /// Scores the whole symbol of each candidate with its format information, as 7.8.3 of
+    /// ISO/IEC 18004 does, the symbol being surrounded by light modules. Picks the same mask
+    /// as reference encoders.
+    Iso,
+}
+
+impl Default for MaskEvaluation {
+    fn default() -> Self {
+        MaskEvaluation::Fast
+    }
+}
+
+/// Scores the 8 masks on `qr`, which has its data placed but neither masked nor
+/// format information
+pub(crate) fn report(qr: &QRCode, ecl: ECL, evaluation: MaskEvaluation) -> MaskReport {
+    let penalties = match evaluation {
+        MaskEvaluation::Fast => fast_penalties(qr),
+        MaskEvaluation::Iso => MASKS.map(|mask| {
+            let mut copy = qr.clone();
+            default::create_matrix_format_info(&mut copy, ecl, mask);
+            datamasking::mask(&mut copy, mask);
+            iso_penalty(&copy, mask)
+        }),
+    };
+
+    winner(penalties)
+}
+
+/// Scores the 8 masks on `qr` like [`report`], masking it in place instead of copying it.
+/// `qr` is left unmasked, with the format information of the last mask for `Iso`.
+pub(crate) fn report_in_place<C: Canvas>(
+    qr: &mut C,
+    ecl: ECL,
+    evaluation: MaskEvaluation,
+) -> MaskReport {
+    let penalties = match evaluation {
+        MaskEvaluation::Fast => fast_penalties_in_place(qr),
+        MaskEvaluation::Iso => MASKS.map(|mask| {
+            default::create_matrix_format_info(qr, ecl, mask);
+            datamasking::mask_canvas(qr, mask);
+            let penalty = iso_penalty(qr, mask);
+            datamasking::mask_canvas(qr, mask);
+            penalty
+        }),
+    };
+
+    winner(penalties)
+}
+
+/// Returns the report of `penalties`, the first lowest total winning
+fn winner(penalties: [MaskPenalty; 8]) -> MaskReport {
+    let mut winner = penalties[0];
+    for penalty in &penalties[1..] {
+        if penalty.total() < winner.total() {
+            winner = *penalty;
+        }
+    }
+
+    MaskReport {
+        penalties,
+        winner: winner.mask,
+    }
+}
+
+/// Scores the 8 masks with [`MaskEvaluation::Fast`], on the rows of a `Canvas` copied one at
+/// a time
+///
+/// ### Opti:
+/// As [`fast_penalties`], columns are scored once, on the unmasked matrix
+fn fast_penalties_in_place<C: Canvas>(qr: &mut C) -> [MaskPenalty; 8] {
+    const MAX_SIZE: usize = 177;
+
+    let n = qr.width();
+    let mut previous = [Module::data(Module::LIGHT); MAX_SIZE];
+    let mut current = [Module::data(Module::LIGHT); MAX_SIZE];
+
+    let (mut col_score, mut col_patt_score) = (0, 0);
+    for x in 0..n {
+        for (y, module) in current[..n].iter_mut().enumerate() {
+            *module = qr.get(y, x);
+        }
+        let (patt_score, line_score) = line(&current[..n]);
+        col_score += line_score;
+        col_patt_score += patt_score;
+    }
+
+    MASKS.map(|mask| {
+        datamasking::mask_canvas(qr, mask);
+
+        let (mut line_score, mut patt_score, mut square_score, mut dark) = (0, 0, 0, 0);
+        for y in 0..n {
+            for (x, module) in current[..n].iter_mut().enumerate() {
+                *module = qr.get(y, x);
+                dark += usize::from(module.value() == Module::DARK);
+            }
+
+            let l = line(&current[..n]);
+            line_score += l.1;
+            patt_score += l.0;
+            if y > 0 {
+                square_score += squares(&previous[..n], &current[..n]);
+            }
+
+            core::mem::swap(&mut previous, &mut current);
+        }
+
+        datamasking::mask_canvas(qr, mask);
+
+        MaskPenalty {
+            mask,
+            n1: line_score + col_score,
+            n2: square_score,
+            n3: patt_score + col_patt_score,
+            n4: dark_score(dark, n * n),
+        }
+    })
+}
+
+/// Scores the 8 masks with [`MaskEvaluation::Fast`]
+///
+/// ### Opti:
+/// Columns are scored on the transpose of the unmasked matrix, computed once
+fn fast_penalties(qr: &QRCode) -> [MaskPenalty; 8] {
+    let transpose = default::transpose(qr);
+
+    MASKS.map(|mask| {
+        let mut copy = qr.clone();
+        datamasking::mask(&mut copy, mask);
+        penalty(&copy, &transpose, mask)
+    })
+}
+
+/// Computes the penalty of a masked `qr` with [`MaskEvaluation::Iso`]
+fn iso_penalty<C: Canvas>(qr: &C, mask: Mask) -> MaskPenalty {
+    let n = qr.width();
+    let value = |y: usize, x: usize| qr.get(y, x).value();
+
+    let mut n1 = 0;
+    let mut n3 = 0;
+    for i in 0..n {
+        let (row_runs, row_patterns) = iso_line((0..n).map(|j| value(i, j)), n);
+        let (col_runs, col_patterns) = iso_line((0..n).map(|j| value(j, i)), n);
+        n1 += row_runs + col_runs;
+        n3 += 40 * (row_patterns + col_patterns);
+    }
+
+    let mut n2 = 0;
+    for i in 0..n - 1 {
+        for j in 0..n - 1 {
+            let color = value(i, j);
+            if value(i, j + 1) == color && value(i + 1, j) == color && value(i + 1, j + 1) == color
+            {
+                n2 += 3;
+            }
+        }
+    }
+
+    // 10 points for each full 5% step away from 50%, n * n being odd
+    let total = n * n;
+    let dark = (0..total)
+        .filter(|i| value(i / n, i % n) == Module::DARK)
+        .count();
+    let deviation = if dark * 2 > total {
+        dark * 20 - total * 10
+    } else {
+        total * 10 - dark * 20
+    };
+    let steps = (deviation + total - 1) / total - 1;
+
+    MaskPenalty {
+        mask,
+        n1,
+        n2,
+        n3,
+        n4: 10 * steps as u32,
+    }
+}
+
+/// Returns the N1 score and the number of finder-like patterns of a line of `n` modules,
+/// a `1011101` pattern counting once per side with 4 light modules
+///
+/// ### Opti:
+/// Patterns are matched on the lengths of the last 7 runs, the quiet zone extending the
+/// first and last light runs
+fn iso_line<I: Iterator<Item = bool>>(line: I, n: usize) -> (u32, u32) {
+    fn push(history: &mut [usize; 7], mut run: usize, n: usize) {
+        if history[0] == 0 {
+            run += n;
+        }
+        history.copy_within(0..6, 1);
+        history[0] = run;
+    }
+
+    fn patterns(history: &[usize; 7]) -> u32 {
+        let m = history[1];
+        let core =
+            m > 0 && history[2] == m && history[3] == m * 3 && history[4] == m && history[5] == m;
+
+        u32::from(core && history[0] >= m * 4 && history[6] >= m)
+            + u32::from(core && history[6] >= m * 4 && history[0] >= m)
+    }
+
+    let mut runs_score = 0;
+    let mut patterns_count = 0;
+
+    let mut history = [0; 7];
+    let mut color = false;
+    let mut run = 0;
+
+    for dark in line {
+        if dark == color {
+            run += 1;
+            if run == 5 {
+                runs_score += 3;
+            } else if run > 5 {
+                runs_score += 1;
+            }
+        } else {
+            push(&mut history, run, n);
+            if !color {
+                patterns_count += patterns(&history);
+            }
+            color = dark;
+            run = 1;
+        }
+    }
+
+    if color {
+        push(&mut history, run, n);
+        run = 0;
+    }
+    push(&mut history, run + n, n);
+    patterns_count += patterns(&history);
+
+    (runs_score, patterns_count)
 }
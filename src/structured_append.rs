@@ -0,0 +1,126 @@
+//! Splits data too large for a single `QRCode` across up to 16 linked symbols,
+//! referring to 9 (Structured Append) of the spec.
+
+#![deny(unsafe_code)]
+#![warn(missing_docs)]
+
+use crate::encode::{self, Segment};
+use crate::{hardcode, Version, ECL};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Maximum number of linked symbols
+pub const MAX_SYMBOLS: usize = 16;
+
+/// Splits `input` in at most 16 chunks fitting `max` next to a Structured Append header
+/// and the `prefix` segments.
+///
+/// Chunks are kept at similar sizes: the smallest version still holding the data in as few
+/// symbols as `max` is looked for, then the data is split evenly if possible.
+pub fn split<'a>(
+    input: &'a [u8],
+    ecl: ECL,
+    max: Version,
+    prefix: &[Segment],
+) -> Option<Vec<&'a [u8]>> {
+    let mut header = vec![Segment::structured_append(0, 1, 0)];
+    header.extend_from_slice(prefix);
+    let splitter = Splitter {
+        ecl,
+        header: &header,
+        utf8: core::str::from_utf8(input).is_ok(),
+    };
+
+    let count = splitter.greedy(input, max, MAX_SYMBOLS)?.len();
+
+    let (mut low, mut high) = (Version::V01 as usize, max as usize);
+    while low < high {
+        let mid = (low + high) / 2;
+        match splitter.greedy(input, from_index(mid), count) {
+            Some(_) => high = mid,
+            None => low = mid + 1,
+        }
+    }
+    let version = from_index(low);
+
+    splitter
+        .even(input, version, count)
+        .or_else(|| splitter.greedy(input, version, count))
+}
+
+/// Returns the `Version` at said index (`Version::V01` is 0)
+const fn from_index(index: usize) -> Version {
+    Version::from_n(index * 4 + 21)
+}
+
+struct Splitter<'h> {
+    ecl: ECL,
+    /// Segments placed in front of each chunk
+    header: &'h [Segment],
+    /// Chunks don't cut UTF-8 characters in half
+    utf8: bool,
+}
+
+impl Splitter<'_> {
+    /// Checks if `chunk` fits in `version` next to the header
+    fn fits(&self, chunk: &[u8], version: Version) -> bool {
+        let segments = encode::prefixed_segments(chunk, version, self.header);
+        encode::segments_bits(&segments, version) <= hardcode::data_bits(version, self.ecl)
+    }
+
+    /// Moves `end` back to the start of a UTF-8 character
+    fn boundary(&self, input: &[u8], mut end: usize) -> usize {
+        while self.utf8 && end > 0 && end < input.len() && input[end] & 0b1100_0000 == 0b1000_0000 {
+            end -= 1;
+        }
+        end
+    }
+
+    /// Fills each chunk as much as possible, fails if more than `limit` chunks are needed
+    fn greedy<'a>(&self, input: &'a [u8], version: Version, limit: usize) -> Option<Vec<&'a [u8]>> {
+        let mut chunks = Vec::new();
+        let mut rest = input;
+
+        while !rest.is_empty() {
+            // Adding bytes never shortens the bitstream, so the fitting prefixes are contiguous
+            let (mut low, mut high) = (0, rest.len());
+            while low < high {
+                let mid = (low + high + 1) / 2;
+                if self.fits(&rest[..mid], version) {
+                    low = mid;
+                } else {
+                    high = mid - 1;
+                }
+            }
+
+            let end = self.boundary(rest, low);
+            if end == 0 || chunks.len() == limit {
+                return None;
+            }
+
+            chunks.push(&rest[..end]);
+            rest = &rest[end..];
+        }
+
+        Some(chunks)
+    }
+
+    /// Splits `input` in `count` chunks of the same length, fails if one doesn't fit
+    fn even<'a>(&self, input: &'a [u8], version: Version, count: usize) -> Option<Vec<&'a [u8]>> {
+        let mut chunks = Vec::with_capacity(count);
+        let mut start = 0;
+
+        for i in 1..=count {
+            let end = self.boundary(input, input.len() * i / count);
+            let chunk = &input[start..end];
+            if chunk.is_empty() || !self.fits(chunk, version) {
+                return None;
+            }
+
+            chunks.push(chunk);
+            start = end;
+        }
+
+        Some(chunks)
+    }
+}
@@ -0,0 +1,163 @@
+use crate::qr::QRCodeError;
+use crate::{Mask, MaskEvaluation, Mode, QRBuilder, QRCode, Segment, Version, ECL};
+
+/// Packs `qr` as `build_into` does
+fn pack(qr: &QRCode) -> Vec<u8> {
+    let stride = (qr.size + 7) / 8;
+    let mut bits = vec![0; stride * qr.size];
+
+    for y in 0..qr.size {
+        for x in 0..qr.size {
+            if qr[y][x].value() {
+                bits[y * stride + x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+    }
+
+    bits
+}
+
+/// Checks `build_into` writes the matrix of `build`
+fn assert_same(builder: &QRBuilder) {
+    let qr = builder.build().unwrap();
+    let expected = pack(&qr);
+
+    let mut buffer = [0xAA; 4096];
+    let version = builder.build_into(&mut buffer).unwrap();
+
+    assert_eq!(version.size(), qr.size);
+    assert_eq!(buffer[..expected.len()], expected[..]);
+    // Past the matrix, bytes are left untouched
+    assert!(buffer[expected.len()..].iter().all(|&byte| byte == 0xAA));
+}
+
+#[test]
+fn build_into_single_mode() {
+    assert_same(&QRBuilder::new("0123456789"));
+    assert_same(&QRBuilder::new("HELLO WORLD"));
+    assert_same(QRBuilder::new("https://example.com/").mode(Mode::Byte));
+    assert_same(QRBuilder::new("lowercase text").ecl(ECL::L));
+    assert_same(QRBuilder::new(vec![0x93, 0x5F, 0xE4, 0xAA]).mode(Mode::Kanji));
+    assert_same(QRBuilder::new("0".repeat(7089)).ecl(ECL::L));
+    assert_same(QRBuilder::new("A".repeat(1000)).ecl(ECL::H));
+}
+
+#[test]
+fn build_into_agrees_with_build() {
+    // Single mode input the optimizer keeps as a single segment
+    let inputs = [
+        "31415926535897932384626433832795028841971693993751",
+        "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG",
+        "HTTPS://EXAMPLE.COM/PATH/A",
+        "the quick brown fox jumps over the lazy dog",
+        "mailto:someone@example.com",
+    ];
+
+    for input in inputs {
+        for len in [1, 4, 20] {
+            for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+                assert_same(QRBuilder::new(input.repeat(len)).ecl(ecl));
+            }
+        }
+    }
+}
+
+#[test]
+fn build_into_options() {
+    assert_same(QRBuilder::new("HELLO").version(Version::V07).ecl(ECL::M));
+    assert_same(QRBuilder::new("HELLO").mask(Mask::Meadow));
+    assert_same(QRBuilder::new("HELLO").maximize_ecl(true));
+    assert_same(
+        QRBuilder::new("Hello, world")
+            .version_range(Version::V05, Version::V10)
+            .ecl(ECL::L),
+    );
+    assert_same(QRBuilder::new("h€llo").utf8_eci(true));
+    assert_same(
+        QRBuilder::new(b"01034531200000111719112510ABCD1234\x1d2110".to_vec())
+            .fnc1_first()
+            .mode(Mode::Alphanumeric),
+    );
+    assert_same(&QRBuilder::segments(vec![
+        Segment::numeric("0123456789"),
+        Segment::alphanumeric(" ORDER"),
+        Segment::eci(26),
+        Segment::bytes("🚀"),
+    ]));
+}
+
+#[test]
+fn build_into_iso_evaluation() {
+    for input in ["HELLO WORLD", "https://example.com/", "0123456789"] {
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            assert_same(
+                QRBuilder::new(input)
+                    .ecl(ecl)
+                    .mask_evaluation(MaskEvaluation::Iso),
+            );
+        }
+    }
+
+    for len in [50, 300, 1200] {
+        assert_same(QRBuilder::new("a".repeat(len)).mask_evaluation(MaskEvaluation::Iso));
+    }
+}
+
+#[test]
+fn build_into_errors() {
+    // A V01 takes 21 rows of 3 bytes
+    let mut buffer = [0; 62];
+    let result = QRBuilder::new("HELLO").build_into(&mut buffer);
+    assert!(matches!(
+        result,
+        Err(QRCodeError::BufferTooSmall { needed: 63 })
+    ));
+
+    let mut buffer = [0; 4071];
+    let result = QRBuilder::new("A".repeat(5000)).build_into(&mut buffer);
+    assert!(matches!(result, Err(QRCodeError::EncodedData)));
+
+    let result = QRBuilder::new("a".repeat(100))
+        .version(Version::V02)
+        .build_into(&mut buffer);
+    assert!(matches!(result, Err(QRCodeError::SpecifiedVersion)));
+
+    let result = QRBuilder::new("hello")
+        .mode(Mode::Numeric)
+        .build_into(&mut buffer);
+    assert!(matches!(
+        result,
+        Err(QRCodeError::InvalidCharacter {
+            index: 0,
+            byte: b'h'
+        })
+    ));
+
+    let result = QRBuilder::new("a".repeat(100))
+        .version_range(Version::V01, Version::V03)
+        .build_into(&mut buffer);
+    let expected = QRBuilder::new("a".repeat(100))
+        .version_range(Version::V01, Version::V03)
+        .build();
+    match (result, expected) {
+        (
+            Err(QRCodeError::MaxVersionExceeded { excess }),
+            Err(QRCodeError::MaxVersionExceeded { excess: expected }),
+        ) => assert_eq!(excess, expected),
+        _ => panic!("expected MaxVersionExceeded"),
+    }
+}
+
+#[test]
+fn build_into_mixed_input() {
+    // A single byte segment needs a bigger version than the optimal segments
+    let input = format!("{}abc", "0".repeat(100));
+    let qr = QRBuilder::new(input.clone()).ecl(ECL::L).build().unwrap();
+
+    let mut buffer = [0; 4071];
+    let version = QRBuilder::new(input)
+        .ecl(ECL::L)
+        .build_into(&mut buffer)
+        .unwrap();
+    assert!(version.size() > qr.size);
+}
@@ -27,3 +27,43 @@ fn it_can_output_to_bytes_from_image() {
     // Verify
     assert_eq!(data_uri, expected_data_uri);
 }
+
+#[cfg(feature = "image")]
+#[test]
+fn image_refuses_occlusion() {
+    use crate::convert::image::{ImageBuilder, ImageError};
+    use crate::convert::Builder;
+    use crate::{QRBuilder, Version, ECL};
+
+    let mut builder = ImageBuilder::default();
+    builder.image(String::from("logo.png"));
+
+    let qrcode = QRBuilder::new("FAST QR")
+        .version(Version::V02)
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+    assert!(matches!(
+        builder.try_to_bytes(&qrcode),
+        Err(ImageError::ImageOcclusion(_))
+    ));
+    assert!(matches!(
+        builder.try_to_pixmap(&qrcode),
+        Err(ImageError::ImageOcclusion(_))
+    ));
+    assert!(matches!(
+        builder.try_to_file(&qrcode, "occluded.png"),
+        Err(ImageError::ImageOcclusion(_))
+    ));
+
+    let raised = builder
+        .raise_ecl(QRBuilder::new("https://example.com/").ecl(ECL::L))
+        .unwrap();
+    assert!(matches!(raised.ecl, Some(ECL::Q | ECL::H)));
+    assert!(builder.try_to_bytes(&raised).is_ok());
+
+    assert!(matches!(
+        builder.raise_ecl(QRBuilder::new("FAST QR").version(Version::V02)),
+        Err(ImageError::ImageOcclusion(_))
+    ));
+}
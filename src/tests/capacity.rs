@@ -0,0 +1,103 @@
+use crate::capacity::{self, Capacity};
+use crate::{Mode, QRBuilder, Version, ECL};
+
+#[test]
+fn capacity_matches_version_tables() {
+    for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+        for capacity in capacity::table(ecl) {
+            for mode in [Mode::Numeric, Mode::Alphanumeric, Mode::Byte, Mode::Kanji] {
+                let max = capacity.chars(mode);
+                let version = capacity.version as usize;
+
+                let fits = Version::get(mode, ecl, max).map(|v| v as usize);
+                let overflows = Version::get(mode, ecl, max + 1).map(|v| v as usize);
+                assert_eq!(fits, Some(version), "V{} {ecl} {mode:?}", version + 1);
+                assert_ne!(overflows, Some(version), "V{} {ecl} {mode:?}", version + 1);
+            }
+        }
+    }
+}
+
+#[test]
+fn capacity_table() {
+    let table = capacity::table(ECL::H);
+    assert_eq!(table.len(), 40);
+
+    for (i, capacity) in table.iter().enumerate() {
+        assert_eq!(capacity.version as usize, i);
+        assert_eq!(capacity.size, i * 4 + 21);
+    }
+
+    let v01 = Capacity::new(Version::V01, ECL::H);
+    assert_eq!(v01.data_codewords, 9);
+    assert_eq!(
+        [v01.numeric, v01.alphanumeric, v01.bytes, v01.kanji],
+        [17, 10, 7, 4]
+    );
+
+    let v40 = Capacity::new(Version::V40, ECL::L);
+    assert_eq!(v40.data_codewords, 2956);
+    assert_eq!(
+        [v40.numeric, v40.alphanumeric, v40.bytes, v40.kanji],
+        [7089, 4296, 2953, 1817]
+    );
+}
+
+#[test]
+fn capacity_smallest_version() {
+    let inputs: [&[u8]; 4] = [
+        b"0123456789",
+        b"HELLO WORLD",
+        b"https://example.com/?query=fast_qr",
+        &[0x42; 1000],
+    ];
+
+    for input in inputs {
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let qr = QRBuilder::new(input).ecl(ecl).build().unwrap();
+            let version = capacity::smallest_version(input, ecl).unwrap();
+            assert_eq!(version as usize, qr.version.unwrap() as usize);
+        }
+    }
+
+    assert!(capacity::smallest_version(&[0; 2954], ECL::L).is_none());
+}
+
+#[test]
+fn capacity_bits() {
+    let input = b"01234567";
+    assert_eq!(
+        capacity::bits(input, Mode::Numeric, Version::V01),
+        Some(4 + 10 + 27)
+    );
+    assert_eq!(
+        capacity::bits(input, Mode::Numeric, Version::V10),
+        Some(4 + 12 + 27)
+    );
+    assert_eq!(
+        capacity::bits(input, Mode::Numeric, Version::V27),
+        Some(4 + 14 + 27)
+    );
+    assert_eq!(
+        capacity::bits(input, Mode::Alphanumeric, Version::V01),
+        Some(4 + 9 + 44)
+    );
+    assert_eq!(
+        capacity::bits(input, Mode::Byte, Version::V01),
+        Some(4 + 8 + 64)
+    );
+    assert_eq!(capacity::bits(input, Mode::Kanji, Version::V01), None);
+
+    // "点茗" in Shift JIS
+    let kanji = [0x93, 0x5F, 0xE4, 0xAA];
+    assert_eq!(
+        capacity::bits(&kanji, Mode::Kanji, Version::V01),
+        Some(4 + 8 + 26)
+    );
+    assert_eq!(capacity::bits(&kanji[..3], Mode::Kanji, Version::V01), None);
+
+    // Mixed input is split, the shortest single mode being byte
+    let mixed = b"ORDER 000000000000000000 shipped";
+    let optimal = capacity::optimal_bits(mixed, Version::V01);
+    assert!(optimal < capacity::bits(mixed, Mode::Byte, Version::V01).unwrap());
+}
@@ -236,3 +236,41 @@ fn mask_meadow_test() {
         ]
     );
 }
+
+#[test]
+fn mask_canvas_matches_mask() {
+    use crate::datamasking::{is_toggled, mask, mask_canvas};
+    use crate::module::Module;
+
+    let masks = [
+        Mask::Checkerboard,
+        Mask::HorizontalLines,
+        Mask::VerticalLines,
+        Mask::DiagonalLines,
+        Mask::LargeCheckerboard,
+        Mask::Fields,
+        Mask::Diamonds,
+        Mask::Meadow,
+    ];
+
+    for size in [21, 25, 45, 177] {
+        let mut template = QRCode::default(size);
+        // A function module is never toggled
+        template[6][9] = Module::timing(Module::DARK);
+
+        for m in masks {
+            let mut expected = template.clone();
+            mask(&mut expected, m);
+            let mut qr = template.clone();
+            mask_canvas(&mut qr, m);
+
+            for y in 0..size {
+                for x in 0..size {
+                    let toggled = is_toggled(m, y, x) && (y, x) != (6, 9);
+                    assert_eq!(expected[y][x].value(), toggled || (y, x) == (6, 9));
+                    assert_eq!(qr[y][x].value(), expected[y][x].value());
+                }
+            }
+        }
+    }
+}
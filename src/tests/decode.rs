@@ -0,0 +1,206 @@
+use crate::decode::{self, DecodeError};
+use crate::gs1::Gs1Builder;
+use crate::module::ModuleType;
+use crate::{polynomials, Mask, MicroQRBuilder, QRBuilder, RMQRBuilder, Segment, Version, ECL};
+
+/// Pseudo-random bytes, deterministic across runs
+fn noise(len: usize) -> Vec<u8> {
+    let mut state: u32 = 0x2545_F491;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (state >> 16) as u8
+        })
+        .collect()
+}
+
+#[test]
+fn decode_round_trips() {
+    let inputs: [&[u8]; 5] = [
+        b"0123456789012",
+        b"HELLO WORLD 42 $%*+-./:",
+        b"https://example.com/?q=fast_qr",
+        "ünïcödé bytes".as_bytes(),
+        &noise(900),
+    ];
+    let masks = [
+        Mask::Checkerboard,
+        Mask::HorizontalLines,
+        Mask::VerticalLines,
+        Mask::DiagonalLines,
+        Mask::LargeCheckerboard,
+        Mask::Fields,
+        Mask::Diamonds,
+        Mask::Meadow,
+    ];
+
+    for input in inputs {
+        for (i, ecl) in [ECL::L, ECL::M, ECL::Q, ECL::H].into_iter().enumerate() {
+            let mask = masks[(i + input.len()) % masks.len()];
+            let qr = QRBuilder::new(input).ecl(ecl).mask(mask).build().unwrap();
+            let decoded = decode::from_qrcode(&qr).unwrap();
+
+            assert_eq!(decoded.data, input);
+            assert_eq!(decoded.version as usize, qr.version.unwrap() as usize);
+            assert_eq!(decoded.ecl as usize, ecl as usize);
+            assert_eq!(decoded.mask as usize, mask as usize);
+            assert_eq!(decoded.corrected, 0);
+        }
+    }
+}
+
+#[test]
+fn decode_headers() {
+    let segments = vec![
+        Segment::numeric("0042"),
+        Segment::kanji([0x93, 0x5F, 0xE4, 0xAA]),
+        Segment::alphanumeric("AB-CD"),
+        Segment::bytes("end"),
+    ];
+    let qr = QRBuilder::segments(segments).build().unwrap();
+    let decoded = decode::from_qrcode(&qr).unwrap();
+    assert_eq!(decoded.data, b"0042\x93\x5F\xE4\xAAAB-CDend");
+
+    let qr = QRBuilder::new("héllo").utf8_eci(true).build().unwrap();
+    assert_eq!(decode::from_qrcode(&qr).unwrap().data, "héllo".as_bytes());
+
+    let mut gs1 = Gs1Builder::new();
+    gs1.element("01", "09506000134352")
+        .element("10", "AB%12")
+        .element("17", "201231");
+    let qr = gs1.qr_builder().unwrap().build().unwrap();
+    assert_eq!(decode::from_qrcode(&qr).unwrap().data, gs1.data().unwrap());
+
+    let input = noise(4000);
+    let symbols = QRBuilder::new(input.clone())
+        .ecl(ECL::L)
+        .build_structured_append()
+        .unwrap();
+    let joined = symbols
+        .iter()
+        .flat_map(|qr| decode::from_qrcode(qr).unwrap().data)
+        .collect::<Vec<_>>();
+    assert_eq!(joined, input);
+}
+
+#[test]
+fn decode_corrects_errors() {
+    let input = "https://example.com/fast_qr/decode";
+    let qr = QRBuilder::new(input)
+        .ecl(ECL::H)
+        .version(Version::V08)
+        .build()
+        .unwrap();
+    let size = qr.size;
+
+    let mut modules = qr.data[..size * size]
+        .iter()
+        .map(|module| module.value())
+        .collect::<Vec<_>>();
+
+    // Damages the format & version information, and a square of data
+    for i in 0..3 {
+        modules[8 * size + i] = !modules[8 * size + i];
+        modules[i * size + size - 11] = !modules[i * size + size - 11];
+    }
+    for y in 20..28 {
+        for x in 20..28 {
+            if qr[y][x].module_type() == ModuleType::Data {
+                modules[y * size + x] = !modules[y * size + x];
+            }
+        }
+    }
+
+    let decoded = decode::from_modules(&modules, size).unwrap();
+    assert_eq!(decoded.data, input.as_bytes());
+    assert!(matches!(decoded.ecl, ECL::H));
+    assert!(decoded.corrected > 0);
+
+    // Wipes most of the data
+    for y in 9..size - 9 {
+        for x in 9..size - 9 {
+            modules[y * size + x] = (x * 7 + y * 3) % 5 == 0;
+        }
+    }
+    let decoded = decode::from_modules(&modules, size);
+    assert!(matches!(
+        decoded,
+        Err(DecodeError::TooManyErrors | DecodeError::InvalidData)
+    ));
+}
+
+#[test]
+fn decode_invalid_sizes() {
+    let micro = MicroQRBuilder::new("12345").build().unwrap();
+    let rmqr = RMQRBuilder::new("12345").build().unwrap();
+
+    assert_eq!(
+        decode::from_qrcode(&micro).err(),
+        Some(DecodeError::InvalidSize)
+    );
+    assert_eq!(
+        decode::from_qrcode(&rmqr).err(),
+        Some(DecodeError::InvalidSize)
+    );
+    assert_eq!(
+        decode::from_modules(&[false; 22 * 22], 22).err(),
+        Some(DecodeError::InvalidSize)
+    );
+    assert_eq!(
+        decode::from_modules(&[false; 21 * 20], 21).err(),
+        Some(DecodeError::InvalidSize)
+    );
+    assert_eq!(
+        decode::from_modules(&[false; 21 * 21], 21).err(),
+        Some(DecodeError::FormatInformation)
+    );
+}
+
+#[test]
+fn polynomials_correct() {
+    const ECC: usize = 10;
+    let data = noise(30);
+    let generator = crate::hardcode::get_polynomial(Version::V01, ECL::M);
+    let division = polynomials::division(&data, generator);
+
+    let mut block = data.clone();
+    block.extend_from_slice(&division[256 - generator.len()..255]);
+    let clean = block.clone();
+
+    assert_eq!(polynomials::correct(&mut block, ECC), Some(0));
+
+    for (errors, step) in [(1, 7), (3, 11), (5, 8)] {
+        let mut damaged = clean.clone();
+        for i in 0..errors {
+            damaged[i * step] ^= 0x5A + i as u8;
+        }
+        assert_eq!(polynomials::correct(&mut damaged, ECC), Some(errors));
+        assert_eq!(damaged, clean);
+    }
+
+    let mut damaged = clean.clone();
+    for byte in damaged.iter_mut().take(8) {
+        *byte ^= 0xFF;
+    }
+    assert_ne!(polynomials::correct(&mut damaged, ECC), Some(0));
+    assert_ne!(damaged.get(..30), Some(&data[..]));
+}
+
+#[test]
+fn decode_qrcode_crate() {
+    let inputs: [&[u8]; 3] = [b"01234567890123456789", b"FAST QR DECODE", &noise(300)];
+
+    for input in inputs {
+        for level in [qrcode::EcLevel::L, qrcode::EcLevel::H] {
+            let code = qrcode::QrCode::with_error_correction_level(input, level).unwrap();
+            let modules = code
+                .to_colors()
+                .iter()
+                .map(|&color| color == qrcode::Color::Dark)
+                .collect::<Vec<_>>();
+
+            let decoded = decode::from_modules(&modules, code.width()).unwrap();
+            assert_eq!(decoded.data, input);
+        }
+    }
+}
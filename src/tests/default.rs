@@ -1,5 +1,5 @@
 use crate::module::Module;
-use crate::QRCode;
+use crate::{MicroQRBuilder, QRBuilder, QRCode, RMQRBuilder, Version};
 
 pub(crate) const F: bool = false;
 pub(crate) const T: bool = true;
@@ -279,3 +279,28 @@ fn transpose() {
         }
     }
 }
+
+#[test]
+fn data_sized_to_matrix() {
+    let v01 = QRBuilder::new("Hello")
+        .version(Version::V01)
+        .build()
+        .unwrap();
+    assert_eq!(v01.data.len(), 21 * 21);
+
+    let v40 = QRBuilder::new("Hello")
+        .version(Version::V40)
+        .build()
+        .unwrap();
+    assert_eq!(v40.data.len(), 177 * 177);
+
+    let micro = MicroQRBuilder::new("12345").build().unwrap();
+    assert_eq!(micro.data.len(), micro.size * micro.size);
+
+    let rmqr = RMQRBuilder::new("12345").build().unwrap();
+    assert_eq!(rmqr.data.len(), rmqr.size * rmqr.height);
+    assert!(rmqr.height < rmqr.size);
+
+    let clone = v01.clone();
+    assert_eq!(clone.data[..], v01.data[..]);
+}
@@ -0,0 +1,159 @@
+use crate::decode::{self, DecodeError};
+use crate::{QRBuilder, QRCode, Version, ECL};
+
+/// Renders a `QRCode` in a `width x width` grayscale image, `to_module` mapping each pixel
+/// center to module coordinates. Outside of the `QRCode` is white.
+fn render<F: Fn(f64, f64) -> (f64, f64)>(qr: &QRCode, width: usize, to_module: F) -> Vec<u8> {
+    let mut pixels = vec![255u8; width * width];
+    for y in 0..width {
+        for x in 0..width {
+            let (mx, my) = to_module(x as f64 + 0.5, y as f64 + 0.5);
+            if mx < 0f64 || my < 0f64 {
+                continue;
+            }
+
+            let (mx, my) = (mx as usize, my as usize);
+            if mx < qr.size && my < qr.size && qr[my][mx].value() {
+                pixels[y * width + x] = 0;
+            }
+        }
+    }
+    pixels
+}
+
+#[test]
+fn detect_axis_aligned() {
+    for (input, version) in [("Hello", Version::V01), ("Hello", Version::V05)] {
+        let qrcode = QRBuilder::new(input).version(version).build().unwrap();
+        let scale = 5f64;
+        let width = (qrcode.size + 8) * 5;
+
+        let pixels = render(&qrcode, width, |x, y| (x / scale - 4f64, y / scale - 4f64));
+        let decoded = decode::from_luma(&pixels, width, width).unwrap();
+
+        assert_eq!(decoded.data, input.as_bytes());
+        assert_eq!(decoded.version as usize, version as usize);
+    }
+}
+
+#[test]
+fn detect_rotated() {
+    let qrcode = QRBuilder::new("https://example.com/rotated")
+        .ecl(ECL::Q)
+        .build()
+        .unwrap();
+    let width = 300;
+    let center = width as f64 / 2f64;
+    let half = qrcode.size as f64 / 2f64;
+
+    for degrees in [10f64, 45f64, 90f64, 160f64, 200f64, 290f64] {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let pixels = render(&qrcode, width, |x, y| {
+            let (dx, dy) = ((x - center) / 6f64, (y - center) / 6f64);
+            (dx * cos + dy * sin + half, dy * cos - dx * sin + half)
+        });
+
+        let decoded = decode::from_luma(&pixels, width, width).unwrap();
+        assert_eq!(
+            decoded.data, b"https://example.com/rotated",
+            "{degrees} degrees"
+        );
+    }
+}
+
+#[test]
+fn detect_perspective() {
+    let qrcode = QRBuilder::new("0123456789".repeat(10))
+        .version(Version::V07)
+        .build()
+        .unwrap();
+    let width = 400;
+
+    // Modules shrink towards the right and the bottom
+    let pixels = render(&qrcode, width, |x, y| {
+        let w = 1f64 + x * 0.0012 + y * 0.0006;
+        ((x - 40f64) * w / 6f64, (y - 30f64) * w / 6f64)
+    });
+
+    let decoded = decode::from_luma(&pixels, width, width).unwrap();
+    assert_eq!(decoded.data, "0123456789".repeat(10).as_bytes());
+}
+
+#[test]
+fn detect_rgba() {
+    let qrcode = QRBuilder::new("RGBA").build().unwrap();
+    let width = (qrcode.size + 8) * 4;
+    let luma = render(&qrcode, width, |x, y| (x / 4f64 - 4f64, y / 4f64 - 4f64));
+
+    // Dark blue modules on a transparent background
+    let rgba = luma
+        .iter()
+        .flat_map(|&pixel| {
+            if pixel == 0 {
+                [20, 30, 120, 255]
+            } else {
+                [0, 0, 0, 0]
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let decoded = decode::from_rgba(&rgba, width, width).unwrap();
+    assert_eq!(decoded.data, b"RGBA");
+}
+
+#[test]
+fn detect_errors() {
+    assert_eq!(
+        decode::from_luma(&[255; 10], 4, 4).err(),
+        Some(DecodeError::InvalidSize)
+    );
+    assert_eq!(
+        decode::from_rgba(&[255; 16], 2, 3).err(),
+        Some(DecodeError::InvalidSize)
+    );
+    assert_eq!(
+        decode::from_luma(&[255; 100 * 100], 100, 100).err(),
+        Some(DecodeError::NotFound)
+    );
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn detect_styled_pixmaps() {
+    use crate::convert::image::ImageBuilder;
+    use crate::convert::{Builder, Shape};
+
+    let qrcode = QRBuilder::new("https://example.com/")
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+
+    let shapes = [
+        Shape::Square,
+        Shape::Circle,
+        Shape::RoundedSquare,
+        Shape::Vertical,
+        Shape::Horizontal,
+        Shape::Diamond,
+    ];
+    for shape in shapes {
+        let name: &str = shape.into();
+        let pixmap = ImageBuilder::default()
+            .shape(shape)
+            .fit_width(400)
+            .to_pixmap(&qrcode);
+
+        let decoded = decode::from_pixmap(&pixmap).expect(name);
+        assert_eq!(decoded.data, b"https://example.com/", "{name}");
+    }
+
+    let image_base64 = "iVBORw0KGgoAAAANSUhEUgAAABAAAAAQCAIAAACQkWg2AAAAFUlEQVR4AWP4oyVDEhrGGkY1jGoAABACQhA+7XDPAAAAAElFTkSuQmCC";
+    let pixmap = ImageBuilder::default()
+        .shape(Shape::Circle)
+        .image(format!("data:image/png;base64,{image_base64}"))
+        .fit_width(400)
+        .to_pixmap(&qrcode);
+
+    let decoded = decode::from_pixmap(&pixmap).unwrap();
+    assert_eq!(decoded.data, b"https://example.com/");
+}
@@ -0,0 +1,75 @@
+use crate::{Mode, QRBuilder, Segment, Version, ECL};
+
+#[test]
+fn utf8_eci_prefixes_non_ascii() {
+    let auto = QRBuilder::new("héllo wörld 🚀")
+        .utf8_eci(true)
+        .build()
+        .unwrap();
+
+    let explicit = QRBuilder::segments(vec![Segment::eci(26), Segment::bytes("héllo wörld 🚀")])
+        .build()
+        .unwrap();
+
+    assert_eq!(auto.data[..], explicit.data[..]);
+    assert_eq!(auto.mode, Some(Mode::Byte));
+}
+
+#[test]
+fn utf8_eci_skips_ascii() {
+    let with = QRBuilder::new("https://example.com/")
+        .utf8_eci(true)
+        .build()
+        .unwrap();
+    let without = QRBuilder::new("https://example.com/").build().unwrap();
+
+    assert_eq!(with.data[..], without.data[..]);
+}
+
+#[test]
+fn utf8_eci_skips_non_utf8() {
+    // "点茗" in Shift JIS
+    let input = [0x93, 0x5F, 0xE4, 0xAA];
+    let with = QRBuilder::new(input).utf8_eci(true).build().unwrap();
+    let without = QRBuilder::new(input).build().unwrap();
+
+    assert_eq!(with.data[..], without.data[..]);
+}
+
+#[test]
+fn utf8_eci_is_opt_in() {
+    let default = QRBuilder::new("héllo").build().unwrap();
+    let explicit = QRBuilder::segments(vec![Segment::bytes("héllo")])
+        .build()
+        .unwrap();
+
+    assert_eq!(default.data[..], explicit.data[..]);
+}
+
+#[test]
+fn utf8_eci_accounts_for_header_bits() {
+    // 17 bytes fill V01-L in byte mode (148 of 152 bits), the 12 bits header doesn't fit
+    let input = "éééééééabc";
+    assert_eq!(input.len(), 17);
+
+    let without = QRBuilder::new(input).ecl(ECL::L).build().unwrap();
+    let with = QRBuilder::new(input)
+        .ecl(ECL::L)
+        .utf8_eci(true)
+        .build()
+        .unwrap();
+
+    assert!(matches!(without.version, Some(Version::V01)));
+    assert!(matches!(with.version, Some(Version::V02)));
+}
+
+#[test]
+fn utf8_eci_forced_version() {
+    let qr = QRBuilder::new("éééééééabc")
+        .ecl(ECL::L)
+        .version(Version::V01)
+        .utf8_eci(true)
+        .build();
+
+    assert!(qr.is_err());
+}
@@ -0,0 +1,98 @@
+use crate::qr::QRCodeError;
+use crate::{QRBuilder, Segment, Version, ECL};
+
+#[test]
+fn maximize_ecl_at_forced_version() {
+    // Version 1 holds 25, 20, 16 and 10 alphanumeric characters at L, M, Q and H
+    let cases = [(10, ECL::H), (16, ECL::Q), (17, ECL::M), (25, ECL::L)];
+
+    for (len, expected) in cases {
+        let qr = QRBuilder::new("A".repeat(len))
+            .version(Version::V01)
+            .maximize_ecl(true)
+            .build()
+            .unwrap();
+
+        assert!(matches!(qr.version, Some(Version::V01)));
+        assert_eq!(
+            qr.ecl.unwrap() as usize,
+            expected as usize,
+            "{len} characters"
+        );
+    }
+
+    let qr = QRBuilder::new("A".repeat(26))
+        .version(Version::V01)
+        .maximize_ecl(true)
+        .build();
+    assert!(matches!(qr, Err(QRCodeError::SpecifiedVersion)));
+}
+
+#[test]
+fn maximize_ecl_at_minimum_version() {
+    // Smallest version is the one at L
+    let qr = QRBuilder::new("HELLO WORLD")
+        .maximize_ecl(true)
+        .build()
+        .unwrap();
+    assert!(matches!(qr.version, Some(Version::V01)));
+    assert!(matches!(qr.ecl, Some(ECL::Q)));
+
+    // A forced ECL is the lowest level tried
+    let qr = QRBuilder::new("A".repeat(24))
+        .ecl(ECL::M)
+        .maximize_ecl(true)
+        .build()
+        .unwrap();
+    assert!(matches!(qr.version, Some(Version::V02)));
+    assert!(matches!(qr.ecl, Some(ECL::Q)));
+
+    let qr = QRBuilder::new("A".repeat(24))
+        .ecl(ECL::H)
+        .maximize_ecl(true)
+        .build()
+        .unwrap();
+    assert!(matches!(qr.version, Some(Version::V03)));
+    assert!(matches!(qr.ecl, Some(ECL::H)));
+
+    // Without the option, the default stays Q
+    let qr = QRBuilder::new("A".repeat(24)).build().unwrap();
+    assert!(matches!(qr.ecl, Some(ECL::Q)));
+
+    // Mixed input is segmented as `build` does, then raised to the min of the range
+    let input = "abc0123456789012345678901234567890123456789";
+    let lowest = QRBuilder::new(input).ecl(ECL::L).build().unwrap();
+    let qr = QRBuilder::new(input).maximize_ecl(true).build().unwrap();
+    assert_eq!(
+        qr.version.unwrap() as usize,
+        lowest.version.unwrap() as usize
+    );
+
+    let qr = QRBuilder::new(input)
+        .version_range(Version::V05, Version::V10)
+        .maximize_ecl(true)
+        .build()
+        .unwrap();
+    assert!(matches!(qr.version, Some(Version::V05)));
+    assert!(matches!(qr.ecl, Some(ECL::H)));
+}
+
+#[test]
+fn maximize_ecl_segments() {
+    let qr = QRBuilder::segments(vec![Segment::numeric("0123456789")])
+        .version(Version::V01)
+        .maximize_ecl(true)
+        .build()
+        .unwrap();
+    assert!(matches!(qr.ecl, Some(ECL::H)));
+
+    let qr = QRBuilder::new(vec![0u8; 2953])
+        .maximize_ecl(true)
+        .build()
+        .unwrap();
+    assert!(matches!(qr.version, Some(Version::V40)));
+    assert!(matches!(qr.ecl, Some(ECL::L)));
+
+    let qr = QRBuilder::new(vec![0u8; 2954]).maximize_ecl(true).build();
+    assert!(matches!(qr, Err(QRCodeError::EncodedData)));
+}
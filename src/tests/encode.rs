@@ -1,7 +1,8 @@
 use crate::compact::{CompactQR, KEEP_LAST};
 use crate::encode;
-use crate::encode::Mode;
+use crate::encode::{Mode, Segment};
 use crate::hardcode::cci_bits;
+use crate::Version;
 
 #[test]
 fn best_encoding_numeric_0() {
@@ -45,12 +46,33 @@ fn best_encoding_byte_2() {
     assert_eq!(Mode::Byte, res);
 }
 
+#[test]
+fn best_encoding_kanji_0() {
+    // "点茗" in Shift JIS
+    let res = encode::best_encoding(&[0x93, 0x5F, 0xE4, 0xAA]);
+    assert_eq!(Mode::Kanji, res);
+}
+
+#[test]
+fn best_encoding_kanji_odd_length() {
+    let res = encode::best_encoding(&[0x93, 0x5F, 0xE4]);
+    assert_eq!(Mode::Byte, res);
+}
+
+#[test]
+fn best_encoding_kanji_utf8_stays_byte() {
+    // "あい" in UTF-8, also valid Shift JIS pairs
+    let res = encode::best_encoding("あい".as_bytes());
+    assert_eq!(Mode::Byte, res);
+}
+
 fn test_encode_header(compact: &CompactQR, input: &[u8], expected_mode: Mode) {
     let res = compact.get_data();
     let mode = match res[0] >> 4 {
         0b0001 => Mode::Numeric,
         0b0010 => Mode::Alphanumeric,
         0b0100 => Mode::Byte,
+        0b1000 => Mode::Kanji,
         _ => panic!("Invalid encoding mode"),
     };
 
@@ -60,7 +82,7 @@ fn test_encode_header(compact: &CompactQR, input: &[u8], expected_mode: Mode) {
         expected_mode
     );
 
-    let cci = cci_bits(crate::Version::V01, mode) as u16;
+    let cci = cci_bits(Version::V01, mode) as u16;
     let character_count: u16 = {
         let first_nb_bits = 4;
         let second_nb_bits = cci - first_nb_bits;
@@ -150,7 +172,7 @@ fn encode_numeric_1() {
 
     // 13, '589'
     assert_eq!(res[1] & 0b0000_0011, 589 >> 8);
-    assert_eq!(res[2] & 0b1111_1111, (589 << 0) & keep_last[8]);
+    assert_eq!(res[2] & 0b1111_1111, 589 & keep_last[8]);
     // 24, '4'
     assert_eq!(res[3] & 0b1111_0000, (4 << 4) & keep_last[8]);
 }
@@ -173,7 +195,7 @@ fn encode_numeric_2() {
 
     // 13, '589'
     assert_eq!(res[1] & 0b0000_0011, 589 >> 8);
-    assert_eq!(res[2] & 0b1111_1111, (589 << 0) & keep_last[8]);
+    assert_eq!(res[2] & 0b1111_1111, 589 & keep_last[8]);
     // 24, '49'
     assert_eq!(res[3] & 0b1111_1110, 49 << 1 & keep_last[8]);
 }
@@ -196,7 +218,7 @@ fn encode_numeric_3() {
 
     // 13, '589'
     assert_eq!(res[1] & 0b0000_0011, 589 >> 8);
-    assert_eq!(res[2] & 0b1111_1111, (589 << 0) & keep_last[8]);
+    assert_eq!(res[2] & 0b1111_1111, 589 & keep_last[8]);
     // 24, '491'
     assert_eq!(res[3] & 0b1111_1111, (491 >> 2) & keep_last[8]);
     assert_eq!(res[4] & 0b1100_0000, (491 << 6) & keep_last[8]);
@@ -220,7 +242,7 @@ fn encode_numeric_4() {
 
     // 13, '200'
     assert_eq!(res[1] & 0b0000_0011, 200 >> 8);
-    assert_eq!(res[2] & 0b1111_1111, (200 << 0) & keep_last[8]);
+    assert_eq!(res[2] & 0b1111_1111, 200 & keep_last[8]);
     // 24, '505'
     assert_eq!(res[3] & 0b1111_1111, (505 >> 2) & keep_last[8]);
     assert_eq!(res[4] & 0b1100_0000, (505 << 6) & keep_last[8]);
@@ -231,3 +253,172 @@ fn encode_numeric_4() {
     assert_eq!(res[5] & 0b0000_1111, (1) >> 6);
     assert_eq!(res[6] & 0b1111_1100, (1) << 2 & keep_last[8]);
 }
+
+#[test]
+fn encode_kanji_1() {
+    let mut compact = CompactQR::new();
+    // "点茗" in Shift JIS
+    const INPUT: &[u8] = &[0x93, 0x5F, 0xE4, 0xAA];
+    encode::encode_kanji(&mut compact, INPUT, 8);
+
+    // 1000 | 00000010 | 0110110011111 | 1101010101010
+    assert_eq!(compact.len(), 38);
+    assert_eq!(
+        &compact.get_data()[..5],
+        &[
+            0b1000_0000,
+            0b0010_0110,
+            0b1100_1111,
+            0b1110_1010,
+            0b1010_1000
+        ]
+    );
+}
+
+#[test]
+fn kanji_picks_smaller_version() {
+    use crate::{QRCode, ECL};
+
+    // 20 kanji fit in V02-L, the same 40 bytes need V03-L
+    let input = [0x93, 0x5F].repeat(20);
+    let qr = QRCode::new(&input, Some(ECL::L), None, None).unwrap();
+
+    assert_eq!(qr.mode, Some(Mode::Kanji));
+    assert!(matches!(qr.version, Some(Version::V02)));
+}
+
+fn segment(mode: Mode, data: &[u8]) -> Segment {
+    Segment::Data(mode, data.to_vec())
+}
+
+#[test]
+fn optimal_segments_mixed() {
+    let segments = encode::optimal_segments(
+        b"ORDER 000123456789 for user@example.com",
+        Version::V01,
+        false,
+    );
+
+    assert_eq!(
+        segments,
+        [
+            segment(Mode::Alphanumeric, b"ORDER "),
+            segment(Mode::Numeric, b"000123456789"),
+            segment(Mode::Byte, b" for user@example.com"),
+        ]
+    );
+    // 4 + 9 + 33 | 4 + 10 + 40 | 4 + 8 + 168
+    assert_eq!(encode::segments_bits(&segments, Version::V01), 280);
+}
+
+#[test]
+fn optimal_segments_single_byte() {
+    let segments = encode::optimal_segments(b"https://example.com/", Version::V01, false);
+    assert_eq!(segments, [segment(Mode::Byte, b"https://example.com/")]);
+}
+
+#[test]
+fn optimal_segments_short_run_stays_merged() {
+    // Switching to numeric for two digits costs more than it saves
+    let segments = encode::optimal_segments(b"hello12world", Version::V01, false);
+    assert_eq!(segments, [segment(Mode::Byte, b"hello12world")]);
+}
+
+#[test]
+fn optimal_segments_kanji() {
+    // "点茗" in Shift JIS followed by "123"
+    let input = [0x93, 0x5F, 0xE4, 0xAA, b'1', b'2', b'3'];
+    let segments = encode::optimal_segments(&input, Version::V01, false);
+
+    assert_eq!(
+        segments,
+        [
+            segment(Mode::Kanji, &input[..4]),
+            segment(Mode::Numeric, b"123"),
+        ]
+    );
+}
+
+#[test]
+fn optimal_segments_matches_brute_force() {
+    // Smallest cost over every split of `input`, each part in every valid mode
+    fn brute_force(input: &[u8], version: Version) -> usize {
+        if input.is_empty() {
+            return 0;
+        }
+
+        let mut best = usize::MAX;
+        for end in 1..=input.len() {
+            let part = &input[..end];
+            for mode in [Mode::Numeric, Mode::Alphanumeric, Mode::Byte] {
+                let valid = part.iter().all(|&c| match mode {
+                    Mode::Numeric => c.is_ascii_digit(),
+                    Mode::Alphanumeric => encode::is_qr_alphanumeric(c),
+                    _ => true,
+                });
+                if !valid {
+                    continue;
+                }
+
+                let cost = segment(mode, part).bits(version) + brute_force(&input[end..], version);
+                best = best.min(cost);
+            }
+        }
+        best
+    }
+
+    for input in [
+        &b"0123a"[..],
+        b"A12345B",
+        b"ab123456",
+        b"AB12cd34",
+        b"1A2B3C4D",
+        b"HELLO WORLD!",
+        b"x0000000Y",
+    ] {
+        for version in [Version::V01, Version::V10, Version::V27] {
+            let segments = encode::optimal_segments(input, version, false);
+            assert_eq!(
+                encode::segments_bits(&segments, version),
+                brute_force(input, version),
+                "{:?}",
+                String::from_utf8_lossy(input)
+            );
+            let data = segments.iter().flat_map(|s| s.data().to_vec());
+            assert!(data.eq(input.iter().copied()));
+        }
+    }
+}
+
+#[test]
+fn mixed_segments_pick_smaller_version() {
+    use crate::{QRCode, ECL};
+
+    // 49 bytes need V04-M in byte mode, mixed segments fit in V03-M
+    let input = b"ORDER 0001234567890123456789 for user@example.com";
+    let qr = QRCode::new(input, Some(ECL::M), None, None).unwrap();
+
+    assert_eq!(qr.mode, None);
+    assert!(matches!(qr.version, Some(Version::V03)));
+}
+
+#[test]
+fn encode_eci_sizes() {
+    for (assignment, expected) in [
+        (26, "0111_00011010"),
+        (1000, "0111_10_00001111101000"),
+        (999_999, "0111_110_011110100001000111111"),
+    ] {
+        let mut compact = CompactQR::with_len(32);
+        encode::encode_eci(&mut compact, assignment);
+
+        let bits = (0..compact.len())
+            .map(|i| match compact.get_data()[i / 8] & (0x80 >> (i % 8)) {
+                0 => '0',
+                _ => '1',
+            })
+            .collect::<String>();
+
+        assert_eq!(bits, expected.replace('_', ""), "ECI {}", assignment);
+    }
+}
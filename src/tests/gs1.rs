@@ -0,0 +1,158 @@
+use crate::compact::CompactQR;
+use crate::encode::{self, Mode};
+use crate::gs1::{Gs1Builder, Gs1Error};
+use crate::qr::QRCodeError;
+use crate::{QRBuilder, Segment, Version, ECL};
+
+/// Renders the first `len` bits of the bitstream of `segments`
+fn bits(segments: &[Segment], len: usize) -> String {
+    let compact: CompactQR = encode::encode(segments, ECL::L, Version::V01);
+
+    (0..len)
+        .map(|i| compact.get_data()[i / 8] >> (7 - i % 8) & 1)
+        .map(|b| if b == 1 { '1' } else { '0' })
+        .collect()
+}
+
+#[test]
+fn fnc1_headers() {
+    let first = bits(&[Segment::fnc1_first(), Segment::numeric("01")], 18);
+    assert_eq!(first, "0101_0001_0000000010_".replace('_', ""));
+
+    // Letter `A` is written as 65 + 100
+    let second = bits(&[Segment::fnc1_second(165), Segment::numeric("1")], 12);
+    assert_eq!(second, "1001_10100101".replace('_', ""));
+}
+
+#[test]
+fn fnc1_invalid_application_indicator() {
+    let valid = QRBuilder::new("1234").fnc1_second(37).build();
+    let invalid = QRBuilder::new("1234").fnc1_second(150).build();
+
+    assert!(valid.is_ok());
+    assert!(matches!(
+        invalid,
+        Err(QRCodeError::InvalidApplicationIndicator(150))
+    ));
+}
+
+#[test]
+fn fnc1_separator_is_alphanumeric() {
+    let segments = encode::optimal_segments(b"10ABC\x1d21XY%Z", Version::V01, true);
+
+    let data = segments.iter().flat_map(Segment::data).copied();
+    assert_eq!(data.collect::<Vec<_>>(), b"10ABC%21XY%%Z");
+    assert!(segments
+        .iter()
+        .all(|segment| matches!(segment, Segment::Data(Mode::Alphanumeric, _))));
+}
+
+#[test]
+fn fnc1_separator_in_byte_mode() {
+    let segments = encode::optimal_segments(b"10abc\x1d21xyz", Version::V01, true);
+
+    assert_eq!(segments, vec![Segment::bytes(&b"10abc\x1d21xyz"[..])]);
+}
+
+#[test]
+fn fnc1_builder_matches_segments() {
+    let built = QRBuilder::new("0109506000134352\x1d10ABC")
+        .fnc1_first()
+        .build()
+        .unwrap();
+    let explicit = QRBuilder::segments(
+        [Segment::fnc1_first()]
+            .into_iter()
+            .chain(encode::optimal_segments(
+                b"0109506000134352\x1d10ABC",
+                Version::V01,
+                true,
+            ))
+            .collect(),
+    )
+    .build()
+    .unwrap();
+
+    assert_eq!(built.data[..], explicit.data[..]);
+}
+
+#[test]
+fn gs1_element_string() {
+    let data = Gs1Builder::new()
+        .element("01", "09506000134352")
+        .element("10", "ABC123")
+        .element("17", "201231")
+        .element("21", "12345")
+        .data()
+        .unwrap();
+
+    // Only the variable length batch is followed by a separator
+    assert_eq!(data, b"010950600013435210ABC123\x1d172012312112345");
+}
+
+#[test]
+fn gs1_builds_qrcode() {
+    let qr = Gs1Builder::new()
+        .element("00", "106141411234567897")
+        .element("3103", "000189")
+        .element("410", "9501101020917")
+        .qr_builder()
+        .unwrap()
+        .build();
+
+    assert!(qr.is_ok());
+}
+
+#[test]
+fn gs1_invalid_elements() {
+    let error = |ai: &str, value: &str| Gs1Builder::new().element(ai, value).data().unwrap_err();
+
+    assert_eq!(Gs1Builder::new().data(), Err(Gs1Error::Empty));
+    assert_eq!(error("05", "1"), Gs1Error::UnknownAi("05".to_owned()));
+    assert_eq!(
+        error("3170", "000189"),
+        Gs1Error::UnknownAi("3170".to_owned())
+    );
+    assert_eq!(
+        error("01", "0950600013435"),
+        Gs1Error::InvalidLength("01".to_owned())
+    );
+    assert_eq!(
+        error("10", "A".repeat(21).as_str()),
+        Gs1Error::InvalidLength("10".to_owned())
+    );
+    assert_eq!(
+        error("01", "09506000134353"),
+        Gs1Error::InvalidCheckDigit("01".to_owned())
+    );
+    assert_eq!(
+        error("17", "201301"),
+        Gs1Error::InvalidDate("17".to_owned())
+    );
+    assert_eq!(
+        error("17", "210229"),
+        Gs1Error::InvalidDate("17".to_owned())
+    );
+    assert_eq!(
+        error("10", "AB#C"),
+        Gs1Error::InvalidCharacter {
+            ai: "10".to_owned(),
+            index: 2
+        }
+    );
+    assert_eq!(error("391", "978A"), Gs1Error::UnknownAi("391".to_owned()));
+    assert_eq!(
+        error("3912", "97812A"),
+        Gs1Error::InvalidCharacter {
+            ai: "3912".to_owned(),
+            index: 5
+        }
+    );
+}
+
+#[test]
+fn gs1_date_without_day() {
+    let data = Gs1Builder::new().element("15", "240200").data();
+
+    assert!(data.is_ok());
+}
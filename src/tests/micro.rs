@@ -0,0 +1,104 @@
+use crate::qr::QRCodeError;
+use crate::{Mask, MicroQRBuilder, MicroVersion, Mode, ECL};
+
+/// Compares with the `qrcode` crate, module by module
+fn assert_same(input: &str, version: MicroVersion, ecl: ECL) {
+    let level = match ecl {
+        ECL::L => qrcode::EcLevel::L,
+        ECL::M => qrcode::EcLevel::M,
+        ECL::Q => qrcode::EcLevel::Q,
+        ECL::H => qrcode::EcLevel::H,
+    };
+    let expected =
+        qrcode::QrCode::with_version(input, qrcode::Version::Micro(version as i16 + 1), level)
+            .expect(input)
+            .to_colors();
+
+    let mut builder = MicroQRBuilder::new(input);
+    builder.version(version);
+    if version != MicroVersion::M1 {
+        builder.ecl(ecl);
+    }
+    let qr = builder.build().unwrap();
+
+    let modules = qr.data[..qr.size * qr.size]
+        .iter()
+        .map(|module| module.value())
+        .collect::<Vec<_>>();
+    let expected = expected
+        .iter()
+        .map(|&color| color == qrcode::Color::Dark)
+        .collect::<Vec<_>>();
+
+    assert_eq!(qr.size, version.size());
+    assert_eq!(modules, expected, "{input} {version:?}-{ecl}");
+}
+
+#[test]
+fn micro_matches_qrcode() {
+    assert_same("12345", MicroVersion::M1, ECL::L);
+    assert_same("0123456789", MicroVersion::M2, ECL::L);
+    assert_same("HELLO", MicroVersion::M2, ECL::M);
+    // `qrcode` places a full last data codeword in M3-L, where the spec has 4 bits
+    assert_same("hello@x", MicroVersion::M3, ECL::M);
+    assert_same("PART-XY", MicroVersion::M3, ECL::M);
+    assert_same("https://a.b.com", MicroVersion::M4, ECL::L);
+    assert_same("LOT-AB/CD EF", MicroVersion::M4, ECL::M);
+    assert_same("012345678901234567890", MicroVersion::M4, ECL::Q);
+}
+
+#[test]
+fn micro_smallest_version() {
+    let qr = MicroQRBuilder::new("12345").build().unwrap();
+    assert_eq!(qr.micro, Some(MicroVersion::M1));
+    assert!(qr.version.is_none());
+    assert!(qr.ecl.is_none());
+
+    // 6 digits don't fit M1
+    let qr = MicroQRBuilder::new("123456").build().unwrap();
+    assert_eq!(qr.micro, Some(MicroVersion::M2));
+    assert!(matches!(qr.ecl, Some(ECL::L)));
+
+    // Byte mode needs M3
+    let qr = MicroQRBuilder::new("ab").build().unwrap();
+    assert_eq!(qr.micro, Some(MicroVersion::M3));
+    assert_eq!(qr.mode, Some(Mode::Byte));
+}
+
+#[test]
+fn micro_capacity() {
+    // 35 digits in M4-L, referring to Table 7 of the spec
+    let fits = MicroQRBuilder::new("1".repeat(35)).build();
+    let too_large = MicroQRBuilder::new("1".repeat(36)).build();
+
+    assert_eq!(fits.unwrap().micro, Some(MicroVersion::M4));
+    assert!(matches!(too_large, Err(QRCodeError::EncodedData)));
+}
+
+#[test]
+fn micro_forced_mask() {
+    let qr = MicroQRBuilder::new("12345")
+        .mask(Mask::Diamonds)
+        .build()
+        .unwrap();
+
+    assert!(matches!(qr.mask, Some(Mask::Diamonds)));
+}
+
+#[test]
+fn micro_errors() {
+    let ecl = MicroQRBuilder::new("12345").ecl(ECL::H).build();
+    let mask = MicroQRBuilder::new("12345")
+        .mask(Mask::Checkerboard)
+        .build();
+    let version = MicroQRBuilder::new("ABC").version(MicroVersion::M1).build();
+    let m1_ecl = MicroQRBuilder::new("1")
+        .version(MicroVersion::M1)
+        .ecl(ECL::L)
+        .build();
+
+    assert!(matches!(ecl, Err(QRCodeError::UnsupportedEcl)));
+    assert!(matches!(mask, Err(QRCodeError::UnsupportedMask)));
+    assert!(matches!(version, Err(QRCodeError::SpecifiedVersion)));
+    assert!(matches!(m1_ecl, Err(QRCodeError::UnsupportedEcl)));
+}
@@ -1,11 +1,26 @@
+mod build_into;
 mod bytes;
+mod capacity;
 mod compact;
 mod datamasking;
+mod decode;
 mod default;
+#[cfg(feature = "std")]
+mod detect;
+mod eci;
+mod ecl;
 mod encode;
 mod error_correction;
+mod gs1;
+mod micro;
+mod mode;
+mod payload;
 mod polynomials;
+mod rmqr;
 mod score;
+mod segments;
 mod structure;
+mod structured_append;
 mod svg;
 mod version;
+mod version_range;
@@ -0,0 +1,129 @@
+use crate::decode;
+use crate::qr::QRCodeError;
+use crate::{Mode, QRBuilder, Segment, Version, ECL};
+
+#[test]
+fn mode_forces_a_single_segment() {
+    let optimized = QRBuilder::new("0123456789012345678901234567890123456789")
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+    let forced = QRBuilder::new("0123456789012345678901234567890123456789")
+        .ecl(ECL::H)
+        .mode(Mode::Byte)
+        .build()
+        .unwrap();
+
+    assert_eq!(optimized.mode, Some(Mode::Numeric));
+    assert_eq!(forced.mode, Some(Mode::Byte));
+    assert!(matches!(optimized.version, Some(Version::V03)));
+    assert!(matches!(forced.version, Some(Version::V05)));
+
+    let decoded = decode::from_qrcode(&forced).unwrap();
+    assert_eq!(decoded.data, b"0123456789012345678901234567890123456789");
+
+    for mode in [Mode::Numeric, Mode::Alphanumeric, Mode::Byte] {
+        let qr = QRBuilder::new("31415926").mode(mode).build().unwrap();
+        assert_eq!(qr.mode, Some(mode));
+        assert_eq!(decode::from_qrcode(&qr).unwrap().data, b"31415926");
+    }
+
+    // "点茗" in Shift JIS
+    let qr = QRBuilder::new(vec![0x93, 0x5F, 0xE4, 0xAA])
+        .mode(Mode::Kanji)
+        .build()
+        .unwrap();
+    assert_eq!(qr.mode, Some(Mode::Kanji));
+}
+
+#[test]
+fn mode_invalid_characters() {
+    let build = |input: &[u8], mode| QRBuilder::new(input).mode(mode).build();
+
+    assert!(matches!(
+        build(b"HELLO world", Mode::Alphanumeric),
+        Err(QRCodeError::InvalidCharacter {
+            index: 6,
+            byte: b'w'
+        })
+    ));
+    assert!(matches!(
+        build(b"12345A", Mode::Numeric),
+        Err(QRCodeError::InvalidCharacter {
+            index: 5,
+            byte: b'A'
+        })
+    ));
+    assert!(matches!(
+        build("点".as_bytes(), Mode::Kanji),
+        Err(QRCodeError::InvalidCharacter { .. })
+    ));
+    assert!(matches!(
+        build(&[0x93, 0x5F, 0xE4], Mode::Kanji),
+        Err(QRCodeError::InvalidCharacter {
+            index: 2,
+            byte: 0xE4
+        })
+    ));
+    assert!(build(&[0xFF, 0x00], Mode::Byte).is_ok());
+
+    // Range & structured append don't get past the validation either
+    let qr = QRBuilder::new("hello")
+        .mode(Mode::Alphanumeric)
+        .version_range(Version::V01, Version::V05)
+        .build();
+    assert!(matches!(
+        qr,
+        Err(QRCodeError::InvalidCharacter { index: 0, .. })
+    ));
+
+    let qrcodes = QRBuilder::new("hello")
+        .mode(Mode::Numeric)
+        .build_structured_append();
+    assert!(matches!(
+        qrcodes,
+        Err(QRCodeError::InvalidCharacter { index: 0, .. })
+    ));
+}
+
+#[test]
+fn mode_with_headers() {
+    // FNC1 separators are written `%` in alphanumeric mode
+    let qr = QRBuilder::new("10ABC\x1d21%1")
+        .fnc1_first()
+        .mode(Mode::Alphanumeric)
+        .build()
+        .unwrap();
+    assert_eq!(qr.mode, Some(Mode::Alphanumeric));
+    assert_eq!(decode::from_qrcode(&qr).unwrap().data, b"10ABC\x1d21%1");
+
+    let qr = QRBuilder::new("10abc\x1d21")
+        .fnc1_first()
+        .mode(Mode::Alphanumeric)
+        .build();
+    assert!(matches!(
+        qr,
+        Err(QRCodeError::InvalidCharacter {
+            index: 2,
+            byte: b'a'
+        })
+    ));
+
+    let qr = QRBuilder::new("Grüße 123")
+        .utf8_eci(true)
+        .mode(Mode::Byte)
+        .build()
+        .unwrap();
+    assert_eq!(qr.mode, Some(Mode::Byte));
+    assert_eq!(
+        decode::from_qrcode(&qr).unwrap().data,
+        "Grüße 123".as_bytes()
+    );
+
+    // Explicit segments are encoded as is
+    let qr = QRBuilder::segments(vec![Segment::numeric("123"), Segment::bytes("abc")])
+        .mode(Mode::Numeric)
+        .build()
+        .unwrap();
+    assert_eq!(qr.mode, None);
+}
@@ -0,0 +1,742 @@
+use crate::decode;
+use crate::payload::{
+    Address, Bitcoin, BitcoinError, EmvError, EmvQr, EmvTemplate, Epc, EpcError, Geo, GeoError,
+    Mailto, MeCard, Otp, OtpAlgorithm, OtpError, Sms, SwissAddress, SwissCurrency, SwissQrBill,
+    SwissQrBillError, Tel, VCard, VCardVersion, Wifi, WifiSecurity,
+};
+use crate::{Mode, ECL};
+
+#[test]
+fn wifi_escaping() {
+    let wifi = Wifi::new(r#"My "Home"; 5:GHz\"#)
+        .security(WifiSecurity::Wpa)
+        .password("a,b;c")
+        .to_string();
+    assert_eq!(wifi, r#"WIFI:T:WPA;S:My \"Home\"\; 5\:GHz\\;P:a\,b\;c;;"#);
+
+    let wifi = Wifi::new("Office")
+        .security(WifiSecurity::Wep)
+        .password("secret")
+        .hidden(true)
+        .to_string();
+    assert_eq!(wifi, "WIFI:T:WEP;S:Office;P:secret;H:true;;");
+
+    // Open networks have no password
+    let wifi = Wifi::new("Guest").password("ignored").to_string();
+    assert_eq!(wifi, "WIFI:T:nopass;S:Guest;;");
+}
+
+#[test]
+fn vcard_versions() {
+    let address = Address {
+        street: "1 Main St.".into(),
+        city: "Springfield".into(),
+        postal_code: "12345".into(),
+        country: "USA".into(),
+        ..Address::default()
+    };
+
+    let vcard = VCard::new("Jane Doe")
+        .name("Doe", "Jane")
+        .title("CEO; founder")
+        .email("jane@example.com")
+        .url("https://example.com/a,b")
+        .address(address)
+        .note("Line 1\nLine 2")
+        .to_string();
+    assert_eq!(
+        vcard,
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Jane Doe\r\nN:Doe;Jane;;;\r\nTITLE:CEO\\; founder\r\n\
+         EMAIL:jane@example.com\r\nURL:https://example.com/a,b\r\n\
+         ADR:;;1 Main St.;Springfield;;12345;USA\r\nNOTE:Line 1\\nLine 2\r\nEND:VCARD\r\n"
+    );
+
+    // A line break in a URL would start a new property
+    let vcard = VCard::new("Acme")
+        .url("https://example.com/\r\nTEL:+15550100")
+        .to_string();
+    assert!(vcard.contains("\r\nURL:https://example.com/TEL:+15550100\r\n"));
+
+    // vCard 3.0 requires a structured name, 4.0 doesn't
+    let vcard = VCard::new("Acme").phone("+1 555 0100").to_string();
+    assert!(vcard.contains("\r\nN:Acme;;;;\r\nTEL:+15550100\r\n"));
+
+    let vcard = VCard::new("Acme")
+        .version(VCardVersion::V4)
+        .phone("+1 555 0100")
+        .to_string();
+    assert_eq!(
+        vcard,
+        "BEGIN:VCARD\r\nVERSION:4.0\r\nFN:Acme\r\nTEL;VALUE=uri:tel:+15550100\r\nEND:VCARD\r\n"
+    );
+}
+
+#[test]
+fn mecard_escaping() {
+    let mecard = MeCard::new("Doe", "Jane")
+        .phone("(555) 123-4567")
+        .email("jane@example.com")
+        .url("https://example.com")
+        .address(Address {
+            street: "1 Main St, Apt 2".into(),
+            city: "Springfield".into(),
+            ..Address::default()
+        })
+        .birthday("19800101")
+        .note("Hi; there")
+        .to_string();
+    assert_eq!(
+        mecard,
+        "MECARD:N:Doe,Jane;TEL:5551234567;EMAIL:jane@example.com;URL:https\\://example.com;\
+         ADR:,,1 Main St\\, Apt 2,Springfield,,,;BDAY:19800101;NOTE:Hi\\; there;;"
+    );
+
+    assert_eq!(MeCard::new("Acme", "").to_string(), "MECARD:N:Acme;;");
+}
+
+#[test]
+fn uri_payloads() {
+    assert_eq!(
+        Geo::new(-33.8568, 151.2153).data(),
+        Ok(String::from("GEO:-33.8568,151.2153"))
+    );
+    assert_eq!(
+        Geo::new(46.0, 7.5).altitude(4478.0).data(),
+        Ok(String::from("GEO:46,7.5,4478"))
+    );
+    assert_eq!(
+        Geo::new(-90.0, 180.0).data(),
+        Ok(String::from("GEO:-90,180"))
+    );
+    assert_eq!(Geo::new(90.5, 0.0).data(), Err(GeoError::InvalidLatitude));
+    assert_eq!(
+        Geo::new(f64::NAN, 0.0).data(),
+        Err(GeoError::InvalidLatitude)
+    );
+    assert_eq!(
+        Geo::new(0.0, -180.5).data(),
+        Err(GeoError::InvalidLongitude)
+    );
+    assert_eq!(
+        Geo::new(0.0, f64::INFINITY).data(),
+        Err(GeoError::InvalidLongitude)
+    );
+    assert_eq!(
+        Geo::new(0.0, 0.0).altitude(f64::NAN).data(),
+        Err(GeoError::InvalidAltitude)
+    );
+
+    assert_eq!(
+        Sms::new("+44 20 7946 0958").to_string(),
+        "SMSTO:+442079460958"
+    );
+
+    let mailto = Mailto::new("jane@example.com")
+        .cc("john@example.com")
+        .cc("ops+qr@example.com")
+        .subject("Q3 report")
+        .body("Hello,\nSee attached.")
+        .to_string();
+    assert_eq!(
+        mailto,
+        "MAILTO:jane@example.com?cc=john@example.com,ops+qr@example.com\
+         &subject=Q3%20report&body=Hello%2C%0D%0ASee%20attached."
+    );
+
+    // `#` ends a URI, it is percent-encoded
+    assert_eq!(Tel::new("*123#").to_string(), "TEL:*123%23");
+}
+
+#[test]
+fn payloads_fit_alphanumeric_mode() {
+    // Forcing the alphanumeric mode fails on any other character
+    let tel = Tel::new("+1 (555) 123-4567");
+    assert!(tel.qr_builder().mode(Mode::Alphanumeric).build().is_ok());
+
+    let sms = Sms::new("+1 555 123 4567").message("ON MY WAY").clone();
+    assert!(sms.qr_builder().mode(Mode::Alphanumeric).build().is_ok());
+}
+
+#[test]
+fn payloads_round_trip() {
+    let wifi = Wifi::new("Café")
+        .security(WifiSecurity::Wpa)
+        .password("p:w")
+        .clone();
+    let vcard = VCard::new("Jane Doe").email("jane@example.com").clone();
+    let geo = Geo::new(48.5, 2.25);
+    let epc = Epc::new("Red Cross of Belgium", "BE72000000001616")
+        .amount("12.50")
+        .clone();
+    let creditor = swiss_address("Robert Schneider AG", "Rue du Lac", "1268", "2501", "Biel");
+    let bill = SwissQrBill::new("CH4431999123000889012", creditor)
+        .amount("1949.75")
+        .qr_reference("210000000003139471430009017")
+        .clone();
+    let otp = Otp::totp("JBSWY3DPEHPK3PXP", "jane");
+    let bitcoin = Bitcoin::new("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq");
+
+    let payloads = [
+        (wifi.qr_builder(), wifi.to_string()),
+        (vcard.qr_builder(), vcard.to_string()),
+        (geo.qr_builder().unwrap(), geo.data().unwrap()),
+        (epc.qr_builder().unwrap(), epc.data().unwrap()),
+        (bill.qr_builder().unwrap(), bill.data().unwrap()),
+        (emv_pix().qr_builder().unwrap(), emv_pix().data().unwrap()),
+        (otp.qr_builder().unwrap(), otp.data().unwrap()),
+        (bitcoin.qr_builder().unwrap(), bitcoin.data().unwrap()),
+    ];
+    for (builder, data) in payloads {
+        let qrcode = builder.build().unwrap();
+        let decoded = decode::from_qrcode(&qrcode).unwrap();
+        assert_eq!(decoded.data, data.as_bytes(), "{data}");
+    }
+
+    assert_eq!(wifi.to_string(), r"WIFI:T:WPA;S:Café;P:p\:w;;");
+}
+
+#[test]
+fn epc_sample() {
+    // Sample of the EPC069-12 guidelines
+    let data = Epc::new("Red Cross of Belgium", "BE72 0000 0000 1616")
+        .bic("bpotbeb1")
+        .amount("1")
+        .purpose("CHAR")
+        .text("Urgency fund")
+        .information("Sample EPC QR code")
+        .data()
+        .unwrap();
+    assert_eq!(
+        data,
+        "BCD\n002\n1\nSCT\nBPOTBEB1\nRed Cross of Belgium\nBE72000000001616\nEUR1\nCHAR\n\n\
+         Urgency fund\nSample EPC QR code"
+    );
+
+    // Trailing empty fields are left out, a missing BIC is not
+    let data = Epc::new("Jane Doe", "DE89370400440532013000")
+        .reference("RF18 5390 0754 7034")
+        .data()
+        .unwrap();
+    assert_eq!(
+        data,
+        "BCD\n002\n1\nSCT\n\nJane Doe\nDE89370400440532013000\n\n\nRF18539007547034"
+    );
+}
+
+#[test]
+fn epc_validation() {
+    const IBAN: &str = "DE89370400440532013000";
+
+    let epc = Epc::new("Jane Doe", "DE89 3704 0044 0532 0130 00").data();
+    assert!(epc.is_ok());
+
+    assert_eq!(Epc::new("", IBAN).data(), Err(EpcError::InvalidName));
+    let long_name = "a".repeat(71);
+    assert_eq!(Epc::new(long_name, IBAN).data(), Err(EpcError::InvalidName));
+    let two_lines = Epc::new("Jane\nDoe", IBAN).data();
+    assert_eq!(two_lines, Err(EpcError::InvalidName));
+
+    // Wrong check digits, too short, without country code, with a separator
+    let iban = Epc::new("Jane Doe", "DE88370400440532013000").data();
+    assert_eq!(iban, Err(EpcError::InvalidIban));
+    let iban = Epc::new("Jane Doe", "DE8937040044").data();
+    assert_eq!(iban, Err(EpcError::InvalidIban));
+    let iban = Epc::new("Jane Doe", "1289370400440532013000").data();
+    assert_eq!(iban, Err(EpcError::InvalidIban));
+    let iban = Epc::new("Jane Doe", "DE89-370400440532013000").data();
+    assert_eq!(iban, Err(EpcError::InvalidIban));
+
+    let mut epc = Epc::new("Jane Doe", IBAN);
+    assert!(epc.bic("COBADEFFXXX").data().is_ok());
+    assert_eq!(epc.bic("COBADE").data(), Err(EpcError::InvalidBic));
+    assert_eq!(epc.bic("COBA1EFF").data(), Err(EpcError::InvalidBic));
+    assert_eq!(epc.bic("COBADEFFXX").data(), Err(EpcError::InvalidBic));
+
+    let mut epc = Epc::new("Jane Doe", IBAN);
+    assert!(epc.amount("0.01").data().is_ok());
+    assert!(epc.amount("12.5").data().is_ok());
+    assert!(epc.amount("999999999.99").data().is_ok());
+    assert_eq!(epc.amount("0").data(), Err(EpcError::InvalidAmount));
+    assert_eq!(epc.amount("0.00").data(), Err(EpcError::InvalidAmount));
+    assert_eq!(epc.amount("12.345").data(), Err(EpcError::InvalidAmount));
+    assert_eq!(
+        epc.amount("1234567890").data(),
+        Err(EpcError::InvalidAmount)
+    );
+    assert_eq!(epc.amount(".5").data(), Err(EpcError::InvalidAmount));
+    assert_eq!(epc.amount("5.").data(), Err(EpcError::InvalidAmount));
+    assert_eq!(epc.amount("1,5").data(), Err(EpcError::InvalidAmount));
+    assert_eq!(epc.amount("-1").data(), Err(EpcError::InvalidAmount));
+    assert_eq!(epc.amount("EUR1").data(), Err(EpcError::InvalidAmount));
+
+    let purpose = Epc::new("Jane Doe", IBAN).purpose("char").data();
+    assert_eq!(purpose, Err(EpcError::InvalidPurpose));
+
+    let mut epc = Epc::new("Jane Doe", IBAN);
+    let reference = epc.reference("RF19539007547034").data();
+    assert_eq!(reference, Err(EpcError::InvalidReference));
+    let reference = epc.reference("12345678").data();
+    assert_eq!(reference, Err(EpcError::InvalidReference));
+    let both = epc.reference("RF18539007547034").text("Invoice").data();
+    assert_eq!(both, Err(EpcError::BothRemittances));
+
+    let mut epc = Epc::new("Jane Doe", IBAN);
+    assert!(epc.text("é".repeat(140)).data().is_ok());
+    assert_eq!(epc.text("a".repeat(141)).data(), Err(EpcError::InvalidText));
+    let text = epc.text("Invoice\r\nRF18").data();
+    assert_eq!(text, Err(EpcError::InvalidText));
+
+    let mut epc = Epc::new("Jane Doe", IBAN);
+    let information = epc.information("a".repeat(71)).data();
+    assert_eq!(information, Err(EpcError::InvalidInformation));
+    let information = epc.information("Thanks\n").data();
+    assert_eq!(information, Err(EpcError::InvalidInformation));
+
+    // Multi-byte characters can outgrow the 331 bytes
+    let large = Epc::new("Jane Doe", IBAN)
+        .text("é".repeat(140))
+        .information("€".repeat(50))
+        .data();
+    assert_eq!(large, Err(EpcError::TooLarge(481)));
+}
+
+#[test]
+fn epc_qr_builder() {
+    let qrcode = Epc::new("Red Cross of Belgium", "BE72000000001616")
+        .qr_builder()
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(qrcode.ecl.map(|ecl| ecl as usize), Some(ECL::M as usize));
+
+    assert!(Epc::new("Jane Doe", "DE00").qr_builder().is_err());
+}
+
+fn swiss_address(name: &str, street: &str, number: &str, code: &str, town: &str) -> SwissAddress {
+    SwissAddress {
+        name: String::from(name),
+        street: String::from(street),
+        building_number: String::from(number),
+        postal_code: String::from(code),
+        town: String::from(town),
+        country: String::from("CH"),
+    }
+}
+
+#[test]
+fn swiss_qr_bill_sample() {
+    // Sample of the Swiss Implementation Guidelines for the QR-bill
+    let creditor = swiss_address("Robert Schneider AG", "Rue du Lac", "1268", "2501", "Biel");
+    let debtor = swiss_address(
+        "Pia-Maria Rutschmann-Schnyder",
+        "Grosse Marktgasse",
+        "28",
+        "9400",
+        "Rorschach",
+    );
+    let data = SwissQrBill::new("CH44 3199 9123 0008 8901 2", creditor.clone())
+        .amount("1949.75")
+        .debtor(debtor)
+        .qr_reference("21 00000 00003 13947 14300 09017")
+        .message("Auftrag vom 15.06.2020")
+        .billing_information("//S1/10/10201409/11/200701/20/140.000-53/30/102673831")
+        .data()
+        .unwrap();
+    assert_eq!(
+        data,
+        "SPC\n0200\n1\nCH4431999123000889012\n\
+         S\nRobert Schneider AG\nRue du Lac\n1268\n2501\nBiel\nCH\n\n\n\n\n\n\n\n\
+         1949.75\nCHF\n\
+         S\nPia-Maria Rutschmann-Schnyder\nGrosse Marktgasse\n28\n9400\nRorschach\nCH\n\
+         QRR\n210000000003139471430009017\nAuftrag vom 15.06.2020\nEPD\n\
+         //S1/10/10201409/11/200701/20/140.000-53/30/102673831"
+    );
+
+    // Without amount nor debtor, the debtor fills them in
+    let data = SwissQrBill::new("CH58 0079 1123 0008 8901 2", creditor)
+        .currency(SwissCurrency::Eur)
+        .amount("12.5")
+        .creditor_reference("RF18 5390 0754 7034")
+        .data()
+        .unwrap();
+    assert!(data.contains("\n12.50\nEUR\n\n\n\n\n\n\n\nSCOR\nRF18539007547034\n\nEPD"));
+    assert!(data.ends_with("EPD"));
+}
+
+#[test]
+fn swiss_qr_bill_validation() {
+    const QR_IBAN: &str = "CH4431999123000889012";
+    const IBAN: &str = "CH5800791123000889012";
+    const QR_REFERENCE: &str = "210000000003139471430009017";
+
+    let creditor = swiss_address("Robert Schneider AG", "", "", "2501", "Biel");
+    assert!(SwissQrBill::new(IBAN, creditor.clone()).data().is_ok());
+    let bill = SwissQrBill::new(QR_IBAN, creditor.clone())
+        .qr_reference(QR_REFERENCE)
+        .data();
+    assert!(bill.is_ok());
+
+    // Not Swiss, wrong check digits, too short
+    let bill = SwissQrBill::new("DE89370400440532013000", creditor.clone()).data();
+    assert_eq!(bill, Err(SwissQrBillError::InvalidIban));
+    let bill = SwissQrBill::new("CH5800791123000889013", creditor.clone()).data();
+    assert_eq!(bill, Err(SwissQrBillError::InvalidIban));
+    let bill = SwissQrBill::new("CH58007911230008890", creditor.clone()).data();
+    assert_eq!(bill, Err(SwissQrBillError::InvalidIban));
+
+    let mut address = creditor.clone();
+    address.country = String::from("Switzerland");
+    let bill = SwissQrBill::new(IBAN, address).data();
+    assert_eq!(bill, Err(SwissQrBillError::InvalidCreditor));
+
+    let mut address = creditor.clone();
+    address.town = "a".repeat(36);
+    let bill = SwissQrBill::new(IBAN, creditor.clone())
+        .debtor(address)
+        .data();
+    assert_eq!(bill, Err(SwissQrBillError::InvalidDebtor));
+
+    let mut address = creditor.clone();
+    address.name = String::from("Robert\nSchneider");
+    let bill = SwissQrBill::new(IBAN, creditor.clone())
+        .debtor(address)
+        .data();
+    assert_eq!(bill, Err(SwissQrBillError::InvalidDebtor));
+
+    let mut bill = SwissQrBill::new(IBAN, creditor.clone());
+    assert_eq!(
+        bill.amount("0").data(),
+        Err(SwissQrBillError::InvalidAmount)
+    );
+    let amount = bill.amount("12.345").data();
+    assert_eq!(amount, Err(SwissQrBillError::InvalidAmount));
+    let amount = bill.amount("1234567890").data();
+    assert_eq!(amount, Err(SwissQrBillError::InvalidAmount));
+    assert_eq!(
+        bill.amount("-1").data(),
+        Err(SwissQrBillError::InvalidAmount)
+    );
+
+    // Wrong check digit, then wrong length
+    let mut bill = SwissQrBill::new(QR_IBAN, creditor.clone());
+    let reference = bill.qr_reference("210000000003139471430009018").data();
+    assert_eq!(reference, Err(SwissQrBillError::InvalidReference));
+    let reference = bill.qr_reference("21000000000313947143000901").data();
+    assert_eq!(reference, Err(SwissQrBillError::InvalidReference));
+    let reference = SwissQrBill::new(IBAN, creditor.clone())
+        .creditor_reference("RF19539007547034")
+        .data();
+    assert_eq!(reference, Err(SwissQrBillError::InvalidReference));
+
+    // QR-IBANs go with QR references, and only them
+    let bill = SwissQrBill::new(QR_IBAN, creditor.clone()).data();
+    assert_eq!(bill, Err(SwissQrBillError::ReferenceMismatch));
+    let bill = SwissQrBill::new(QR_IBAN, creditor.clone())
+        .creditor_reference("RF18539007547034")
+        .data();
+    assert_eq!(bill, Err(SwissQrBillError::ReferenceMismatch));
+    let bill = SwissQrBill::new(IBAN, creditor.clone())
+        .qr_reference(QR_REFERENCE)
+        .data();
+    assert_eq!(bill, Err(SwissQrBillError::ReferenceMismatch));
+
+    // The message and the billing information share 140 characters
+    let mut bill = SwissQrBill::new(IBAN, creditor);
+    bill.message("a".repeat(100));
+    assert!(bill.billing_information("b".repeat(40)).data().is_ok());
+    let message = bill.billing_information("b".repeat(41)).data();
+    assert_eq!(message, Err(SwissQrBillError::InvalidMessage));
+}
+
+#[test]
+fn swiss_qr_bill_qr_builder() {
+    let creditor = swiss_address("Robert Schneider AG", "Rue du Lac", "1268", "2501", "Biel");
+    let qrcode = SwissQrBill::new("CH4431999123000889012", creditor)
+        .qr_reference("210000000003139471430009017")
+        .qr_builder()
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(qrcode.ecl.map(|ecl| ecl as usize), Some(ECL::M as usize));
+}
+
+fn emv_pix() -> EmvQr {
+    let account = EmvTemplate::new()
+        .field(0, "br.gov.bcb.pix")
+        .field(1, "123e4567-e12b-12d1-a456-426655440000")
+        .clone();
+
+    EmvQr::new("Fulano de Tal", "BRASILIA")
+        .country("BR")
+        .currency("986")
+        .merchant_category("0000")
+        .merchant_account(26, &account)
+        .additional_data(EmvTemplate::new().field(5, "***"))
+        .clone()
+}
+
+#[test]
+fn emv_pix_sample() {
+    // Sample of the PIX specification, data objects being sorted by ID
+    assert_eq!(
+        emv_pix().data().unwrap(),
+        "00020126580014br.gov.bcb.pix0136123e4567-e12b-12d1-a456-42665544000052040000\
+         53039865802BR5913Fulano de Tal6008BRASILIA62070503***63041D3D"
+    );
+
+    // Setting a data object again replaces it
+    let data = emv_pix()
+        .amount("10")
+        .amount("12.50")
+        .dynamic(true)
+        .data()
+        .unwrap();
+    assert!(data.starts_with("000201010212"));
+    assert!(data.contains("540512.50"));
+    assert!(!data.contains("5402"));
+}
+
+#[test]
+fn emv_validation() {
+    let account = EmvTemplate::new().field(0, "br.gov.bcb.pix").clone();
+
+    assert!(emv_pix().data().is_ok());
+    let id = emv_pix().field(100, "a").data();
+    assert_eq!(id, Err(EmvError::InvalidId(100)));
+    let id = emv_pix().field(63, "ABCD").data();
+    assert_eq!(id, Err(EmvError::InvalidId(63)));
+    let value = emv_pix().field(80, "").data();
+    assert_eq!(value, Err(EmvError::InvalidValue(80)));
+    let value = emv_pix().field(80, "a".repeat(100)).data();
+    assert_eq!(value, Err(EmvError::InvalidValue(80)));
+
+    // Errors of nested templates are reported by the root
+    let nested = EmvTemplate::new().field(1, "").clone();
+    let value = emv_pix().template(80, &nested).data();
+    assert_eq!(value, Err(EmvError::InvalidValue(1)));
+
+    // Replacing an invalid data object clears its error
+    assert!(emv_pix().field(80, "").field(80, "a").data().is_ok());
+    assert!(emv_pix()
+        .template(80, &nested)
+        .field(80, "a")
+        .data()
+        .is_ok());
+
+    // IDs 02 to 25 are reserved to card networks
+    assert!(emv_pix().merchant_account(51, &account).data().is_ok());
+    let id = emv_pix().merchant_account(2, &account).data();
+    assert_eq!(id, Err(EmvError::InvalidId(2)));
+    let id = emv_pix().merchant_account(52, &account).data();
+    assert_eq!(id, Err(EmvError::InvalidId(52)));
+
+    let value = emv_pix().field(1, "13").data();
+    assert_eq!(value, Err(EmvError::InvalidValue(1)));
+    let value = emv_pix().merchant_category("12345").data();
+    assert_eq!(value, Err(EmvError::InvalidValue(52)));
+    let value = emv_pix().currency("EUR").data();
+    assert_eq!(value, Err(EmvError::InvalidValue(53)));
+    let value = emv_pix().amount("1.2.3").data();
+    assert_eq!(value, Err(EmvError::InvalidValue(54)));
+    let value = emv_pix().country("Brazil").data();
+    assert_eq!(value, Err(EmvError::InvalidValue(58)));
+    let value = EmvQr::new("Fulano de Tal Comercio de Alimentos", "BRASILIA")
+        .merchant_account(26, &account)
+        .data();
+    assert_eq!(value, Err(EmvError::InvalidValue(59)));
+    let value = EmvQr::new("Fulano de Tal", "Sao Jose dos Campos")
+        .merchant_account(26, &account)
+        .data();
+    assert_eq!(value, Err(EmvError::InvalidValue(60)));
+
+    let missing = EmvQr::new("Fulano de Tal", "BRASILIA")
+        .merchant_account(26, &account)
+        .data();
+    assert_eq!(missing, Err(EmvError::MissingField(52)));
+    let missing = EmvQr::new("Fulano de Tal", "BRASILIA")
+        .merchant_category("0000")
+        .currency("986")
+        .country("BR")
+        .data();
+    assert_eq!(missing, Err(EmvError::MissingMerchantAccount));
+
+    let mut large = emv_pix();
+    for id in 80..=85 {
+        large.field(id, "a".repeat(99));
+    }
+    assert_eq!(large.data(), Err(EmvError::TooLarge(755)));
+}
+
+#[test]
+fn emv_parse() {
+    let data = emv_pix().amount("12.50").data().unwrap();
+
+    let root = EmvQr::parse(&data).unwrap();
+    let ids = root.fields().map(|(id, _)| id).collect::<Vec<_>>();
+    assert_eq!(ids, [0, 26, 52, 53, 54, 58, 59, 60, 62, 63]);
+    assert_eq!(root.get(54), Some("12.50"));
+
+    let account = EmvTemplate::parse(root.get(26).unwrap()).unwrap();
+    assert_eq!(account.get(0), Some("br.gov.bcb.pix"));
+    assert_eq!(account.to_string(), root.get(26).unwrap());
+
+    // Non ASCII values are counted in characters
+    let template = EmvTemplate::new().field(0, "São Paulo").to_string();
+    assert_eq!(template, "0009São Paulo");
+    assert_eq!(
+        EmvTemplate::parse(&template).unwrap().get(0),
+        Some("São Paulo")
+    );
+
+    let tampered = data.replace("12.50", "92.50");
+    assert_eq!(EmvQr::parse(&tampered), Err(EmvError::InvalidCrc));
+    assert_eq!(
+        EmvQr::parse(&data[..data.len() - 8]),
+        Err(EmvError::InvalidCrc)
+    );
+    assert!(EmvQr::parse("000201580202630406D4").is_ok());
+    assert_eq!(
+        EmvQr::parse("0002015802026304+6D4"),
+        Err(EmvError::InvalidCrc)
+    );
+    assert_eq!(EmvQr::parse("000201010"), Err(EmvError::Malformed(6)));
+    assert_eq!(EmvQr::parse("0002010A12"), Err(EmvError::Malformed(6)));
+}
+
+#[test]
+fn otp_uris() {
+    let totp = Otp::totp("jbsw y3dp ehpk 3pxp====", "jane@example.com")
+        .data()
+        .unwrap();
+    assert_eq!(
+        totp,
+        "otpauth://totp/jane@example.com?secret=JBSWY3DPEHPK3PXP"
+    );
+
+    // Parameters at their default value are left out
+    let totp = Otp::totp("JBSWY3DPEHPK3PXP", "jane@example.com")
+        .issuer("Acme & Co")
+        .algorithm(OtpAlgorithm::Sha256)
+        .digits(8)
+        .period(60)
+        .data()
+        .unwrap();
+    assert_eq!(
+        totp,
+        "otpauth://totp/Acme%20%26%20Co:jane@example.com?secret=JBSWY3DPEHPK3PXP\
+         &issuer=Acme%20%26%20Co&algorithm=SHA256&digits=8&period=60"
+    );
+
+    let hotp = Otp::hotp("JBSWY3DPEHPK3PXP", "jane", 42)
+        .period(60)
+        .data()
+        .unwrap();
+    assert_eq!(
+        hotp,
+        "otpauth://hotp/jane?secret=JBSWY3DPEHPK3PXP&counter=42"
+    );
+}
+
+#[test]
+fn otp_validation() {
+    const SECRET: &str = "JBSWY3DPEHPK3PXP";
+
+    assert!(Otp::totp(SECRET, "jane").data().is_ok());
+
+    // Empty, out of the alphabet, not ending on a whole byte, with a separator
+    let secret = Otp::totp("", "jane").data();
+    assert_eq!(secret, Err(OtpError::InvalidSecret));
+    let secret = Otp::totp("JBSWY3DPEHPK3PX1", "jane").data();
+    assert_eq!(secret, Err(OtpError::InvalidSecret));
+    let secret = Otp::totp("JBSWY3DPEHPK3P", "jane").data();
+    assert_eq!(secret, Err(OtpError::InvalidSecret));
+    let secret = Otp::totp("JBSWY3DP-EHPK3PXP", "jane").data();
+    assert_eq!(secret, Err(OtpError::InvalidSecret));
+
+    assert_eq!(Otp::totp(SECRET, " ").data(), Err(OtpError::InvalidLabel));
+    assert_eq!(Otp::totp(SECRET, "a:b").data(), Err(OtpError::InvalidLabel));
+    let label = Otp::totp(SECRET, "jane").issuer("Acme: Co").data();
+    assert_eq!(label, Err(OtpError::InvalidLabel));
+
+    let mut otp = Otp::totp(SECRET, "jane");
+    assert_eq!(otp.digits(5).data(), Err(OtpError::InvalidDigits));
+    assert_eq!(otp.digits(9).data(), Err(OtpError::InvalidDigits));
+
+    let period = Otp::totp(SECRET, "jane").period(0).data();
+    assert_eq!(period, Err(OtpError::InvalidPeriod));
+}
+
+#[test]
+fn bitcoin_uris() {
+    const BECH32: &str = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+
+    let data = Bitcoin::new(BECH32)
+        .amount("20.3")
+        .label("Luke Jr")
+        .message("Donation for project xyz")
+        .data()
+        .unwrap();
+    assert_eq!(
+        data,
+        "BITCOIN:BC1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZWF5MDQ?amount=20.3&label=Luke%20Jr\
+         &message=Donation%20for%20project%20xyz"
+    );
+
+    // Base58 addresses are case-sensitive
+    let data = Bitcoin::new("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH")
+        .data()
+        .unwrap();
+    assert_eq!(data, "BITCOIN:1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH");
+
+    // Without parameters, the URI of a bech32 address fits in the alphanumeric mode, in a
+    // smaller version than the lowercase one
+    let bitcoin = Bitcoin::new(BECH32).data().unwrap();
+    let qrcode = crate::QRBuilder::new(bitcoin.clone())
+        .mode(Mode::Alphanumeric)
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+    let lowercase = crate::QRBuilder::new(bitcoin.to_lowercase())
+        .ecl(ECL::H)
+        .build()
+        .unwrap();
+    assert!((qrcode.version.unwrap() as usize) < lowercase.version.unwrap() as usize);
+}
+
+#[test]
+fn bitcoin_validation() {
+    let bitcoin = Bitcoin::new("BC1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZWF5MDQ").data();
+    assert!(bitcoin.is_ok());
+
+    // Empty, mixed case, out of the bech32 alphabet, out of the base58 alphabet, too short
+    assert_eq!(Bitcoin::new("").data(), Err(BitcoinError::InvalidAddress));
+    let address = Bitcoin::new("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwF5mdq").data();
+    assert_eq!(address, Err(BitcoinError::InvalidAddress));
+    let address = Bitcoin::new("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwb5mdq").data();
+    assert_eq!(address, Err(BitcoinError::InvalidAddress));
+    let address = Bitcoin::new("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAM0").data();
+    assert_eq!(address, Err(BitcoinError::InvalidAddress));
+    let address = Bitcoin::new("1BgGZ9tc").data();
+    assert_eq!(address, Err(BitcoinError::InvalidAddress));
+
+    let mut bitcoin = Bitcoin::new("BC1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZWF5MDQ");
+    assert!(bitcoin.amount("0.00000001").data().is_ok());
+    assert!(bitcoin.amount("1").data().is_ok());
+    assert!(bitcoin.amount("21000000").data().is_ok());
+    assert!(bitcoin.amount("20999999.99999999").data().is_ok());
+
+    let amount = bitcoin.amount("0").data();
+    assert_eq!(amount, Err(BitcoinError::InvalidAmount));
+    let amount = bitcoin.amount("0.000000001").data();
+    assert_eq!(amount, Err(BitcoinError::InvalidAmount));
+    let amount = bitcoin.amount("21000000.00000001").data();
+    assert_eq!(amount, Err(BitcoinError::InvalidAmount));
+    let amount = bitcoin.amount("1.").data();
+    assert_eq!(amount, Err(BitcoinError::InvalidAmount));
+    let amount = bitcoin.amount(".5").data();
+    assert_eq!(amount, Err(BitcoinError::InvalidAmount));
+    let amount = bitcoin.amount("+1").data();
+    assert_eq!(amount, Err(BitcoinError::InvalidAmount));
+    let amount = bitcoin.amount("1e3").data();
+    assert_eq!(amount, Err(BitcoinError::InvalidAmount));
+}
@@ -0,0 +1,193 @@
+use crate::qr::QRCodeError;
+use crate::{Mask, ModuleType, RMQRBuilder, RMQRVersion, ECL};
+
+#[test]
+fn rmqr_fills_every_version() {
+    // Codewords & remainder bits of each version, referring to Table 6 of ISO/IEC 23941
+    #[rustfmt::skip]
+    const CODEWORDS: [(usize, usize); 32] = [
+        (13, 0), (21, 3), (32, 5), (44, 6), (68, 1),
+        (21, 2), (33, 3), (49, 1), (66, 4), (99, 5),
+        (15, 2), (31, 1), (47, 0), (67, 2), (89, 7), (132, 6),
+        (21, 4), (41, 1), (60, 6), (85, 4), (113, 3), (166, 0),
+        (51, 1), (74, 4), (103, 6), (136, 7), (199, 2),
+        (61, 1), (88, 2), (122, 0), (160, 3), (232, 4),
+    ];
+
+    for (&version, &(codewords, remainder)) in RMQRVersion::ALL.iter().zip(CODEWORDS.iter()) {
+        for ecl in [ECL::M, ECL::H] {
+            let qr = RMQRBuilder::new("1")
+                .version(version)
+                .ecl(ecl)
+                .build()
+                .unwrap();
+
+            let data_modules = qr.data[..qr.size * qr.height]
+                .iter()
+                .filter(|module| module.module_type() == ModuleType::Data)
+                .count();
+
+            assert_eq!((qr.size, qr.height), (version.width(), version.height()));
+            assert_eq!(data_modules, codewords * 8 + remainder, "{version:?}");
+        }
+    }
+}
+
+#[test]
+fn rmqr_capacity() {
+    let fits = |len: usize, version: RMQRVersion| {
+        RMQRBuilder::new("1".repeat(len))
+            .version(version)
+            .build()
+            .is_ok()
+    };
+
+    assert!(fits(12, RMQRVersion::R7x43));
+    assert!(!fits(13, RMQRVersion::R7x43));
+    assert!(fits(361, RMQRVersion::R17x139));
+    assert!(!fits(362, RMQRVersion::R17x139));
+
+    let too_long = RMQRBuilder::new("1".repeat(362)).build();
+    assert!(matches!(too_long, Err(QRCodeError::EncodedData)));
+}
+
+#[test]
+fn rmqr_picks_smallest_area() {
+    let qr = RMQRBuilder::new("HELLO WORLD").build().unwrap();
+    assert_eq!(qr.rmqr, Some(RMQRVersion::R13x27));
+    assert!(matches!(qr.ecl, Some(ECL::M)));
+    assert!(matches!(qr.mask, Some(Mask::LargeCheckerboard)));
+    assert!(qr.version.is_none());
+
+    let qr = RMQRBuilder::new("HELLO WORLD")
+        .max_height(7)
+        .build()
+        .unwrap();
+    assert_eq!(qr.rmqr, Some(RMQRVersion::R7x59));
+
+    let qr = RMQRBuilder::new("HELLO WORLD")
+        .max_height(9)
+        .max_width(43)
+        .ecl(ECL::H)
+        .build();
+    assert!(matches!(qr, Err(QRCodeError::EncodedData)));
+}
+
+#[test]
+fn rmqr_function_patterns() {
+    let qr = RMQRBuilder::new("1")
+        .version(RMQRVersion::R7x43)
+        .build()
+        .unwrap();
+    let (width, height) = (qr.size, qr.height);
+
+    // Corner finder pattern & finder sub pattern
+    assert!(qr[0][width - 2].value() && qr[0][width - 1].value() && qr[1][width - 1].value());
+    assert!(!qr[1][width - 2].value());
+    assert!(qr[height - 3][width - 3].value() && !qr[height - 2][width - 3].value());
+
+    // Hollow alignment patterns on both edges
+    assert!(qr[0][21].value() && !qr[1][21].value() && qr[2][21].value());
+    assert!(qr[height - 1][21].value() && !qr[height - 2][21].value());
+
+    // R7x43-M has no set bit, its format information is the mask itself
+    let format = (0..18).fold(0u32, |acc, i| {
+        acc | u32::from(qr[1 + i % 5][8 + i / 5].value()) << i
+    });
+    assert_eq!(format, 0b01_1111_1010_1011_0010);
+}
+
+#[test]
+fn rmqr_prints_rectangle() {
+    let qr = RMQRBuilder::new("1")
+        .version(RMQRVersion::R9x59)
+        .build()
+        .unwrap();
+
+    let out = qr.to_str();
+    assert_eq!(out.lines().count(), 6);
+    assert!(out.lines().all(|line| line.chars().count() == 61));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn rmqr_svg_rectangle() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+
+    let qr = RMQRBuilder::new("1")
+        .version(RMQRVersion::R7x43)
+        .build()
+        .unwrap();
+    let svg = SvgBuilder::default().margin(2).to_str(&qr);
+
+    assert!(svg.starts_with(r#"<svg viewBox="0 0 47 11""#));
+    assert!(svg.contains(r#"<rect width="47px" height="11px""#));
+}
+
+#[test]
+fn rmqr_errors() {
+    let ecl = RMQRBuilder::new("1").ecl(ECL::Q).build();
+    let version = RMQRBuilder::new("1".repeat(13))
+        .version(RMQRVersion::R7x43)
+        .build();
+
+    assert!(matches!(ecl, Err(QRCodeError::UnsupportedEcl)));
+    assert!(matches!(version, Err(QRCodeError::SpecifiedVersion)));
+}
+
+#[test]
+fn rmqr_matches_reference() {
+    // Symbols from the zxing-cpp rMQR decoder tests, generated with Zint
+    #[rustfmt::skip]
+    let cases: [(&str, RMQRVersion, ECL, &[&str]); 3] = [
+        ("ABCDEFG", RMQRVersion::R7x43, ECL::M, &[
+            "XXXXXXX X X X X X X XXX X X X X X X X X XXX",
+            "X     X  X XXX  XXXXX XXX      X X XX   X X",
+            "X XXX X X XXX X X X XXXX XXXX X  X XXXXXXXX",
+            "X XXX X  XX    XXXXX   XXXXXX   X X   X   X",
+            "X XXX X   XX  XXX   XXXXXXX  X X  XX  X X X",
+            "X     X XXXXX XXX XXX XXXXX    XXXXXX X   X",
+            "XXXXXXX X X X X X X XXX X X X X X X X XXXXX",
+        ]),
+        ("ABCDEFGHIJKLMN", RMQRVersion::R9x59, ECL::H, &[
+            "XXXXXXX X X X X X XXX X X X X X X X X XXX X X X X X X X XXX",
+            "X     X    X  XXXXX XXX X  X XXXXXXXX X X  X    X XXXX  X X",
+            "X XXX X XX XXX  X XXX XXXX  X         XXXXXXX  X XXXXX X  X",
+            "X XXX X XXXX X XX X   XX   XXXX XX  XX   X  X  X XXX     X ",
+            "X XXX X    X    X XX XXXXXX X X XX   X XX   X X XXXX  XXXXX",
+            "X     X X  X  X  X  XXX X X   X   XX  X XXXX XX  X X  X   X",
+            "XXXXXXX  XXXXX  XXXXXX X XX XXX X    XXXX  X    X  X XX X X",
+            "          XXX  XXXX XX XXX    X XXXXXXX X XX XXX  XX XX   X",
+            "XXX X X X X X X X XXX X X X X X X X X XXX X X X X X X XXXXX",
+        ]),
+        ("ABCDEF", RMQRVersion::R11x27, ECL::H, &[
+            "XXXXXXX X X X X X X X X XXX",
+            "X     X  XX        X  X X X",
+            "X XXX X    X  XX X   X   XX",
+            "X XXX X XXXX XX X  XXXXXX  ",
+            "X XXX X  X X XX  XX   XXX X",
+            "X     X XXX  X XX  XXXX  X ",
+            "XXXXXXX     X   XX  X XXXXX",
+            "           X   X   X  X   X",
+            "XXXX  X   X X XX XXXXXX X X",
+            "X XX XXXXXX XXX  XXXX X   X",
+            "XXX X X X X X X X X X XXXXX",
+        ]),
+    ];
+
+    for (input, version, ecl, expected) in cases.iter() {
+        let qr = RMQRBuilder::new(*input)
+            .version(*version)
+            .ecl(*ecl)
+            .build()
+            .unwrap();
+
+        for (y, row) in expected.iter().enumerate() {
+            let actual = (0..qr.size)
+                .map(|x| if qr[y][x].value() { 'X' } else { ' ' })
+                .collect::<String>();
+            assert_eq!(&actual, row, "{input} row {y}");
+        }
+    }
+}
@@ -5,6 +5,7 @@ use crate::score::{
     test_score_line, test_score_pattern,
 };
 use crate::tests::default::{DATA, EMPT, F, FIND, T};
+use crate::{Mask, MaskEvaluation, MicroQRBuilder, Mode, QRBuilder, Version, ECL};
 
 #[rustfmt::skip]
 const MAT_EXAMPLE_COM: [[bool; 29]; 29] = [
@@ -378,3 +379,118 @@ fn pattern() {
 
     assert_eq!(test_score_pattern(&line), 40, "pattern, expected 40");
 }
+
+#[test]
+fn mask_report_winner() {
+    let inputs = [
+        ("https://example.com/", None),
+        ("0123456789", Some(Version::V01)),
+        ("HELLO WORLD", Some(Version::V07)),
+        ("fast_qr", Some(Version::V20)),
+    ];
+
+    for (input, version) in inputs {
+        for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+            let mut builder = QRBuilder::new(input);
+            builder.ecl(ecl);
+            if let Some(version) = version {
+                builder.version(version);
+            }
+            let qrcode = builder.build().unwrap();
+            let report = qrcode.mask_report(MaskEvaluation::Fast).unwrap();
+
+            assert_eq!(report.winner as usize, qrcode.mask.unwrap() as usize);
+            for (i, penalty) in report.penalties.iter().enumerate() {
+                assert_eq!(penalty.mask as usize, i);
+                assert_eq!(penalty.n2 % 3, 0);
+                assert_eq!(penalty.n3 % 40, 0);
+                assert_eq!(penalty.n4 % 10, 0);
+                assert!(penalty.total() >= report.penalties[report.winner as usize].total());
+            }
+        }
+    }
+}
+
+#[test]
+fn mask_report_forced_mask() {
+    let qrcode = QRBuilder::new("https://example.com/").build().unwrap();
+    let report = qrcode.mask_report(MaskEvaluation::Fast).unwrap();
+
+    // The report ignores the forced mask, the data being the same
+    let forced = QRBuilder::new("https://example.com/")
+        .mask(Mask::Meadow)
+        .build()
+        .unwrap();
+    let forced_report = forced.mask_report(MaskEvaluation::Fast).unwrap();
+
+    assert_eq!(forced_report.winner as usize, report.winner as usize);
+    for (penalty, forced_penalty) in report.penalties.iter().zip(&forced_report.penalties) {
+        assert_eq!(penalty.total(), forced_penalty.total());
+    }
+}
+
+#[test]
+fn mask_report_micro() {
+    let qrcode = MicroQRBuilder::new("12345").build().unwrap();
+    assert!(qrcode.mask_report(MaskEvaluation::Fast).is_none());
+}
+
+#[test]
+fn mask_evaluation_iso_reference() {
+    // Byte mode QR codes generated by qrcodegen 1.8.0, whose mask selection follows the ISO
+    // penalties, see the header of the file
+    let reference = include_str!("data/qrcodegen_iso.txt");
+    let mut lines = reference.lines().filter(|line| !line.starts_with('#'));
+
+    while let Some(header) = lines.next() {
+        let fields = header.split('\t').collect::<Vec<_>>();
+        let (input, version) = (fields[0], fields[1].parse::<usize>().unwrap());
+        let ecl = match fields[2] {
+            "L" => ECL::L,
+            "M" => ECL::M,
+            "Q" => ECL::Q,
+            _ => ECL::H,
+        };
+        let mask = fields[3].parse::<usize>().unwrap();
+
+        let size = version * 4 + 17;
+        let qrcode = QRBuilder::new(input)
+            .mode(Mode::Byte)
+            .version(Version::from_n(size))
+            .ecl(ecl)
+            .mask_evaluation(MaskEvaluation::Iso)
+            .build()
+            .unwrap();
+        assert_eq!(qrcode.mask.unwrap() as usize, mask, "{input}");
+
+        for y in 0..size {
+            let row = lines.next().unwrap();
+            for x in 0..size {
+                let nibble = row.as_bytes()[x / 4] as char;
+                let dark = nibble.to_digit(16).unwrap() & (8 >> (x % 4)) != 0;
+                assert_eq!(qrcode[y][x].value(), dark, "{input} ({x}, {y})");
+            }
+        }
+
+        let report = qrcode.mask_report(MaskEvaluation::Iso).unwrap();
+        assert_eq!(report.winner as usize, mask, "{input}");
+    }
+}
+
+#[test]
+fn mask_evaluation_iso_penalties() {
+    let qrcode = QRBuilder::new("https://example.com/")
+        .mask_evaluation(MaskEvaluation::Iso)
+        .build()
+        .unwrap();
+    let report = qrcode.mask_report(MaskEvaluation::Iso).unwrap();
+
+    for penalty in report.penalties {
+        // The 3 finder patterns match 1:1:3:1:1 on 3 rows and 3 columns each, with the
+        // separator on one side
+        assert!(penalty.n3 >= 18 * 40, "{penalty:?}");
+        assert_eq!(penalty.n2 % 3, 0);
+        assert_eq!(penalty.n4 % 10, 0);
+        assert!(penalty.n4 <= 90);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::qr::QRCodeError;
+use crate::{Mode, QRBuilder, Segment, Version, ECL};
+
+#[test]
+fn segments_match_optimized_input() {
+    let input = "ORDER 000123456789 for user@example.com";
+    let optimized = QRBuilder::new(input).ecl(ECL::M).build().unwrap();
+
+    let explicit = QRBuilder::segments(vec![
+        Segment::alphanumeric("ORDER "),
+        Segment::numeric("000123456789"),
+        Segment::bytes(" for user@example.com"),
+    ])
+    .ecl(ECL::M)
+    .build()
+    .unwrap();
+
+    assert_eq!(optimized.size, explicit.size);
+    assert_eq!(optimized.data[..], explicit.data[..]);
+}
+
+#[test]
+fn segments_are_encoded_as_is() {
+    // Optimizing would pick alphanumeric, explicit segments force byte mode
+    let qr = QRBuilder::segments(vec![Segment::bytes("HELLO WORLD")])
+        .build()
+        .unwrap();
+
+    assert_eq!(qr.mode, Some(Mode::Byte));
+}
+
+#[test]
+fn segments_mixed_mode_is_none() {
+    let qr = QRBuilder::segments(vec![Segment::numeric("123"), Segment::bytes("abc")])
+        .build()
+        .unwrap();
+
+    assert_eq!(qr.mode, None);
+}
+
+#[test]
+fn segments_eci_keeps_mode() {
+    let qr = QRBuilder::segments(vec![Segment::eci(26), Segment::bytes("é")])
+        .build()
+        .unwrap();
+
+    assert_eq!(qr.mode, Some(Mode::Byte));
+}
+
+#[test]
+fn segments_invalid_numeric() {
+    let qr = QRBuilder::segments(vec![Segment::numeric("12a4")]).build();
+
+    assert!(matches!(
+        qr,
+        Err(QRCodeError::InvalidCharacter {
+            index: 2,
+            byte: b'a'
+        })
+    ));
+}
+
+#[test]
+fn segments_invalid_alphanumeric_offset() {
+    // Index counts the data of the previous segments
+    let qr = QRBuilder::segments(vec![
+        Segment::bytes("abc"),
+        Segment::eci(26),
+        Segment::alphanumeric("HELLo"),
+    ])
+    .build();
+
+    assert!(matches!(
+        qr,
+        Err(QRCodeError::InvalidCharacter {
+            index: 7,
+            byte: b'o'
+        })
+    ));
+}
+
+#[test]
+fn segments_invalid_kanji_trailing_byte() {
+    let qr = QRBuilder::segments(vec![Segment::kanji([0x93, 0x5F, 0xE4, 0x7F])]).build();
+
+    assert!(matches!(
+        qr,
+        Err(QRCodeError::InvalidCharacter {
+            index: 3,
+            byte: 0x7F
+        })
+    ));
+}
+
+#[test]
+fn segments_invalid_kanji_odd_length() {
+    let qr = QRBuilder::segments(vec![Segment::kanji([0x93, 0x5F, 0xE4])]).build();
+
+    assert!(matches!(
+        qr,
+        Err(QRCodeError::InvalidCharacter {
+            index: 2,
+            byte: 0xE4
+        })
+    ));
+}
+
+#[test]
+fn segments_invalid_eci() {
+    let qr = QRBuilder::segments(vec![Segment::eci(1_000_000)]).build();
+    assert!(matches!(qr, Err(QRCodeError::InvalidEci(1_000_000))));
+}
+
+#[test]
+fn segments_specified_version() {
+    let qr = QRBuilder::segments(vec![Segment::bytes([0; 20])])
+        .ecl(ECL::H)
+        .version(Version::V01)
+        .build();
+
+    assert!(matches!(qr, Err(QRCodeError::SpecifiedVersion)));
+}
+
+#[test]
+fn segments_too_large() {
+    let qr = QRBuilder::segments(vec![Segment::bytes([0; 3000])]).build();
+    assert!(matches!(qr, Err(QRCodeError::EncodedData)));
+}
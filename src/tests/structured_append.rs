@@ -0,0 +1,145 @@
+use crate::compact::CompactQR;
+use crate::encode::encode_structured_append;
+use crate::qr::QRCodeError;
+use crate::structured_append::split;
+use crate::{QRBuilder, Segment, Version, ECL};
+
+/// Bytes that no mode but byte mode can encode
+fn noise(len: usize) -> Vec<u8> {
+    let mut state: u32 = 0x2545_F491;
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (state >> 16) as u8 | 0x80
+        })
+        .collect()
+}
+
+#[test]
+fn structured_append_header() {
+    let mut compact = CompactQR::with_len(20);
+    encode_structured_append(&mut compact, 2, 5, 0xA5);
+
+    let bits = (0..compact.len())
+        .map(|i| compact.get_data()[i / 8] >> (7 - i % 8) & 1)
+        .map(|b| if b == 1 { '1' } else { '0' })
+        .collect::<String>();
+
+    assert_eq!(bits, "0011_0010_0100_10100101".replace('_', ""));
+}
+
+#[test]
+fn structured_append_parity() {
+    assert_eq!(Segment::parity(b""), 0);
+    assert_eq!(Segment::parity(b"A"), b'A');
+    assert_eq!(Segment::parity(b"ABC"), b'A' ^ b'B' ^ b'C');
+}
+
+#[test]
+fn structured_append_splits_large_payload() {
+    let input = noise(4000);
+    let qrcodes = QRBuilder::new(input.clone())
+        .ecl(ECL::Q)
+        .build_structured_append()
+        .unwrap();
+
+    let chunks = split(&input, ECL::Q, Version::V40, &[]).unwrap();
+    assert_eq!(qrcodes.len(), 3);
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks.concat(), input);
+
+    let parity = Segment::parity(&input);
+    for (i, (qr, chunk)) in qrcodes.iter().zip(&chunks).enumerate() {
+        let expected = QRBuilder::segments(vec![
+            Segment::structured_append(i as u8, 3, parity),
+            Segment::bytes(*chunk),
+        ])
+        .ecl(ECL::Q)
+        .build()
+        .unwrap();
+
+        assert_eq!(qr.data[..], expected.data[..]);
+        assert_eq!(qr.size, expected.size);
+    }
+}
+
+#[test]
+fn structured_append_similar_versions() {
+    let input = noise(4000);
+    let qrcodes = QRBuilder::new(input)
+        .ecl(ECL::Q)
+        .build_structured_append()
+        .unwrap();
+
+    let sizes = qrcodes.iter().map(|qr| qr.size).collect::<Vec<_>>();
+    let min = sizes.iter().min().unwrap();
+    let max = sizes.iter().max().unwrap();
+    assert!(max - min <= 4, "{:?}", sizes);
+    assert!(*max < Version::V40 as usize * 4 + 21);
+}
+
+#[test]
+fn structured_append_single_symbol() {
+    let qrcodes = QRBuilder::new("https://example.com/")
+        .build_structured_append()
+        .unwrap();
+    let qr = QRBuilder::new("https://example.com/").build().unwrap();
+
+    assert_eq!(qrcodes.len(), 1);
+    assert_eq!(qrcodes[0].data[..], qr.data[..]);
+}
+
+#[test]
+fn structured_append_forced_version() {
+    let qrcodes = QRBuilder::new(noise(300))
+        .ecl(ECL::M)
+        .version(Version::V05)
+        .build_structured_append()
+        .unwrap();
+
+    assert_eq!(qrcodes.len(), 4);
+    for qr in &qrcodes {
+        assert!(matches!(qr.version, Some(Version::V05)));
+    }
+}
+
+#[test]
+fn structured_append_keeps_utf8_characters() {
+    let input = "é".repeat(3000);
+    let chunks = split(input.as_bytes(), ECL::H, Version::V40, &[]).unwrap();
+
+    assert!(chunks.len() > 1);
+    for chunk in chunks {
+        assert!(core::str::from_utf8(chunk).is_ok());
+    }
+}
+
+#[test]
+fn structured_append_too_large() {
+    let qrcodes = QRBuilder::new(noise(16 * 3000)).build_structured_append();
+
+    assert!(matches!(qrcodes, Err(QRCodeError::EncodedData)));
+}
+
+#[test]
+fn structured_append_invalid_header() {
+    let too_many = QRBuilder::segments(vec![
+        Segment::structured_append(0, 17, 0),
+        Segment::bytes("data"),
+    ])
+    .build();
+    let out_of_range = QRBuilder::segments(vec![
+        Segment::structured_append(3, 3, 0),
+        Segment::bytes("data"),
+    ])
+    .build();
+
+    assert!(matches!(
+        too_many,
+        Err(QRCodeError::InvalidStructuredAppend)
+    ));
+    assert!(matches!(
+        out_of_range,
+        Err(QRCodeError::InvalidStructuredAppend)
+    ));
+}
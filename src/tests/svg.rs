@@ -49,3 +49,231 @@ fn check_svg_is_not_inverted() {
         }
     }
 }
+
+#[cfg(feature = "svg")]
+#[test]
+fn svg_occlusion_report() {
+    use crate::convert::svg::{SvgBuilder, SvgError};
+    use crate::convert::Builder;
+    use crate::{MicroQRBuilder, QRBuilder, Version, ECL};
+
+    let qr = |version, ecl| {
+        QRBuilder::new("FAST QR")
+            .version(version)
+            .ecl(ecl)
+            .build()
+            .unwrap()
+    };
+    let mut builder = SvgBuilder::default();
+
+    // Without image, nothing is covered
+    let report = builder.verify_occlusion(&qr(Version::V02, ECL::L)).unwrap();
+    assert_eq!(report.modules, 0);
+    assert!(report.is_recoverable());
+
+    builder.image(String::from("logo.png"));
+
+    let report = builder.verify_occlusion(&qr(Version::V05, ECL::Q)).unwrap();
+    assert_eq!(report.modules, 169);
+    assert_eq!(report.blocks.len(), 4);
+    assert!(report.is_recoverable());
+
+    // V02 is too small for the default image, whatever the ECL
+    let report = builder.verify_occlusion(&qr(Version::V02, ECL::H)).unwrap();
+    assert_eq!(report.blocks.len(), 1);
+    assert_eq!(report.blocks[0].capacity, 14);
+    assert_eq!(report.blocks[0].data + report.blocks[0].ecc, 16);
+    assert!(!report.is_recoverable());
+
+    assert!(matches!(
+        builder.try_to_str(&qr(Version::V02, ECL::H)),
+        Err(SvgError::ImageOcclusion(_))
+    ));
+    assert!(builder.try_to_str(&qr(Version::V05, ECL::H)).is_ok());
+
+    let raised = builder
+        .raise_ecl(QRBuilder::new("https://example.com/").ecl(ECL::L))
+        .unwrap();
+    assert!(builder.verify_occlusion(&raised).unwrap().is_recoverable());
+    assert!(matches!(raised.ecl, Some(ECL::Q | ECL::H)));
+
+    assert!(matches!(
+        builder.raise_ecl(QRBuilder::new("FAST QR").version(Version::V02)),
+        Err(SvgError::ImageOcclusion(report)) if matches!(report.ecl, ECL::H)
+    ));
+    assert!(matches!(
+        builder.raise_ecl(&QRBuilder::new("A".repeat(5000).as_str())),
+        Err(SvgError::QRCodeError(_))
+    ));
+
+    let micro = MicroQRBuilder::new("123").build().unwrap();
+    assert!(builder.verify_occlusion(&micro).is_none());
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn svg_occlusion_matches_decoder() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, ImageBackgroundShape};
+    use crate::decode;
+    use crate::{QRBuilder, Version, ECL};
+
+    let mut builder = SvgBuilder::default();
+    builder.image(String::from("logo.png"));
+
+    for (shape, shaped_erase) in [
+        (ImageBackgroundShape::Square, false),
+        (ImageBackgroundShape::Circle, false),
+        (ImageBackgroundShape::Circle, true),
+    ] {
+        builder
+            .image_background_shape(shape)
+            .shaped_erase(shaped_erase);
+        let circle = shaped_erase && matches!(shape, ImageBackgroundShape::Circle);
+
+        for version in 1..=40 {
+            for ecl in [ECL::L, ECL::M, ECL::Q, ECL::H] {
+                let qr = QRBuilder::new("FAST QR")
+                    .version(Version::from_n(version * 4 + 17))
+                    .ecl(ecl)
+                    .build()
+                    .unwrap();
+                let report = builder.verify_occlusion(&qr).unwrap();
+
+                // Reads the background of the image back from the svg, then inverts every
+                // module it covers, so each covered codeword is wrong
+                let svg = builder.to_str(&qr);
+                let rect = &svg[svg.find("<rect x=").unwrap()..];
+                let attribute = |name: &str| {
+                    let start = rect.find(&format!(r#"{name}=""#)).unwrap() + name.len() + 2;
+                    let end = start + rect[start..].find('"').unwrap();
+                    rect[start..end].parse::<f64>().unwrap()
+                };
+                let (left, top, size) = (
+                    attribute("x") - 4f64,
+                    attribute("y") - 4f64,
+                    attribute("width"),
+                );
+
+                // The point of a module closest to the center of the circle lies within it
+                let radius = size / 2f64;
+                let (cx, cy) = (left + radius, top + radius);
+                let in_circle = |x: f64, y: f64| {
+                    let (px, py) = (cx.max(x).min(x + 1f64), cy.max(y).min(y + 1f64));
+                    (px - cx) * (px - cx) + (py - cy) * (py - cy) < radius * radius
+                };
+
+                let n = qr.size;
+                let modules = (0..n * n)
+                    .map(|i| {
+                        let (x, y) = ((i % n) as f64, (i / n) as f64);
+                        let covered = x + 1f64 > left
+                            && x < left + size
+                            && y + 1f64 > top
+                            && y < top + size
+                            && (!circle || in_circle(x, y));
+                        qr.data[i].value() != covered
+                    })
+                    .collect::<Vec<_>>();
+
+                if report.is_recoverable() {
+                    let decoded = decode::from_modules(&modules, n).unwrap();
+                    assert_eq!(decoded.data, b"FAST QR", "V{version} {ecl}");
+                }
+
+                // Without misdecode protection codewords, the decoder fails exactly when the
+                // report does
+                if version > 3 && !report.is_recoverable() {
+                    let decoded = decode::from_modules(&modules, n);
+                    assert!(
+                        decoded.map_or(true, |decoded| decoded.data != b"FAST QR"),
+                        "V{version} {ecl}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn svg_swiss_cross() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::Builder;
+    use crate::payload::{SwissAddress, SwissQrBill};
+
+    let creditor = SwissAddress {
+        name: String::from("Robert Schneider AG"),
+        postal_code: String::from("2501"),
+        town: String::from("Biel"),
+        country: String::from("CH"),
+        ..SwissAddress::default()
+    };
+    let qr = SwissQrBill::new("CH4431999123000889012", creditor)
+        .amount("1949.75")
+        .qr_reference("210000000003139471430009017")
+        .qr_builder()
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let mut builder = SvgBuilder::default();
+    builder.margin(0).swiss_cross();
+    let svg = builder.to_str(&qr);
+    assert!(!svg.contains("<image"));
+
+    // The cross is 7 mm wide on a 46 mm QR Code, centered
+    let rect = &svg[svg.find("<rect x=").unwrap()..];
+    let attribute = |name: &str| {
+        let start = rect.find(&format!(r#"{name}=""#)).unwrap() + name.len() + 2;
+        let end = start + rect[start..].find('"').unwrap();
+        rect[start..end].parse::<f64>().unwrap()
+    };
+    let n = qr.size as f64;
+    assert!((attribute("width") * 46f64 / n - 7f64).abs() < 0.01);
+    assert!((attribute("x") * 2f64 + attribute("width") - n).abs() < 0.01);
+
+    // Black square then white cross
+    assert_eq!(svg.matches(r##"fill="#000000""##).count(), 2);
+    assert!(builder.verify_occlusion(&qr).unwrap().is_recoverable());
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn svg_occlusion_background_shape() {
+    use crate::convert::svg::SvgBuilder;
+    use crate::convert::{Builder, ImageBackgroundShape};
+    use crate::{QRBuilder, Version, ECL};
+
+    let qr = QRBuilder::new("FAST QR")
+        .version(Version::V05)
+        .ecl(ECL::Q)
+        .build()
+        .unwrap();
+    let mut builder = SvgBuilder::default();
+    builder.image(String::from("logo.png"));
+
+    let modules = |builder: &SvgBuilder| builder.verify_occlusion(&qr).unwrap().modules;
+    let erase = |builder: &SvgBuilder| {
+        let svg = builder.to_str(&qr);
+        let rect = &svg[svg.find("<rect x=").unwrap()..];
+        String::from(&rect[..rect.find("/>").unwrap()])
+    };
+
+    // By default, the modules under the background are erased in a square
+    let square = modules(&builder);
+    let rounded = modules(builder.image_background_shape(ImageBackgroundShape::RoundedSquare));
+    let circle = modules(builder.image_background_shape(ImageBackgroundShape::Circle));
+    assert_eq!((square, rounded, circle), (169, 169, 169));
+    assert!(!erase(&builder).contains("rx="));
+
+    builder.shaped_erase(true);
+    let circle = modules(&builder);
+    let rounded = modules(builder.image_background_shape(ImageBackgroundShape::RoundedSquare));
+    let square = modules(builder.image_background_shape(ImageBackgroundShape::Square));
+    assert_eq!((square, rounded, circle), (169, 169, 149));
+    assert!(!erase(&builder).contains("rx="));
+
+    builder.image_background_shape(ImageBackgroundShape::Circle);
+    assert!(erase(&builder).contains(r#"rx="1000px""#));
+}
@@ -0,0 +1,125 @@
+use crate::qr::QRCodeError;
+use crate::{QRBuilder, Segment, Version, ECL};
+
+#[test]
+fn version_range_min() {
+    let qr = QRBuilder::new("Hello")
+        .version_range(Version::V05, Version::V10)
+        .build()
+        .unwrap();
+    assert!(matches!(qr.version, Some(Version::V05)));
+    assert_eq!(qr.size, 37);
+
+    // Data needing more than `min` gets the smallest version holding it
+    let qr = QRBuilder::new("a".repeat(100))
+        .ecl(ECL::M)
+        .version_range(Version::V05, Version::V10)
+        .build()
+        .unwrap();
+    assert!(matches!(qr.version, Some(Version::V06)));
+
+    // A forced version wins over the range
+    let qr = QRBuilder::new("Hello")
+        .version_range(Version::V05, Version::V10)
+        .version(Version::V02)
+        .build()
+        .unwrap();
+    assert!(matches!(qr.version, Some(Version::V02)));
+
+    let qr = QRBuilder::segments(vec![Segment::numeric("0123456789")])
+        .version_range(Version::V03, Version::V03)
+        .build()
+        .unwrap();
+    assert!(matches!(qr.version, Some(Version::V03)));
+}
+
+#[test]
+fn version_range_max() {
+    // V01-L holds 152 bits, 17 bytes take 4 + 8 + 136 of them
+    let build = |len: usize| {
+        QRBuilder::new("a".repeat(len))
+            .ecl(ECL::L)
+            .version_range(Version::V01, Version::V01)
+            .build()
+    };
+
+    assert!(build(17).is_ok());
+    assert!(matches!(
+        build(18),
+        Err(QRCodeError::MaxVersionExceeded { excess: 1 })
+    ));
+    assert!(matches!(
+        build(20),
+        Err(QRCodeError::MaxVersionExceeded { excess: 3 })
+    ));
+
+    // Beyond V40, the excess is still counted against the max version: 4 + 16 + 24000 bits
+    // for 23648
+    let qr = QRBuilder::new(vec![0u8; 3000])
+        .ecl(ECL::L)
+        .version_range(Version::V01, Version::V40)
+        .build();
+    assert!(matches!(
+        qr,
+        Err(QRCodeError::MaxVersionExceeded { excess: 47 })
+    ));
+
+    let qr = QRBuilder::segments(vec![Segment::bytes("a".repeat(18))])
+        .ecl(ECL::L)
+        .version_range(Version::V01, Version::V01)
+        .build();
+    assert!(matches!(
+        qr,
+        Err(QRCodeError::MaxVersionExceeded { excess: 1 })
+    ));
+}
+
+#[test]
+fn version_range_with_other_options() {
+    let qr = QRBuilder::new("HELLO WORLD")
+        .version_range(Version::V03, Version::V05)
+        .maximize_ecl(true)
+        .build()
+        .unwrap();
+    assert!(matches!(qr.version, Some(Version::V03)));
+    assert!(matches!(qr.ecl, Some(ECL::H)));
+
+    let input = (0..4000).map(|i| (i * 7 % 256) as u8).collect::<Vec<_>>();
+    let qrcodes = QRBuilder::new(input)
+        .version_range(Version::V25, Version::V30)
+        .build_structured_append()
+        .unwrap();
+
+    assert!(qrcodes.len() > 1);
+    for qr in qrcodes {
+        let version = qr.version.unwrap() as usize;
+        assert!((Version::V25 as usize..=Version::V30 as usize).contains(&version));
+    }
+}
+
+#[test]
+fn version_range_inverted() {
+    let mut builder = QRBuilder::new("Hello");
+    builder.version_range(Version::V10, Version::V05);
+
+    assert!(matches!(
+        builder.build(),
+        Err(QRCodeError::InvalidVersionRange)
+    ));
+    assert!(matches!(
+        builder.build_into(&mut [0; 4071]),
+        Err(QRCodeError::InvalidVersionRange)
+    ));
+
+    // Even when the version is forced, or with the ECL maximized
+    builder.version(Version::V07);
+    assert!(matches!(
+        builder.build(),
+        Err(QRCodeError::InvalidVersionRange)
+    ));
+    let qr = QRBuilder::new("Hello")
+        .version_range(Version::V10, Version::V05)
+        .maximize_ecl(true)
+        .build();
+    assert!(matches!(qr, Err(QRCodeError::InvalidVersionRange)));
+}
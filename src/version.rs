@@ -90,6 +90,8 @@ pub enum Version {
 
 impl Version {
     /// Computes the best `Version` according to `mode`, `ecl` and `len`
+    ///
+    /// `len` is a character count, so for `Mode::Kanji` it is half the input's byte length
     #[must_use]
     #[allow(clippy::too_many_lines)]
     pub(crate) const fn get(mode: Mode, ecl: ECL, len: usize) -> Option<Self> {
@@ -622,6 +624,180 @@ impl Version {
                     _ => None,
                 },
             },
+            Mode::Kanji => match ecl {
+                ECL::L => match len {
+                    0..=10 => Some(V01),
+                    11..=20 => Some(V02),
+                    21..=32 => Some(V03),
+                    33..=48 => Some(V04),
+                    49..=65 => Some(V05),
+                    66..=82 => Some(V06),
+                    83..=95 => Some(V07),
+                    96..=118 => Some(V08),
+                    119..=141 => Some(V09),
+                    142..=167 => Some(V10),
+                    168..=198 => Some(V11),
+                    199..=226 => Some(V12),
+                    227..=262 => Some(V13),
+                    263..=282 => Some(V14),
+                    283..=320 => Some(V15),
+                    321..=361 => Some(V16),
+                    362..=397 => Some(V17),
+                    398..=442 => Some(V18),
+                    443..=488 => Some(V19),
+                    489..=528 => Some(V20),
+                    529..=572 => Some(V21),
+                    573..=618 => Some(V22),
+                    619..=672 => Some(V23),
+                    673..=721 => Some(V24),
+                    722..=784 => Some(V25),
+                    785..=842 => Some(V26),
+                    843..=902 => Some(V27),
+                    903..=940 => Some(V28),
+                    941..=1002 => Some(V29),
+                    1003..=1066 => Some(V30),
+                    1067..=1132 => Some(V31),
+                    1133..=1201 => Some(V32),
+                    1202..=1273 => Some(V33),
+                    1274..=1347 => Some(V34),
+                    1348..=1417 => Some(V35),
+                    1418..=1496 => Some(V36),
+                    1497..=1577 => Some(V37),
+                    1578..=1661 => Some(V38),
+                    1662..=1729 => Some(V39),
+                    1730..=1817 => Some(V40),
+                    _ => None,
+                },
+                ECL::M => match len {
+                    0..=8 => Some(V01),
+                    9..=16 => Some(V02),
+                    17..=26 => Some(V03),
+                    27..=38 => Some(V04),
+                    39..=52 => Some(V05),
+                    53..=65 => Some(V06),
+                    66..=75 => Some(V07),
+                    76..=93 => Some(V08),
+                    94..=111 => Some(V09),
+                    112..=131 => Some(V10),
+                    132..=155 => Some(V11),
+                    156..=177 => Some(V12),
+                    178..=204 => Some(V13),
+                    205..=223 => Some(V14),
+                    224..=254 => Some(V15),
+                    255..=277 => Some(V16),
+                    278..=310 => Some(V17),
+                    311..=345 => Some(V18),
+                    346..=384 => Some(V19),
+                    385..=410 => Some(V20),
+                    411..=438 => Some(V21),
+                    439..=480 => Some(V22),
+                    481..=528 => Some(V23),
+                    529..=561 => Some(V24),
+                    562..=614 => Some(V25),
+                    615..=652 => Some(V26),
+                    653..=692 => Some(V27),
+                    693..=732 => Some(V28),
+                    733..=778 => Some(V29),
+                    779..=843 => Some(V30),
+                    844..=894 => Some(V31),
+                    895..=947 => Some(V32),
+                    948..=1002 => Some(V33),
+                    1003..=1060 => Some(V34),
+                    1061..=1113 => Some(V35),
+                    1114..=1176 => Some(V36),
+                    1177..=1224 => Some(V37),
+                    1225..=1292 => Some(V38),
+                    1293..=1362 => Some(V39),
+                    1363..=1435 => Some(V40),
+                    _ => None,
+                },
+                ECL::Q => match len {
+                    0..=7 => Some(V01),
+                    8..=12 => Some(V02),
+                    13..=20 => Some(V03),
+                    21..=28 => Some(V04),
+                    29..=37 => Some(V05),
+                    38..=45 => Some(V06),
+                    46..=53 => Some(V07),
+                    54..=66 => Some(V08),
+                    67..=80 => Some(V09),
+                    81..=93 => Some(V10),
+                    94..=109 => Some(V11),
+                    110..=125 => Some(V12),
+                    126..=149 => Some(V13),
+                    150..=159 => Some(V14),
+                    160..=180 => Some(V15),
+                    181..=198 => Some(V16),
+                    199..=224 => Some(V17),
+                    225..=243 => Some(V18),
+                    244..=272 => Some(V19),
+                    273..=297 => Some(V20),
+                    298..=314 => Some(V21),
+                    315..=348 => Some(V22),
+                    349..=376 => Some(V23),
+                    377..=407 => Some(V24),
+                    408..=440 => Some(V25),
+                    441..=462 => Some(V26),
+                    463..=496 => Some(V27),
+                    497..=534 => Some(V28),
+                    535..=559 => Some(V29),
+                    560..=604 => Some(V30),
+                    605..=634 => Some(V31),
+                    635..=684 => Some(V32),
+                    685..=719 => Some(V33),
+                    720..=756 => Some(V34),
+                    757..=790 => Some(V35),
+                    791..=832 => Some(V36),
+                    833..=876 => Some(V37),
+                    877..=923 => Some(V38),
+                    924..=972 => Some(V39),
+                    973..=1024 => Some(V40),
+                    _ => None,
+                },
+                ECL::H => match len {
+                    0..=4 => Some(V01),
+                    5..=8 => Some(V02),
+                    9..=15 => Some(V03),
+                    16..=21 => Some(V04),
+                    22..=27 => Some(V05),
+                    28..=36 => Some(V06),
+                    37..=39 => Some(V07),
+                    40..=52 => Some(V08),
+                    53..=60 => Some(V09),
+                    61..=74 => Some(V10),
+                    75..=85 => Some(V11),
+                    86..=96 => Some(V12),
+                    97..=109 => Some(V13),
+                    110..=120 => Some(V14),
+                    121..=136 => Some(V15),
+                    137..=154 => Some(V16),
+                    155..=173 => Some(V17),
+                    174..=191 => Some(V18),
+                    192..=208 => Some(V19),
+                    209..=235 => Some(V20),
+                    236..=248 => Some(V21),
+                    249..=270 => Some(V22),
+                    271..=284 => Some(V23),
+                    285..=315 => Some(V24),
+                    316..=330 => Some(V25),
+                    331..=365 => Some(V26),
+                    366..=385 => Some(V27),
+                    386..=405 => Some(V28),
+                    406..=430 => Some(V29),
+                    431..=457 => Some(V30),
+                    458..=486 => Some(V31),
+                    487..=518 => Some(V32),
+                    519..=553 => Some(V33),
+                    554..=590 => Some(V34),
+                    591..=605 => Some(V35),
+                    606..=647 => Some(V36),
+                    648..=673 => Some(V37),
+                    674..=701 => Some(V38),
+                    702..=750 => Some(V39),
+                    751..=784 => Some(V40),
+                    _ => None,
+                },
+            },
         }
     }
 
@@ -630,7 +806,6 @@ impl Version {
     /// # Panics
     /// Function panics if `n` is not included in `(21..=177).step_by(4)`
     #[must_use]
-    #[cfg(any(test, feature = "svg", feature = "image", debug_assertions))]
     pub(crate) const fn from_n(n: usize) -> Self {
         use Version::{
             V01, V02, V03, V04, V05, V06, V07, V08, V09, V10, V11, V12, V13, V14, V15, V16, V17,
@@ -810,9 +985,9 @@ impl Version {
         ALIGNMENT_PATTERNS_GRID[self as usize]
     }
 
-    /// Returns the size of a `QRCode` for said version.
+    /// Returns the size of a `QRCode` for said version, in modules per side
     #[must_use]
-    pub(crate) const fn size(self) -> usize {
+    pub const fn size(self) -> usize {
         self as usize * 4 + 21
     }
 }
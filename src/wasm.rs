@@ -5,8 +5,7 @@ use crate::QRCode;
 use wasm_bindgen::prelude::*;
 
 fn bool_to_u8(qr: QRCode) -> Vec<u8> {
-    let dim = qr.size;
-    qr.data[..dim * dim]
+    qr.data[..qr.size * qr.height]
         .iter()
         .map(|x| u8::from(x.value()))
         .collect()
@@ -17,7 +16,7 @@ fn bool_to_u8(qr: QRCode) -> Vec<u8> {
 #[must_use]
 pub fn qr(content: &str) -> Vec<u8> {
     let qrcode = QRCode::new(content.as_bytes(), None, None, None);
-    qrcode.map(bool_to_u8).unwrap_or(Vec::new())
+    qrcode.map(bool_to_u8).unwrap_or_default()
 }
 
 /// Configuration for the SVG output.
@@ -164,6 +163,13 @@ impl SvgOptions {
     }
 }
 
+#[cfg(feature = "svg")]
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Generate a QR code from a string. All parameters are automatically set.
 #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen)]
 #[cfg(feature = "svg")]
@@ -198,5 +204,5 @@ pub fn qr_svg(content: &str, options: SvgOptions) -> String {
 
     qrcode
         .map(|qrcode| builder.to_str(&qrcode))
-        .unwrap_or(String::new())
+        .unwrap_or_default()
 }
@@ -8,7 +8,9 @@ echo "Building with cargo mode: ${CARGO_MODE}"
 
 OUTPUT_DIR="pkg"
 
-cargo +nightly build ${CARGO_MODE} \
+cargo +nightly rustc ${CARGO_MODE} \
+    --lib \
+    --crate-type cdylib \
     --target wasm32-unknown-unknown \
     -Z "build-std=std,panic_abort" \
     -Z "build-std-features=${BUILD_STD_FEATURES}" \